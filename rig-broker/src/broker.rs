@@ -0,0 +1,409 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_nats::jetstream;
+use async_nats::Client;
+use futures::StreamExt;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use crate::error::BrokerError;
+use crate::message::Message;
+
+/// Key identifying a single subscription: a topic plus the queue group
+/// (NATS queue subscription) it was made under.
+type SubKey = (String, String);
+
+/// State backing [`Broker::with_dedup_window`]: which content hashes have
+/// been published recently, and how many repeats have been suppressed.
+struct DedupState {
+    window: Duration,
+    seen: HashMap<u64, Instant>,
+    suppressed: u64,
+}
+
+/// Which stream/subjects a JetStream-backed [`Broker`] persists publishes
+/// to, passed to [`Broker::new_jetstream`].
+#[derive(Debug, Clone)]
+pub struct StreamConfig {
+    pub name: String,
+    pub subjects: Vec<String>,
+}
+
+impl StreamConfig {
+    pub fn new(name: impl Into<String>, subjects: Vec<String>) -> Self {
+        Self { name: name.into(), subjects }
+    }
+}
+
+/// Thin wrapper around a NATS client that speaks [`Message`] envelopes.
+///
+/// Every `subscribe` spawns a background task that forwards incoming
+/// messages to the caller-supplied handler; the handle is kept around in
+/// `subscriptions` so callers can manage it later (see `unsubscribe`).
+#[derive(Clone)]
+pub struct Broker {
+    client: Client,
+    subscriptions: Arc<Mutex<HashMap<SubKey, JoinHandle<()>>>>,
+    dedup: Option<Arc<Mutex<DedupState>>>,
+    /// Set by [`Self::new_jetstream`]; when present, [`Self::publish`]
+    /// persists through this context instead of the bare core-NATS path,
+    /// and [`Self::consume_durable`] can pull from `stream`.
+    jetstream: Option<jetstream::Context>,
+    stream: Option<jetstream::stream::Stream>,
+}
+
+impl Broker {
+    pub async fn connect(url: &str) -> Result<Self, BrokerError> {
+        let client = async_nats::connect(url).await?;
+        Ok(Self {
+            client,
+            subscriptions: Arc::new(Mutex::new(HashMap::new())),
+            dedup: None,
+            jetstream: None,
+            stream: None,
+        })
+    }
+
+    /// Like [`Self::connect`], but publishes persist to a JetStream stream
+    /// (created if it doesn't already exist) instead of core NATS, so
+    /// they aren't lost when no subscriber is currently live.
+    pub async fn new_jetstream(url: &str, stream_config: StreamConfig) -> Result<Self, BrokerError> {
+        let client = async_nats::connect(url).await?;
+        let context = jetstream::new(client.clone());
+        let stream = context
+            .get_or_create_stream(jetstream::stream::Config {
+                name: stream_config.name,
+                subjects: stream_config.subjects,
+                ..Default::default()
+            })
+            .await
+            .map_err(|err| BrokerError::JetStream(err.to_string()))?;
+
+        Ok(Self {
+            client,
+            subscriptions: Arc::new(Mutex::new(HashMap::new())),
+            dedup: None,
+            jetstream: Some(context),
+            stream: Some(stream),
+        })
+    }
+
+    /// Create (or reuse) a durable pull consumer named `consumer` on this
+    /// broker's JetStream stream, and invoke `handler` for every message
+    /// it delivers — including any published before `consumer` first
+    /// existed, giving at-least-once delivery with replay. Requires
+    /// [`Self::new_jetstream`].
+    pub async fn consume_durable<F>(&self, consumer: &str, handler: F) -> Result<(), BrokerError>
+    where
+        F: Fn(Message) + Send + Sync + 'static,
+    {
+        let stream = self.stream.as_ref().ok_or_else(|| BrokerError::JetStream("broker has no jetstream stream configured".to_string()))?;
+
+        let pull_consumer = stream
+            .get_or_create_consumer(
+                consumer,
+                jetstream::consumer::pull::Config { durable_name: Some(consumer.to_string()), ..Default::default() },
+            )
+            .await
+            .map_err(|err| BrokerError::JetStream(err.to_string()))?;
+
+        let mut messages = pull_consumer.messages().await.map_err(|err| BrokerError::JetStream(err.to_string()))?;
+
+        tokio::spawn(async move {
+            while let Some(Ok(message)) = messages.next().await {
+                match serde_json::from_slice::<Message>(&message.payload) {
+                    Ok(decoded) => handler(decoded),
+                    Err(err) => warn!(%err, "failed to decode jetstream message"),
+                }
+                if let Err(err) = message.ack().await {
+                    warn!(%err, "failed to ack jetstream message");
+                }
+            }
+            debug!("durable consumer stream ended");
+        });
+
+        Ok(())
+    }
+
+    /// Suppress republishing an exact-duplicate payload (by content hash)
+    /// within `window` of its last publish, common with retries. Use
+    /// [`Self::suppressed_duplicates`] to see how many were dropped.
+    pub fn with_dedup_window(mut self, window: Duration) -> Self {
+        self.dedup = Some(Arc::new(Mutex::new(DedupState { window, seen: HashMap::new(), suppressed: 0 })));
+        self
+    }
+
+    /// How many publishes [`Self::with_dedup_window`] has suppressed as
+    /// duplicates so far. `0` if no dedup window is configured.
+    pub async fn suppressed_duplicates(&self) -> u64 {
+        match &self.dedup {
+            Some(dedup) => dedup.lock().await.suppressed,
+            None => 0,
+        }
+    }
+
+    /// Fire-and-forget publish. The payload is serialized as JSON. If a
+    /// dedup window is configured and `payload` exactly matches one
+    /// published within it, this is a silent no-op.
+    pub async fn publish(&self, topic: &str, payload: Message) -> Result<(), BrokerError> {
+        let bytes = serde_json::to_vec(&payload)?;
+
+        if let Some(dedup) = &self.dedup {
+            if Self::is_duplicate(dedup, &bytes).await {
+                debug!(%topic, "suppressed duplicate publish within dedup window");
+                return Ok(());
+            }
+        }
+
+        match &self.jetstream {
+            Some(context) => {
+                context
+                    .publish(topic.to_string(), bytes.into())
+                    .await
+                    .map_err(|err| BrokerError::JetStream(err.to_string()))?
+                    .await
+                    .map_err(|err| BrokerError::JetStream(err.to_string()))?;
+            }
+            None => {
+                self.client.publish(topic.to_string(), bytes.into()).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn is_duplicate(dedup: &Arc<Mutex<DedupState>>, bytes: &[u8]) -> bool {
+        let hash = content_hash(bytes);
+        let mut state = dedup.lock().await;
+        let now = Instant::now();
+        let window = state.window;
+        state.seen.retain(|_, seen_at| now.duration_since(*seen_at) < window);
+
+        if state.seen.contains_key(&hash) {
+            state.suppressed += 1;
+            return true;
+        }
+        state.seen.insert(hash, now);
+        false
+    }
+
+    /// Subscribe to `topic` under queue group `queue`, invoking `handler`
+    /// for every message received until the subscription is dropped via
+    /// `unsubscribe`.
+    pub async fn subscribe<F>(&self, topic: &str, queue: &str, handler: F) -> Result<(), BrokerError>
+    where
+        F: Fn(Message) + Send + Sync + 'static,
+    {
+        let mut sub = self
+            .client
+            .queue_subscribe(topic.to_string(), queue.to_string())
+            .await?;
+
+        let task = tokio::spawn(async move {
+            while let Some(msg) = sub.next().await {
+                match serde_json::from_slice::<Message>(&msg.payload) {
+                    Ok(decoded) => handler(decoded),
+                    Err(err) => warn!(%err, "failed to decode broker message"),
+                }
+            }
+            debug!("subscription stream ended");
+        });
+
+        self.subscriptions
+            .lock()
+            .await
+            .insert((topic.to_string(), queue.to_string()), task);
+        Ok(())
+    }
+
+    /// Send `payload` to `topic` and wait for a single reply, failing with
+    /// [`BrokerError::RequestTimeout`] if none arrives within `timeout`.
+    pub async fn request(
+        &self,
+        topic: &str,
+        payload: Message,
+        timeout: Duration,
+    ) -> Result<Message, BrokerError> {
+        let bytes = serde_json::to_vec(&payload)?;
+        let reply = tokio::time::timeout(timeout, self.client.request(topic.to_string(), bytes.into()))
+            .await
+            .map_err(|_| BrokerError::RequestTimeout {
+                topic: topic.to_string(),
+                timeout_secs: timeout.as_secs(),
+            })??;
+
+        let decoded = serde_json::from_slice::<Message>(&reply.payload)?;
+        Ok(decoded)
+    }
+
+    /// Stop the subscription for `topic`/`queue`, aborting its background
+    /// forwarding task and removing it from the tracked set.
+    pub async fn unsubscribe(&self, topic: &str, queue: &str) -> Result<(), BrokerError> {
+        let key = (topic.to_string(), queue.to_string());
+        let task = self.subscriptions.lock().await.remove(&key).ok_or_else(|| {
+            BrokerError::NotSubscribed {
+                topic: topic.to_string(),
+                queue: queue.to_string(),
+            }
+        })?;
+        task.abort();
+        Ok(())
+    }
+
+    /// List the queue groups currently subscribed to `topic`, resolving
+    /// NATS wildcards (`*` for a single token, `>` for the remainder) in
+    /// each subscription's pattern against the concrete `topic` given.
+    pub async fn get_subscribers(&self, topic: &str) -> Vec<String> {
+        self.subscriptions
+            .lock()
+            .await
+            .keys()
+            .filter(|(pattern, _)| topic_matches(pattern, topic))
+            .map(|(_, queue)| queue.clone())
+            .collect()
+    }
+}
+
+fn content_hash(bytes: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Whether a NATS subject `pattern` (which may use `*` to match exactly
+/// one `.`-delimited token, or `>` to match one-or-more trailing tokens)
+/// matches the concrete `topic`.
+fn topic_matches(pattern: &str, topic: &str) -> bool {
+    let pattern_tokens: Vec<&str> = pattern.split('.').collect();
+    let topic_tokens: Vec<&str> = topic.split('.').collect();
+
+    for (i, token) in pattern_tokens.iter().enumerate() {
+        if *token == ">" {
+            return topic_tokens.len() > i;
+        }
+        match topic_tokens.get(i) {
+            Some(t) if *token == "*" || token == t => {}
+            _ => return false,
+        }
+    }
+    pattern_tokens.len() == topic_tokens.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn a_repeated_publish_within_the_window_is_counted_as_suppressed() {
+        let dedup = Arc::new(Mutex::new(DedupState { window: Duration::from_secs(60), seen: HashMap::new(), suppressed: 0 }));
+        let bytes = b"identical payload".to_vec();
+
+        assert!(!Broker::is_duplicate(&dedup, &bytes).await);
+        assert!(Broker::is_duplicate(&dedup, &bytes).await);
+        assert_eq!(dedup.lock().await.suppressed, 1);
+    }
+
+    #[tokio::test]
+    async fn a_publish_after_the_window_elapses_is_not_suppressed() {
+        let dedup = Arc::new(Mutex::new(DedupState { window: Duration::from_millis(1), seen: HashMap::new(), suppressed: 0 }));
+        let bytes = b"identical payload".to_vec();
+
+        assert!(!Broker::is_duplicate(&dedup, &bytes).await);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!Broker::is_duplicate(&dedup, &bytes).await);
+        assert_eq!(dedup.lock().await.suppressed, 0);
+    }
+
+    /// Requires a live NATS server at `localhost:4222`, so it's excluded
+    /// from the default test run.
+    #[tokio::test]
+    #[ignore]
+    async fn publishing_the_same_message_twice_within_the_window_only_reaches_subscribers_once() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let broker = Broker::connect("localhost:4222").await.unwrap().with_dedup_window(Duration::from_secs(5));
+        let received = Arc::new(AtomicUsize::new(0));
+        let received_for_handler = received.clone();
+        broker
+            .subscribe("agents.dedup-test", "subscriber-a", move |_message| {
+                received_for_handler.fetch_add(1, Ordering::SeqCst);
+            })
+            .await
+            .unwrap();
+
+        let message = Message::new("agents.dedup-test", serde_json::json!({"hello": "world"}));
+        broker.publish("agents.dedup-test", message.clone()).await.unwrap();
+        broker.publish("agents.dedup-test", message).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        assert_eq!(received.load(Ordering::SeqCst), 1);
+        assert_eq!(broker.suppressed_duplicates().await, 1);
+    }
+
+    /// Requires a live NATS server with JetStream enabled at
+    /// `localhost:4222`, so it's excluded from the default test run.
+    #[tokio::test]
+    #[ignore]
+    async fn publishing_with_no_live_consumer_is_replayed_once_one_subscribes() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let broker = Broker::new_jetstream(
+            "localhost:4222",
+            StreamConfig::new("agents-stream", vec!["agents.jetstream-test".to_string()]),
+        )
+        .await
+        .unwrap();
+
+        // No consumer exists yet, so this would be lost on core NATS.
+        let message = Message::new("agents.jetstream-test", serde_json::json!({"hello": "world"}));
+        broker.publish("agents.jetstream-test", message).await.unwrap();
+
+        let received = Arc::new(AtomicUsize::new(0));
+        let received_for_handler = received.clone();
+        broker
+            .consume_durable("backlog-consumer", move |_message| {
+                received_for_handler.fetch_add(1, Ordering::SeqCst);
+            })
+            .await
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+
+        assert_eq!(received.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn a_greater_than_wildcard_matches_the_remainder_of_the_topic() {
+        assert!(topic_matches("agents.>", "agents.alice"));
+        assert!(topic_matches("agents.>", "agents.alice.status"));
+        assert!(!topic_matches("agents.>", "agents"));
+    }
+
+    #[test]
+    fn a_star_wildcard_matches_exactly_one_token() {
+        assert!(topic_matches("agents.*.status", "agents.alice.status"));
+        assert!(!topic_matches("agents.*.status", "agents.alice.bob.status"));
+        assert!(!topic_matches("agents.*.status", "agents.status"));
+    }
+
+    #[test]
+    fn a_concrete_pattern_only_matches_itself() {
+        assert!(topic_matches("agents.alice", "agents.alice"));
+        assert!(!topic_matches("agents.alice", "agents.bob"));
+    }
+
+    /// Requires a live NATS server at `localhost:4222`, so it's excluded
+    /// from the default test run.
+    #[tokio::test]
+    #[ignore]
+    async fn a_wildcard_subscription_is_reported_as_a_subscriber_of_a_matching_concrete_topic() {
+        let broker = Broker::connect("localhost:4222").await.unwrap();
+        broker.subscribe("agents.>", "wildcard-queue", |_message| {}).await.unwrap();
+
+        let subscribers = broker.get_subscribers("agents.alice").await;
+        assert!(subscribers.contains(&"wildcard-queue".to_string()));
+    }
+}
@@ -0,0 +1,28 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum BrokerError {
+    #[error("failed to connect to broker: {0}")]
+    Connect(#[from] async_nats::ConnectError),
+
+    #[error("failed to publish message: {0}")]
+    Publish(#[from] async_nats::PublishError),
+
+    #[error("request failed: {0}")]
+    Request(#[from] async_nats::RequestError),
+
+    #[error("failed to subscribe to topic: {0}")]
+    Subscribe(#[from] async_nats::SubscribeError),
+
+    #[error("failed to serialize message: {0}")]
+    Serialize(#[from] serde_json::Error),
+
+    #[error("request to '{topic}' timed out after {timeout_secs}s")]
+    RequestTimeout { topic: String, timeout_secs: u64 },
+
+    #[error("no subscription found for topic '{topic}' queue '{queue}'")]
+    NotSubscribed { topic: String, queue: String },
+
+    #[error("jetstream error: {0}")]
+    JetStream(String),
+}
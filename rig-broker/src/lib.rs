@@ -0,0 +1,7 @@
+mod broker;
+mod error;
+mod message;
+
+pub use broker::Broker;
+pub use error::BrokerError;
+pub use message::Message;
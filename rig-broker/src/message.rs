@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// An envelope published onto the broker. Payloads are opaque JSON so that
+/// any agent can publish without the broker needing to know the shape.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Message {
+    pub id: Uuid,
+    pub topic: String,
+    pub payload: serde_json::Value,
+    /// Correlation id for tracing this message across services (gateway,
+    /// broker, store). Auto-generated by [`Self::new`] when the caller
+    /// doesn't already have one to propagate — see [`Self::with_trace_id`]
+    /// for forwarding an id received upstream.
+    pub trace_id: String,
+}
+
+impl Message {
+    pub fn new(topic: impl Into<String>, payload: serde_json::Value) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            topic: topic.into(),
+            payload,
+            trace_id: Uuid::new_v4().to_string(),
+        }
+    }
+
+    /// Override the auto-generated trace id, e.g. to propagate the
+    /// `X-Trace-Id` the gateway attached to the originating request
+    /// instead of minting a fresh one.
+    pub fn with_trace_id(mut self, trace_id: impl Into<String>) -> Self {
+        self.trace_id = trace_id.into();
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_auto_generates_a_trace_id() {
+        let message = Message::new("agents.status", serde_json::json!({}));
+        assert!(!message.trace_id.is_empty());
+    }
+
+    #[test]
+    fn with_trace_id_overrides_the_generated_one() {
+        let message = Message::new("agents.status", serde_json::json!({})).with_trace_id("req-123");
+        assert_eq!(message.trace_id, "req-123");
+    }
+}
@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+use rig_broker::{Broker, Message};
+use serde_json::json;
+
+/// Requires a local NATS server (`nats-server`) reachable at localhost:4222.
+#[ignore]
+#[tokio::test]
+async fn request_round_trips_reply() {
+    let broker = Broker::connect("localhost:4222").await.unwrap();
+
+    let responder = broker.clone();
+    broker
+        .subscribe("rpc.echo", "responders", move |msg| {
+            let responder = responder.clone();
+            tokio::spawn(async move {
+                let reply = Message::new(msg.topic.clone(), msg.payload.clone());
+                responder.publish("_INBOX_IGNORED_", reply).await.ok();
+            });
+        })
+        .await
+        .unwrap();
+
+    let request = Message::new("rpc.echo", json!({"hello": "world"}));
+    let reply = broker
+        .request("rpc.echo", request, Duration::from_secs(2))
+        .await
+        .unwrap();
+
+    assert_eq!(reply.payload, json!({"hello": "world"}));
+}
@@ -0,0 +1,17 @@
+use rig_broker::Broker;
+
+/// Requires a local NATS server (`nats-server`) reachable at localhost:4222.
+#[ignore]
+#[tokio::test]
+async fn unsubscribe_removes_subscriber() {
+    let broker = Broker::connect("localhost:4222").await.unwrap();
+
+    broker
+        .subscribe("presence.updates", "workers", |_msg| {})
+        .await
+        .unwrap();
+    assert_eq!(broker.get_subscribers("presence.updates").await, vec!["workers"]);
+
+    broker.unsubscribe("presence.updates", "workers").await.unwrap();
+    assert!(broker.get_subscribers("presence.updates").await.is_empty());
+}
@@ -0,0 +1,493 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use futures::StreamExt;
+use rig_broker::{Broker, Message as BrokerMessage};
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, oneshot, RwLock};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use uuid::Uuid;
+
+use crate::connection::Connection;
+use crate::error::Error;
+use crate::outbox::{OutboxEntry, OutboxStore};
+use crate::pool::ConnectionPool;
+use crate::protocol::stream::{self, StreamReceiver};
+use crate::protocol::{MessageType, ProtocolMessage};
+
+/// Capacity of the [`StreamReceiver`] returned by [`Agent::subscribe`].
+const DEFAULT_TOPIC_STREAM_CAPACITY: usize = 256;
+
+/// Live state of an [`Agent`]'s connection to its server, tracked locally
+/// so callers don't have to infer it from send failures.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AgentStatus {
+    Offline,
+    Connected,
+    /// The underlying connection failed; carries a human-readable reason.
+    Error(String),
+}
+
+/// Category of outbound action an [`Agent`] can send, each requiring the
+/// matching capability string (as advertised via
+/// [`crate::ConnectionPool::register_agent`]) on the peer it's sent to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgentAction {
+    Chat,
+    VectorStore,
+}
+
+impl AgentAction {
+    fn required_capability(&self) -> &'static str {
+        match self {
+            AgentAction::Chat => "chat",
+            AgentAction::VectorStore => "vector_store",
+        }
+    }
+
+    /// Reverse of [`Self::required_capability`], for rebuilding an action
+    /// from a persisted [`OutboxEntry`].
+    fn from_capability(capability: &str) -> Option<Self> {
+        match capability {
+            "chat" => Some(AgentAction::Chat),
+            "vector_store" => Some(AgentAction::VectorStore),
+            _ => None,
+        }
+    }
+}
+
+/// A send enqueued onto the pump, along with a channel the pump uses to
+/// report back whether it was validated and dispatched.
+struct PumpMessage {
+    target: Uuid,
+    action: AgentAction,
+    payload: serde_json::Value,
+    result_tx: oneshot::Sender<Result<(), Error>>,
+}
+
+/// Client-side handle for a single agent's traffic through a
+/// [`ConnectionPool`]: caches what its peers can do (so a send lacking the
+/// needed capability fails locally instead of round-tripping to be
+/// rejected downstream) and tracks its own connection status.
+///
+/// Sends don't touch the pool directly — they're enqueued onto a pump task
+/// (spawned in [`Self::new`]) that owns the receiving end of `message_tx`,
+/// validates each message's capability, and dispatches it to the target
+/// connection.
+pub struct Agent {
+    pub id: Uuid,
+    connection_pool: ConnectionPool,
+    /// Id of this agent's own connection to its server, if currently
+    /// connected, so [`Self::disconnect`] knows what to remove from the
+    /// pool.
+    connection_id: Arc<RwLock<Option<Uuid>>>,
+    peer_capabilities: Arc<RwLock<HashMap<Uuid, Vec<String>>>>,
+    status: Arc<RwLock<AgentStatus>>,
+    message_tx: mpsc::Sender<PumpMessage>,
+    broker: Option<Broker>,
+    outbox: Option<Arc<dyn OutboxStore>>,
+    /// Extra PEM-encoded CA certificate trusted by [`Self::dial`], in
+    /// addition to the Mozilla root store, for dialing a `wss://` server
+    /// whose certificate is signed by a private CA.
+    tls_ca_cert: Option<PathBuf>,
+}
+
+impl Agent {
+    pub fn new(id: Uuid, connection_pool: ConnectionPool) -> Self {
+        let (message_tx, message_rx) = mpsc::channel(32);
+        let peer_capabilities = Arc::new(RwLock::new(HashMap::new()));
+        tokio::spawn(Self::run_pump(message_rx, connection_pool.clone(), peer_capabilities.clone()));
+        Self {
+            id,
+            connection_pool,
+            connection_id: Arc::new(RwLock::new(None)),
+            peer_capabilities,
+            status: Arc::new(RwLock::new(AgentStatus::Offline)),
+            message_tx,
+            broker: None,
+            outbox: None,
+            tls_ca_cert: None,
+        }
+    }
+
+    /// Attach a [`Broker`] so [`Self::subscribe`]/[`Self::publish`] can
+    /// bridge topic traffic through NATS pub/sub, in addition to this
+    /// agent's point-to-point connections.
+    pub fn with_broker(mut self, broker: Broker) -> Self {
+        self.broker = Some(broker);
+        self
+    }
+
+    /// Attach an [`OutboxStore`] so [`Self::send_message`] durably persists
+    /// a message before sending it and only clears it once delivery is
+    /// confirmed, and [`Self::replay_outbox`] can resend anything left
+    /// over from a crash between those two steps.
+    pub fn with_outbox(mut self, outbox: Arc<dyn OutboxStore>) -> Self {
+        self.outbox = Some(outbox);
+        self
+    }
+
+    /// Trust `ca_cert_path` (a PEM-encoded CA certificate) in addition to
+    /// the Mozilla root store when [`Self::connect`] dials a `wss://` URL —
+    /// for a server whose certificate is signed by a private CA.
+    pub fn with_tls_ca_cert(mut self, ca_cert_path: impl Into<PathBuf>) -> Self {
+        self.tls_ca_cert = Some(ca_cert_path.into());
+        self
+    }
+
+    pub async fn status(&self) -> AgentStatus {
+        self.status.read().await.clone()
+    }
+
+    /// Publish `content` to `topic` over the attached [`Broker`].
+    pub async fn publish(&self, topic: &str, content: serde_json::Value) -> Result<(), Error> {
+        let broker = self.broker_or_err()?;
+        broker.publish(topic, BrokerMessage::new(topic, content)).await?;
+        Ok(())
+    }
+
+    /// Join `topic` over the attached [`Broker`], translating each
+    /// incoming broker [`BrokerMessage`] into a `MessageType::Data`
+    /// [`ProtocolMessage`] delivered on the returned [`StreamReceiver`].
+    /// This agent's id is used as the queue group, so every agent that
+    /// subscribes gets its own copy of every publish.
+    pub async fn subscribe(&self, topic: &str) -> Result<StreamReceiver, Error> {
+        let broker = self.broker_or_err()?;
+        let (tx, rx) = stream::channel(DEFAULT_TOPIC_STREAM_CAPACITY);
+        broker
+            .subscribe(topic, &self.id.to_string(), move |message| {
+                let _ = tx.try_send(ProtocolMessage::new(MessageType::Data, message.payload));
+            })
+            .await?;
+        Ok(rx)
+    }
+
+    fn broker_or_err(&self) -> Result<&Broker, Error> {
+        self.broker.as_ref().ok_or_else(|| Error::Connection("agent has no broker configured".to_string()))
+    }
+
+    /// Dial `url`, add the resulting connection to the pool, and mark this
+    /// agent [`AgentStatus::Connected`]. On failure, mark it
+    /// [`AgentStatus::Error`] instead of leaving the previous status in
+    /// place, since a failed dial means the old connection (if any) is
+    /// gone either way.
+    pub async fn connect(&self, url: &str) -> Result<(), Error> {
+        match self.dial(url).await {
+            Ok(connection) => {
+                let id = connection.id;
+                self.connection_pool.add_connection(connection).await?;
+                *self.connection_id.write().await = Some(id);
+                *self.status.write().await = AgentStatus::Connected;
+                Ok(())
+            }
+            Err(err) => {
+                *self.status.write().await = AgentStatus::Error(err.to_string());
+                Err(err)
+            }
+        }
+    }
+
+    /// Dial `url`'s host/port over TCP, upgrading to TLS first (via
+    /// [`crate::tls::client_connector`] or, if [`Self::with_tls_ca_cert`]
+    /// was used, a connector that also trusts that CA) for a `wss://` URL,
+    /// then perform the WebSocket handshake. Dialing over a raw TCP stream
+    /// ourselves — rather than `tokio_tungstenite::connect_async`, which
+    /// has no TLS support compiled in here — is what actually makes the
+    /// server certificate get verified.
+    async fn dial(&self, url: &str) -> Result<Connection, Error> {
+        let request = url.into_client_request()?;
+        let uri = request.uri();
+        let host = uri.host().ok_or_else(|| Error::Connection(format!("{url} has no host")))?.to_string();
+        let use_tls = uri.scheme_str() == Some("wss");
+        let port = uri.port_u16().unwrap_or(if use_tls { 443 } else { 80 });
+
+        let tcp = TcpStream::connect((host.as_str(), port)).await?;
+
+        if use_tls {
+            let connector = match &self.tls_ca_cert {
+                Some(ca_cert) => tokio_rustls::TlsConnector::from(crate::tls::client_tls_config(Some(ca_cert.as_path()))?),
+                None => crate::tls::client_connector(),
+            };
+            let server_name = rustls::pki_types::ServerName::try_from(host)
+                .map_err(|_| Error::Connection(format!("{url} has an invalid TLS server name")))?
+                .to_owned();
+            let tls_stream = connector.connect(server_name, tcp).await?;
+            let (ws, _response) = tokio_tungstenite::client_async(request, tls_stream).await?;
+            let (sink, _stream) = ws.split();
+            Ok(Connection::spawn(sink))
+        } else {
+            let (ws, _response) = tokio_tungstenite::client_async(request, tcp).await?;
+            let (sink, _stream) = ws.split();
+            Ok(Connection::spawn(sink))
+        }
+    }
+
+    /// Remove this agent's connection from the pool (if any) and mark it
+    /// [`AgentStatus::Offline`].
+    pub async fn disconnect(&self) {
+        if let Some(id) = self.connection_id.write().await.take() {
+            self.connection_pool.remove_connection(id).await;
+        }
+        *self.status.write().await = AgentStatus::Offline;
+    }
+
+    /// [`Self::disconnect`], then dial `url` again via [`Self::connect`].
+    pub async fn reconnect(&self, url: &str) -> Result<(), Error> {
+        self.disconnect().await;
+        self.connect(url).await
+    }
+
+    /// Cache `peer`'s advertised capabilities, as learned from its
+    /// presence/registration on [`ConnectionPool`], so the pump can check
+    /// them without asking the peer first.
+    pub async fn observe_peer_capabilities(&self, peer: Uuid, capabilities: Vec<String>) {
+        self.peer_capabilities.write().await.insert(peer, capabilities);
+    }
+
+    /// Enqueue `action` for `target` onto the pump and wait for it to be
+    /// validated and dispatched. Rejects with [`Error::MissingCapability`]
+    /// if `target` hasn't advertised the capability `action` requires,
+    /// rather than sending a message that'll just be rejected downstream.
+    ///
+    /// If an [`OutboxStore`] is attached, the message is durably persisted
+    /// before the send is attempted and only cleared once it's confirmed
+    /// dispatched, so a crash in between leaves it for [`Self::replay_outbox`]
+    /// to resend instead of silently dropping it.
+    pub async fn send_message(&self, target: Uuid, action: AgentAction, payload: serde_json::Value) -> Result<(), Error> {
+        self.send_message_with_id(Uuid::new_v4(), target, action, payload).await
+    }
+
+    /// Replay every entry an attached [`OutboxStore`] still has marked
+    /// undelivered, e.g. on startup after a crash. Returns how many were
+    /// successfully resent.
+    pub async fn replay_outbox(&self) -> usize {
+        let Some(outbox) = self.outbox.clone() else { return 0 };
+
+        let mut replayed = 0;
+        for entry in outbox.undelivered().await {
+            let Some(action) = AgentAction::from_capability(&entry.action) else { continue };
+            if self.send_message_with_id(entry.id, entry.target, action, entry.payload).await.is_ok() {
+                replayed += 1;
+            }
+        }
+        replayed
+    }
+
+    async fn send_message_with_id(&self, id: Uuid, target: Uuid, action: AgentAction, payload: serde_json::Value) -> Result<(), Error> {
+        if let Some(outbox) = &self.outbox {
+            let action_name = action.required_capability().to_string();
+            outbox.persist(OutboxEntry { id, target, action: action_name, payload: payload.clone() }).await;
+        }
+
+        let (result_tx, result_rx) = oneshot::channel();
+        self.message_tx
+            .send(PumpMessage { target, action, payload, result_tx })
+            .await
+            .map_err(|_| Error::Connection("agent message pump has shut down".to_string()))?;
+        let result = result_rx.await.map_err(|_| Error::Connection("agent message pump dropped the result".to_string()))?;
+
+        if result.is_ok() {
+            if let Some(outbox) = &self.outbox {
+                outbox.mark_delivered(id).await;
+            }
+        }
+        result
+    }
+
+    /// Owns `message_rx` for the lifetime of the agent: validates each
+    /// enqueued message's capability against the cached peer roster,
+    /// then dispatches it to the target connection.
+    async fn run_pump(
+        mut message_rx: mpsc::Receiver<PumpMessage>,
+        connection_pool: ConnectionPool,
+        peer_capabilities: Arc<RwLock<HashMap<Uuid, Vec<String>>>>,
+    ) {
+        while let Some(message) = message_rx.recv().await {
+            let result = Self::dispatch(&connection_pool, &peer_capabilities, message.target, message.action, message.payload).await;
+            let _ = message.result_tx.send(result);
+        }
+    }
+
+    async fn dispatch(
+        connection_pool: &ConnectionPool,
+        peer_capabilities: &Arc<RwLock<HashMap<Uuid, Vec<String>>>>,
+        target: Uuid,
+        action: AgentAction,
+        payload: serde_json::Value,
+    ) -> Result<(), Error> {
+        let required = action.required_capability();
+        let has_capability = peer_capabilities
+            .read()
+            .await
+            .get(&target)
+            .map(|capabilities| capabilities.iter().any(|c| c == required))
+            .unwrap_or(false);
+        if !has_capability {
+            return Err(Error::MissingCapability { peer: target.to_string(), capability: required.to_string() });
+        }
+
+        let message = ProtocolMessage::new(MessageType::Data, serde_json::json!({ "target": target, "payload": payload }));
+        let connection = connection_pool.get_connection(target).await.ok_or_else(|| Error::ConnectionNotFound(target.to_string()))?;
+        connection.send_message(&message).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn sending_an_action_to_a_peer_missing_the_capability_errors_before_touching_the_connection() {
+        let pool = ConnectionPool::new();
+        let agent = Agent::new(Uuid::new_v4(), pool);
+        let peer = Uuid::new_v4();
+        agent.observe_peer_capabilities(peer, vec!["chat".to_string()]).await;
+
+        let err = agent
+            .send_message(peer, AgentAction::VectorStore, serde_json::json!({"query": "hi"}))
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            Error::MissingCapability { ref capability, .. } if capability == "vector_store"
+        ));
+    }
+
+    #[tokio::test]
+    async fn a_sent_message_flows_through_the_pump_to_the_connection() {
+        use crate::connection::Connection;
+        use futures::sink::drain;
+
+        let pool = ConnectionPool::new();
+        let connection = Connection::spawn(drain());
+        let peer = connection.id;
+        pool.add_connection(connection).await.unwrap();
+
+        let agent = Agent::new(Uuid::new_v4(), pool);
+        agent.observe_peer_capabilities(peer, vec!["vector_store".to_string()]).await;
+
+        agent
+            .send_message(peer, AgentAction::VectorStore, serde_json::json!({"query": "hi"}))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn a_freshly_created_agent_starts_offline() {
+        let agent = Agent::new(Uuid::new_v4(), ConnectionPool::new());
+        assert_eq!(agent.status().await, AgentStatus::Offline);
+    }
+
+    #[tokio::test]
+    async fn connect_then_disconnect_transitions_status_and_clears_the_pool() {
+        use crate::server::MeapServer;
+
+        let server = MeapServer::new();
+        let addr = server.start("127.0.0.1:0".parse().unwrap(), None).await.unwrap();
+
+        let agent = Agent::new(Uuid::new_v4(), ConnectionPool::new());
+        agent.connect(&format!("ws://{addr}")).await.unwrap();
+        assert_eq!(agent.status().await, AgentStatus::Connected);
+        assert_eq!(agent.connection_pool.stats().await.total_connections, 1);
+
+        agent.disconnect().await;
+        assert_eq!(agent.status().await, AgentStatus::Offline);
+        assert_eq!(agent.connection_pool.stats().await.total_connections, 0);
+    }
+
+    /// Builds a fresh self-signed cert valid for `localhost`, writes it and
+    /// its key to `dir` as PEM files, and returns their paths.
+    fn self_signed_localhost_cert(dir: &tempfile::TempDir) -> (std::path::PathBuf, std::path::PathBuf) {
+        let rcgen::CertifiedKey { cert, key_pair } = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let cert_path = dir.path().join("cert.pem");
+        let key_path = dir.path().join("key.pem");
+        std::fs::write(&cert_path, cert.pem()).unwrap();
+        std::fs::write(&key_path, key_pair.serialize_pem()).unwrap();
+        (cert_path, key_path)
+    }
+
+    #[tokio::test]
+    async fn connecting_over_tls_succeeds_with_the_signing_ca_trusted_and_fails_without_it() {
+        use crate::server::MeapServer;
+
+        let dir = tempfile::tempdir().unwrap();
+        let (cert_path, key_path) = self_signed_localhost_cert(&dir);
+        let acceptor = crate::tls::server_acceptor(&cert_path, &key_path).unwrap();
+
+        let server = MeapServer::new();
+        let addr = server.start("127.0.0.1:0".parse().unwrap(), Some(acceptor)).await.unwrap();
+        let url = format!("wss://localhost:{}", addr.port());
+
+        // Without the signing CA trusted, the self-signed cert is rejected.
+        let untrusting_agent = Agent::new(Uuid::new_v4(), ConnectionPool::new());
+        let err = untrusting_agent.connect(&url).await.unwrap_err();
+        assert!(
+            matches!(err, Error::Io(ref io_err) if io_err.to_string().contains("UnknownIssuer")),
+            "expected a certificate verification failure, got {err:?}"
+        );
+
+        // With it trusted via `with_tls_ca_cert`, the same connection succeeds.
+        let trusting_agent = Agent::new(Uuid::new_v4(), ConnectionPool::new()).with_tls_ca_cert(cert_path);
+        trusting_agent.connect(&url).await.unwrap();
+        assert_eq!(trusting_agent.status().await, AgentStatus::Connected);
+    }
+
+    #[tokio::test]
+    async fn connecting_to_a_dead_address_reports_an_error_status() {
+        let agent = Agent::new(Uuid::new_v4(), ConnectionPool::new());
+        let err = agent.connect("ws://127.0.0.1:1").await.unwrap_err();
+        assert!(matches!(agent.status().await, AgentStatus::Error(ref reason) if reason == &err.to_string()));
+    }
+
+    /// Requires a live NATS server at `localhost:4222`, so it's excluded
+    /// from the default test run.
+    #[tokio::test]
+    #[ignore]
+    async fn two_agents_subscribed_to_a_topic_both_receive_a_publish() {
+        let broker = Broker::connect("localhost:4222").await.unwrap();
+
+        let subscriber_a = Agent::new(Uuid::new_v4(), ConnectionPool::new()).with_broker(broker.clone());
+        let subscriber_b = Agent::new(Uuid::new_v4(), ConnectionPool::new()).with_broker(broker.clone());
+        let mut stream_a = subscriber_a.subscribe("agents.announcements").await.unwrap();
+        let mut stream_b = subscriber_b.subscribe("agents.announcements").await.unwrap();
+
+        let publisher = Agent::new(Uuid::new_v4(), ConnectionPool::new()).with_broker(broker);
+        publisher.publish("agents.announcements", serde_json::json!({"hello": "world"})).await.unwrap();
+
+        let received_a = stream_a.recv().await.unwrap();
+        let received_b = stream_b.recv().await.unwrap();
+        assert_eq!(received_a.payload, serde_json::json!({"hello": "world"}));
+        assert_eq!(received_b.payload, serde_json::json!({"hello": "world"}));
+    }
+
+    #[tokio::test]
+    async fn a_persisted_message_is_resent_by_a_new_agent_over_the_same_store() {
+        use crate::connection::Connection;
+        use crate::outbox::InMemoryOutboxStore;
+        use futures::sink::drain;
+
+        let store: Arc<dyn OutboxStore> = Arc::new(InMemoryOutboxStore::new());
+        let connection = Connection::spawn(drain());
+        let target = connection.id;
+
+        // Crashes before the target connection is ever registered in its
+        // pool, so the send fails and the outbox entry is left behind.
+        let crashed_agent = Agent::new(Uuid::new_v4(), ConnectionPool::new()).with_outbox(store.clone());
+        crashed_agent.observe_peer_capabilities(target, vec!["chat".to_string()]).await;
+        crashed_agent.send_message(target, AgentAction::Chat, serde_json::json!({"text": "hi"})).await.unwrap_err();
+        assert_eq!(store.undelivered().await.len(), 1);
+
+        // A fresh `Agent` over the same store, with a pool that can
+        // actually reach the target, replays and clears it.
+        let pool = ConnectionPool::new();
+        pool.add_connection(connection).await.unwrap();
+        let restarted_agent = Agent::new(Uuid::new_v4(), pool).with_outbox(store.clone());
+        restarted_agent.observe_peer_capabilities(target, vec!["chat".to_string()]).await;
+
+        let replayed = restarted_agent.replay_outbox().await;
+        assert_eq!(replayed, 1);
+        assert!(store.undelivered().await.is_empty());
+    }
+}
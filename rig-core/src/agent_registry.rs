@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// An agent registered with a [`crate::ConnectionPool`] after completing
+/// its connection handshake, tracked alongside the raw [`crate::Connection`]
+/// so callers can answer "what can agent X do" without reaching into
+/// application-level state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConnectedAgent {
+    pub id: Uuid,
+    pub capabilities: Vec<String>,
+    pub status: String,
+}
+
+/// Registry of [`ConnectedAgent`]s, keyed by connection id.
+#[derive(Clone, Default)]
+pub struct AgentRegistry {
+    agents: Arc<RwLock<HashMap<Uuid, ConnectedAgent>>>,
+}
+
+impl AgentRegistry {
+    /// Record `id` as connected with the capabilities it advertised in its
+    /// registration message (empty if it advertised none).
+    pub async fn register(&self, id: Uuid, capabilities: Vec<String>) {
+        self.agents.write().await.insert(
+            id,
+            ConnectedAgent {
+                id,
+                capabilities,
+                status: "connected".to_string(),
+            },
+        );
+    }
+
+    pub async fn remove(&self, id: Uuid) -> Option<ConnectedAgent> {
+        self.agents.write().await.remove(&id)
+    }
+
+    pub async fn list_agents(&self) -> Vec<ConnectedAgent> {
+        self.agents.read().await.values().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn registering_an_agent_records_its_advertised_capabilities() {
+        let registry = AgentRegistry::default();
+        let id = Uuid::new_v4();
+        registry.register(id, vec!["chat".to_string(), "search".to_string()]).await;
+
+        let agents = registry.list_agents().await;
+        assert_eq!(agents.len(), 1);
+        assert_eq!(agents[0].id, id);
+        assert_eq!(agents[0].capabilities, vec!["chat", "search"]);
+        assert_eq!(agents[0].status, "connected");
+    }
+
+    #[tokio::test]
+    async fn removing_an_agent_drops_it_from_list_agents() {
+        let registry = AgentRegistry::default();
+        let id = Uuid::new_v4();
+        registry.register(id, Vec::new()).await;
+        registry.remove(id).await;
+        assert!(registry.list_agents().await.is_empty());
+    }
+}
@@ -0,0 +1,52 @@
+use std::time::Duration;
+
+use rand::Rng;
+
+/// Default starting delay for [`backoff_delay`] before any jitter.
+pub const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(100);
+
+/// Default ceiling for [`backoff_delay`], regardless of attempt count.
+pub const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// Capped exponential backoff with full jitter: returns a delay chosen
+/// uniformly at random from `[0, min(max_delay, base * 2^attempt)]`.
+///
+/// Full jitter (rather than a fixed or half-jittered delay) spreads out
+/// retries from agents that all failed around the same time, avoiding the
+/// thundering herd a fixed `base * 2^attempt` delay would cause. Uses
+/// saturating arithmetic throughout so a very large `attempt` clamps to
+/// `max_delay` instead of overflowing or panicking.
+pub fn backoff_delay(attempt: u32, base: Duration, max_delay: Duration) -> Duration {
+    let multiplier = 1u32.checked_shl(attempt).unwrap_or(u32::MAX);
+    let capped = base.saturating_mul(multiplier).min(max_delay);
+    let jitter_micros = rand::thread_rng().gen_range(0..=capped.as_micros() as u64);
+    Duration::from_micros(jitter_micros)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delay_never_exceeds_the_capped_exponential_bound() {
+        let base = Duration::from_millis(100);
+        let max_delay = Duration::from_secs(5);
+        for attempt in 0..=64u32 {
+            let delay = backoff_delay(attempt, base, max_delay);
+            let bound = base.saturating_mul(1u32.checked_shl(attempt).unwrap_or(u32::MAX)).min(max_delay);
+            assert!(delay <= bound, "attempt {attempt}: delay {delay:?} exceeded bound {bound:?}");
+        }
+    }
+
+    #[test]
+    fn delay_is_capped_at_max_delay_for_a_large_attempt_count() {
+        let delay = backoff_delay(64, Duration::from_millis(100), Duration::from_secs(5));
+        assert!(delay <= Duration::from_secs(5));
+    }
+
+    #[test]
+    fn zero_attempts_stays_within_the_base_delay() {
+        let delay = backoff_delay(0, Duration::from_millis(100), Duration::from_secs(30));
+        assert!(delay <= Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,107 @@
+use std::sync::atomic::{AtomicU32, AtomicU8, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+enum State {
+    Closed = 0,
+    Open = 1,
+    HalfOpen = 2,
+}
+
+/// Tracks consecutive failures on a connection and trips open once
+/// `failure_threshold` is reached, rejecting calls until `reset_timeout`
+/// has elapsed, at which point a single trial call is let through
+/// (half-open) to decide whether to close again.
+#[derive(Clone)]
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    reset_timeout: Duration,
+    failures: Arc<AtomicU32>,
+    state: Arc<AtomicU8>,
+    opened_at: Arc<Mutex<Option<Instant>>>,
+}
+
+impl CircuitBreaker {
+    pub fn new(failure_threshold: u32, reset_timeout: Duration) -> Self {
+        Self {
+            failure_threshold,
+            reset_timeout,
+            failures: Arc::new(AtomicU32::new(0)),
+            state: Arc::new(AtomicU8::new(State::Closed as u8)),
+            opened_at: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Whether a call should currently be allowed through.
+    pub async fn is_call_permitted(&self) -> bool {
+        match self.state.load(Ordering::SeqCst) {
+            s if s == State::Closed as u8 => true,
+            s if s == State::Open as u8 => {
+                let elapsed = self
+                    .opened_at
+                    .lock()
+                    .await
+                    .map(|t| t.elapsed())
+                    .unwrap_or(Duration::ZERO);
+                if elapsed >= self.reset_timeout {
+                    self.state.store(State::HalfOpen as u8, Ordering::SeqCst);
+                    true
+                } else {
+                    false
+                }
+            }
+            _ => true, // half-open: allow the single trial call
+        }
+    }
+
+    pub async fn record_success(&self) {
+        self.failures.store(0, Ordering::SeqCst);
+        self.state.store(State::Closed as u8, Ordering::SeqCst);
+        *self.opened_at.lock().await = None;
+    }
+
+    pub async fn record_failure(&self) {
+        let failures = self.failures.fetch_add(1, Ordering::SeqCst) + 1;
+        if failures >= self.failure_threshold {
+            self.state.store(State::Open as u8, Ordering::SeqCst);
+            *self.opened_at.lock().await = Some(Instant::now());
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.state.load(Ordering::SeqCst) == State::Open as u8
+    }
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new(5, Duration::from_secs(30))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn trips_open_after_threshold() {
+        let cb = CircuitBreaker::new(2, Duration::from_secs(60));
+        cb.record_failure().await;
+        assert!(cb.is_call_permitted().await);
+        cb.record_failure().await;
+        assert!(!cb.is_call_permitted().await);
+    }
+
+    #[tokio::test]
+    async fn half_opens_after_reset_timeout() {
+        let cb = CircuitBreaker::new(1, Duration::from_millis(10));
+        cb.record_failure().await;
+        assert!(!cb.is_call_permitted().await);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(cb.is_call_permitted().await);
+    }
+}
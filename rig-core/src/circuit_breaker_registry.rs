@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tokio::sync::RwLock;
+
+use crate::circuit_breaker::CircuitBreaker;
+
+/// Tracks one [`CircuitBreaker`] per named endpoint (e.g. a backend node
+/// address), so callers like [`crate::LoadBalancer`] can route around
+/// endpoints that are currently failing instead of tripping a single
+/// breaker shared across everything.
+#[derive(Clone, Default)]
+pub struct CircuitBreakerRegistry {
+    breakers: Arc<RwLock<HashMap<String, CircuitBreaker>>>,
+}
+
+impl CircuitBreakerRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the breaker for `endpoint`, creating one with default
+    /// thresholds on first use.
+    pub async fn breaker_for(&self, endpoint: &str) -> CircuitBreaker {
+        if let Some(breaker) = self.breakers.read().await.get(endpoint) {
+            return breaker.clone();
+        }
+        self.breakers
+            .write()
+            .await
+            .entry(endpoint.to_string())
+            .or_insert_with(CircuitBreaker::default)
+            .clone()
+    }
+
+    pub async fn record_success(&self, endpoint: &str) {
+        self.breaker_for(endpoint).await.record_success().await;
+    }
+
+    pub async fn record_failure(&self, endpoint: &str) {
+        self.breaker_for(endpoint).await.record_failure().await;
+    }
+
+    /// Whether a call to `endpoint` should currently be permitted.
+    /// Endpoints with no breaker yet (never recorded a failure) are
+    /// always permitted.
+    pub async fn is_available(&self, endpoint: &str) -> bool {
+        match self.breakers.read().await.get(endpoint) {
+            Some(breaker) => breaker.is_call_permitted().await,
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn an_endpoint_with_no_recorded_failures_is_available() {
+        let registry = CircuitBreakerRegistry::new();
+        assert!(registry.is_available("node-a").await);
+    }
+
+    #[tokio::test]
+    async fn a_tripped_endpoint_is_unavailable_while_others_are_unaffected() {
+        let registry = CircuitBreakerRegistry::new();
+        for _ in 0..5 {
+            registry.record_failure("node-a").await;
+        }
+
+        assert!(!registry.is_available("node-a").await);
+        assert!(registry.is_available("node-b").await);
+    }
+
+    #[tokio::test]
+    async fn recording_a_success_resets_a_tripped_endpoint() {
+        let registry = CircuitBreakerRegistry::new();
+        for _ in 0..5 {
+            registry.record_failure("node-a").await;
+        }
+        assert!(!registry.is_available("node-a").await);
+
+        registry.record_success("node-a").await;
+        assert!(registry.is_available("node-a").await);
+    }
+}
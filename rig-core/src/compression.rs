@@ -0,0 +1,201 @@
+use std::io::{Read, Write};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+use crate::protocol::{MessageType, ProtocolMessage};
+
+/// Compression algorithm applied to a frame's payload, written as a
+/// one-byte marker ahead of the (possibly compressed) bytes so a receiver
+/// always knows whether to decompress, even for a frame that ended up
+/// stored uncompressed because it was under the size threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Codec {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Codec {
+    fn as_byte(self) -> u8 {
+        match self {
+            Codec::None => 0,
+            Codec::Gzip => 1,
+            Codec::Zstd => 2,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Result<Self, Error> {
+        match byte {
+            0 => Ok(Codec::None),
+            1 => Ok(Codec::Gzip),
+            2 => Ok(Codec::Zstd),
+            _ => Err(Error::Decompression),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Codec::None => "none",
+            Codec::Gzip => "gzip",
+            Codec::Zstd => "zstd",
+        }
+    }
+}
+
+/// Codecs a peer supports, in preference order (most preferred first).
+/// Used both to configure outbound compression and to negotiate over a
+/// handshake.
+pub const PREFERENCE_ORDER: [Codec; 3] = [Codec::Zstd, Codec::Gzip, Codec::None];
+
+/// Outbound compression settings for a [`crate::Connection`]. Payloads
+/// under `threshold_bytes` are sent uncompressed (still marker-prefixed)
+/// since compressing small payloads tends to cost more than it saves.
+#[derive(Debug, Clone, Copy)]
+pub struct CompressionConfig {
+    pub threshold_bytes: usize,
+    pub codec: Codec,
+}
+
+impl CompressionConfig {
+    pub fn new(threshold_bytes: usize, codec: Codec) -> Self {
+        Self { threshold_bytes, codec }
+    }
+}
+
+/// Compress `payload` per `config`, returning the marker byte followed by
+/// the (possibly compressed) bytes. Always marker-prefixed so [`decode`]
+/// doesn't need out-of-band knowledge of whether this frame is compressed.
+pub fn encode(payload: &[u8], config: &CompressionConfig) -> Vec<u8> {
+    let codec = if payload.len() >= config.threshold_bytes { config.codec } else { Codec::None };
+
+    let mut body = match codec {
+        Codec::None => payload.to_vec(),
+        Codec::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(payload).expect("writing to an in-memory encoder cannot fail");
+            encoder.finish().expect("finishing an in-memory encoder cannot fail")
+        }
+        Codec::Zstd => zstd::encode_all(payload, 0).expect("zstd encoding an in-memory buffer cannot fail"),
+    };
+
+    let mut framed = Vec::with_capacity(body.len() + 1);
+    framed.push(codec.as_byte());
+    framed.append(&mut body);
+    framed
+}
+
+/// Reverse of [`encode`]: read the marker byte and decompress the rest
+/// accordingly.
+pub fn decode(frame: &[u8]) -> Result<Vec<u8>, Error> {
+    let (&marker, body) = frame.split_first().ok_or(Error::Decompression)?;
+    match Codec::from_byte(marker)? {
+        Codec::None => Ok(body.to_vec()),
+        Codec::Gzip => {
+            let mut decoder = GzDecoder::new(body);
+            let mut out = Vec::new();
+            decoder.read_to_end(&mut out).map_err(|_| Error::Decompression)?;
+            Ok(out)
+        }
+        Codec::Zstd => zstd::decode_all(body).map_err(|_| Error::Decompression),
+    }
+}
+
+/// Build the `Connect` handshake message advertising the codecs this peer
+/// supports, most preferred first.
+pub fn handshake(supported: &[Codec]) -> ProtocolMessage {
+    let labels: Vec<&str> = supported.iter().map(|codec| codec.label()).collect();
+    ProtocolMessage::new(MessageType::Connect, serde_json::json!({ "supported_codecs": labels }))
+}
+
+/// Pick the best codec both sides support, from a handshake sent by the
+/// other peer via [`handshake`]. Falls back to [`Codec::None`] if the
+/// payload is malformed or the two sides share nothing compressed.
+pub fn negotiate_from_handshake(message: &ProtocolMessage, local_supported: &[Codec]) -> Codec {
+    let remote: Vec<Codec> = message
+        .payload
+        .get("supported_codecs")
+        .and_then(|value| value.as_array())
+        .map(|codecs| {
+            codecs
+                .iter()
+                .filter_map(|codec| codec.as_str())
+                .filter_map(|label| PREFERENCE_ORDER.iter().find(|candidate| candidate.label() == label).copied())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    negotiate(local_supported, &remote)
+}
+
+/// The highest-priority codec (per [`PREFERENCE_ORDER`]) present in both
+/// `local_supported` and `remote_supported`, or [`Codec::None`] if they
+/// share nothing.
+pub fn negotiate(local_supported: &[Codec], remote_supported: &[Codec]) -> Codec {
+    PREFERENCE_ORDER
+        .into_iter()
+        .find(|codec| local_supported.contains(codec) && remote_supported.contains(codec))
+        .unwrap_or(Codec::None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn below_threshold_payloads_are_stored_uncompressed_with_a_marker_byte() {
+        let config = CompressionConfig::new(1024, Codec::Gzip);
+        let frame = encode(b"short", &config);
+        assert_eq!(frame[0], Codec::None.as_byte());
+        assert_eq!(decode(&frame).unwrap(), b"short");
+    }
+
+    #[test]
+    fn a_100kb_payload_round_trips_through_gzip_compression() {
+        let payload = "x".repeat(100_000).into_bytes();
+        let config = CompressionConfig::new(1024, Codec::Gzip);
+
+        let frame = encode(&payload, &config);
+        assert_eq!(frame[0], Codec::Gzip.as_byte());
+        assert!(frame.len() < payload.len(), "compressed frame should be smaller than the original 100KB payload");
+
+        assert_eq!(decode(&frame).unwrap(), payload);
+    }
+
+    #[test]
+    fn a_100kb_payload_round_trips_through_zstd_compression() {
+        let payload = "x".repeat(100_000).into_bytes();
+        let config = CompressionConfig::new(1024, Codec::Zstd);
+
+        let frame = encode(&payload, &config);
+        assert_eq!(frame[0], Codec::Zstd.as_byte());
+        assert!(frame.len() < payload.len(), "compressed frame should be smaller than the original 100KB payload");
+
+        assert_eq!(decode(&frame).unwrap(), payload);
+    }
+
+    #[test]
+    fn negotiate_prefers_the_highest_priority_codec_both_sides_support() {
+        let local = [Codec::Zstd, Codec::Gzip, Codec::None];
+        let remote = [Codec::Gzip, Codec::None];
+        assert_eq!(negotiate(&local, &remote), Codec::Gzip);
+    }
+
+    #[test]
+    fn negotiate_falls_back_to_none_when_codecs_dont_overlap() {
+        let local = [Codec::Zstd];
+        let remote = [Codec::Gzip];
+        assert_eq!(negotiate(&local, &remote), Codec::None);
+    }
+
+    #[test]
+    fn negotiate_from_handshake_reads_the_remote_peers_supported_codecs() {
+        let message = handshake(&[Codec::Gzip, Codec::None]);
+        let codec = negotiate_from_handshake(&message, &[Codec::Zstd, Codec::Gzip, Codec::None]);
+        assert_eq!(codec, Codec::Gzip);
+    }
+}
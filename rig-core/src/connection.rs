@@ -0,0 +1,350 @@
+use std::time::Duration;
+
+use futures::SinkExt;
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+use uuid::Uuid;
+
+use crate::circuit_breaker::CircuitBreaker;
+use crate::compression::{self, CompressionConfig};
+use crate::error::Error;
+use crate::metrics::ConnectionMetrics;
+use crate::security::SecurityManager;
+use crate::wire_format::{self, WireFormat};
+
+/// A single logical connection to a remote agent, backed by a WebSocket
+/// sink. Outbound frames are handed to a background write task over
+/// `outbound`, so `send` never blocks on the socket itself.
+#[derive(Clone)]
+pub struct Connection {
+    pub id: Uuid,
+    pub metrics: ConnectionMetrics,
+    pub circuit_breaker: CircuitBreaker,
+    security: Option<SecurityManager>,
+    compression: Option<CompressionConfig>,
+    wire_format: WireFormat,
+    send_timeout: Option<Duration>,
+    outbound: mpsc::Sender<WsMessage>,
+}
+
+/// How many outbound frames may be queued for the background write task
+/// before a sender without [`Connection::with_send_timeout`] configured
+/// blocks waiting for room.
+const DEFAULT_OUTBOUND_CAPACITY: usize = 1024;
+
+impl Connection {
+    /// Spawn the background write task that drains `sink` into `outbound`,
+    /// and return a handle for sending frames to it.
+    pub fn spawn<S>(sink: S) -> Self
+    where
+        S: futures::Sink<WsMessage> + Send + Unpin + 'static,
+        S::Error: std::fmt::Display,
+    {
+        Self::spawn_with_circuit_breaker(sink, CircuitBreaker::default())
+    }
+
+    /// Like [`Self::spawn`], but with an explicit [`CircuitBreaker`]
+    /// configuration instead of the default thresholds.
+    pub fn spawn_with_circuit_breaker<S>(sink: S, circuit_breaker: CircuitBreaker) -> Self
+    where
+        S: futures::Sink<WsMessage> + Send + Unpin + 'static,
+        S::Error: std::fmt::Display,
+    {
+        Self::spawn_with_capacity(sink, circuit_breaker, DEFAULT_OUTBOUND_CAPACITY)
+    }
+
+    /// Like [`Self::spawn_with_circuit_breaker`], but with an explicit
+    /// outbound queue capacity. Kept private: callers reach for
+    /// [`Self::with_send_timeout`] to bound how long a full queue blocks a
+    /// sender, rather than tuning the queue depth itself.
+    fn spawn_with_capacity<S>(sink: S, circuit_breaker: CircuitBreaker, capacity: usize) -> Self
+    where
+        S: futures::Sink<WsMessage> + Send + Unpin + 'static,
+        S::Error: std::fmt::Display,
+    {
+        let (tx, mut rx) = mpsc::channel::<WsMessage>(capacity);
+        let mut sink = sink;
+        tokio::spawn(async move {
+            while let Some(msg) = rx.recv().await {
+                if let Err(err) = sink.send(msg).await {
+                    tracing::warn!(%err, "failed writing to connection sink");
+                    break;
+                }
+            }
+        });
+
+        Self {
+            id: Uuid::new_v4(),
+            metrics: ConnectionMetrics::default(),
+            circuit_breaker,
+            security: None,
+            compression: None,
+            wire_format: WireFormat::default(),
+            send_timeout: None,
+            outbound: tx,
+        }
+    }
+
+    /// Enable at-rest encryption of outbound frames via `security`.
+    pub fn with_security(mut self, security: SecurityManager) -> Self {
+        self.security = Some(security);
+        self
+    }
+
+    /// Enable compression of outbound frames above `config`'s size
+    /// threshold. Safe to combine with [`Self::with_security`]: payloads
+    /// are compressed first, then encrypted, since encrypted bytes don't
+    /// compress.
+    pub fn with_compression(mut self, config: CompressionConfig) -> Self {
+        self.compression = Some(config);
+        self
+    }
+
+    /// Use `format` to encode [`Self::send_message`] payloads instead of
+    /// the default [`WireFormat::Json`].
+    pub fn with_wire_format(mut self, format: WireFormat) -> Self {
+        self.wire_format = format;
+        self
+    }
+
+    /// Bound how long [`Self::send`]/[`Self::send_message`] will wait for
+    /// room in the outbound queue before giving up. Without this, a writer
+    /// task stalled on a slow or dead socket leaves the queue full and
+    /// every subsequent sender blocked indefinitely.
+    pub fn with_send_timeout(mut self, timeout: Duration) -> Self {
+        self.send_timeout = Some(timeout);
+        self
+    }
+
+    /// Queue a text frame for delivery, tripping the circuit breaker on
+    /// failure so future sends can be rejected fast.
+    #[tracing::instrument(skip(self, payload), fields(connection_id = %self.id))]
+    pub async fn send(&self, payload: impl Into<String>) -> Result<(), Error> {
+        if !self.circuit_breaker.is_call_permitted().await {
+            return Err(Error::CircuitOpen(self.id.to_string()));
+        }
+
+        let payload = payload.into();
+        let len = payload.len();
+        let frame = if self.compression.is_none() && self.security.is_none() {
+            WsMessage::Text(payload)
+        } else {
+            self.compress_and_secure(payload.into_bytes())?
+        };
+
+        self.enqueue(frame, len).await
+    }
+
+    /// Serialize `message` per [`Self::with_wire_format`] and queue it for
+    /// delivery, applying compression/encryption exactly like [`Self::send`].
+    /// Always sent as a binary frame: `WireFormat::MsgPack` isn't valid
+    /// UTF-8 text, and the receiver decodes by `wire_format`, not by WS
+    /// frame type (see [`crate::wire_format::decode_incoming`]).
+    pub async fn send_message<T: Serialize>(&self, message: &T) -> Result<(), Error> {
+        if !self.circuit_breaker.is_call_permitted().await {
+            return Err(Error::CircuitOpen(self.id.to_string()));
+        }
+
+        let bytes = wire_format::encode(message, self.wire_format)?;
+        let len = bytes.len();
+        let frame = self.compress_and_secure(bytes)?;
+
+        self.enqueue(frame, len).await
+    }
+
+    /// Shared compress-then-encrypt pipeline for any payload that isn't
+    /// going out as a plain `Text` frame.
+    fn compress_and_secure(&self, bytes: Vec<u8>) -> Result<WsMessage, Error> {
+        let bytes = match &self.compression {
+            Some(config) => {
+                let before = bytes.len();
+                let compressed = compression::encode(&bytes, config);
+                self.metrics.record_compression(before, compressed.len());
+                compressed
+            }
+            None => bytes,
+        };
+        match &self.security {
+            Some(security) => Ok(WsMessage::Binary(security.encrypt(&bytes)?)),
+            None => Ok(WsMessage::Binary(bytes)),
+        }
+    }
+
+    /// Hand `frame` to the background write task, updating metrics and the
+    /// circuit breaker based on whether the send succeeded. If
+    /// [`Self::with_send_timeout`] is set and the queue stays full for
+    /// longer than that, the send is abandoned and counted as a circuit
+    /// breaker failure rather than left to block forever.
+    async fn enqueue(&self, frame: WsMessage, len: usize) -> Result<(), Error> {
+        let sent = match self.send_timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, self.outbound.send(frame)).await {
+                Ok(result) => result,
+                Err(_) => {
+                    self.circuit_breaker.record_failure().await;
+                    return Err(Error::Connection("send timed out".to_string()));
+                }
+            },
+            None => self.outbound.send(frame).await,
+        };
+
+        match sent {
+            Ok(()) => {
+                self.metrics.record_sent(len);
+                self.circuit_breaker.record_success().await;
+                Ok(())
+            }
+            Err(_) => {
+                self.circuit_breaker.record_failure().await;
+                Err(Error::ConnectionClosed(self.id.to_string()))
+            }
+        }
+    }
+
+    /// Send a WebSocket ping frame, used by [`crate::ConnectionPool`]'s
+    /// heartbeat task to detect dead peers. Best-effort: if the outbound
+    /// queue is full, the ping is dropped rather than blocking the
+    /// heartbeat task.
+    pub fn send_ping(&self) -> Result<(), Error> {
+        self.outbound
+            .try_send(WsMessage::Ping(Vec::new()))
+            .map_err(|_| Error::ConnectionClosed(self.id.to_string()))
+    }
+
+    /// Queue a WebSocket close frame, used by [`crate::ConnectionPool::close_all`]
+    /// for graceful shutdown. Best-effort, like [`Self::send_ping`].
+    pub fn send_close(&self) -> Result<(), Error> {
+        self.outbound
+            .try_send(WsMessage::Close(None))
+            .map_err(|_| Error::ConnectionClosed(self.id.to_string()))
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.outbound.is_closed()
+    }
+
+    /// Coarse health derived from the connection's existing socket/circuit
+    /// breaker state, rather than tracked as separate state that could
+    /// drift from the truth.
+    pub fn status(&self) -> ConnectionStatus {
+        if self.is_closed() {
+            ConnectionStatus::Closed
+        } else if self.circuit_breaker.is_open() {
+            ConnectionStatus::Degraded
+        } else {
+            ConnectionStatus::Connected
+        }
+    }
+}
+
+/// Default interval between heartbeat pings sent to every pooled connection.
+pub const DEFAULT_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Coarse connection health, as reported by [`Connection::status`] and
+/// aggregated across a pool by [`crate::ConnectionPool::stats`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConnectionStatus {
+    /// Socket open, circuit breaker closed: sends should succeed.
+    Connected,
+    /// Socket open but the circuit breaker has tripped: sends are being
+    /// rejected fast until it recovers.
+    Degraded,
+    /// The background write task has exited; sends will fail immediately.
+    Closed,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::sink::drain;
+
+    #[tokio::test]
+    async fn send_encrypts_when_security_is_configured() {
+        let security = SecurityManager::new(SecurityManager::generate_key());
+        let conn = Connection::spawn(drain()).with_security(security);
+        conn.send("secret payload").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn send_compresses_a_large_payload_when_compression_is_configured() {
+        use crate::compression::{CompressionConfig, Codec};
+
+        let config = CompressionConfig::new(1024, Codec::Gzip);
+        let conn = Connection::spawn(drain()).with_compression(config);
+        conn.send("x".repeat(100_000)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn compression_ratio_reflects_actual_bytes_saved() {
+        use crate::compression::{CompressionConfig, Codec};
+
+        let config = CompressionConfig::new(1024, Codec::Gzip);
+        let conn = Connection::spawn(drain()).with_compression(config);
+        for _ in 0..3 {
+            conn.send("x".repeat(100_000)).await.unwrap();
+        }
+
+        let stats = conn.metrics.stats();
+        assert_eq!(stats.bytes_before_compression, 300_000);
+        assert!(stats.bytes_after_compression < stats.bytes_before_compression);
+        let ratio = stats.compression_ratio.unwrap();
+        assert!(ratio > 0.0 && ratio < 1.0, "expected meaningful compression, got ratio {ratio}");
+    }
+
+    #[tokio::test]
+    async fn send_message_encodes_with_the_configured_wire_format() {
+        use crate::protocol::{MessageType, ProtocolMessage};
+
+        let conn = Connection::spawn(drain()).with_wire_format(WireFormat::MsgPack);
+        let message = ProtocolMessage::new(MessageType::Heartbeat, serde_json::Value::Null);
+        conn.send_message(&message).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn custom_circuit_breaker_threshold_is_honored() {
+        let cb = CircuitBreaker::new(1, Duration::from_secs(60));
+        let conn = Connection::spawn_with_circuit_breaker(drain(), cb);
+        assert!(!conn.circuit_breaker.is_open());
+        conn.circuit_breaker.record_failure().await;
+        assert!(conn.circuit_breaker.is_open());
+    }
+
+    #[tokio::test]
+    async fn a_tripped_breaker_surfaces_circuit_open_rather_than_connection_closed() {
+        let cb = CircuitBreaker::new(1, Duration::from_secs(60));
+        let conn = Connection::spawn_with_circuit_breaker(drain(), cb);
+        conn.circuit_breaker.record_failure().await;
+
+        let err = conn.send("hello").await.unwrap_err();
+        assert!(matches!(err, Error::CircuitOpen(_)), "expected Error::CircuitOpen, got {err:?}");
+    }
+
+    #[tokio::test]
+    async fn send_times_out_rather_than_hanging_when_the_queue_stays_full() {
+        // A sink whose `send` never resolves: the background write task
+        // gets stuck forever on the first frame it pulls off the queue, so
+        // the queue behind it fills up and stays full.
+        let stalled = futures::sink::unfold((), |(), _msg: WsMessage| std::future::pending::<Result<(), std::io::Error>>());
+
+        let conn = Connection::spawn_with_capacity(stalled, CircuitBreaker::default(), 1)
+            .with_send_timeout(Duration::from_millis(50));
+
+        // First frame is picked up immediately by the write task (which
+        // then stalls on it), second fills the lone queue slot.
+        conn.send("first").await.unwrap();
+        conn.send("second").await.unwrap();
+
+        let err = conn.send("third").await.unwrap_err();
+        assert!(matches!(err, Error::Connection(ref msg) if msg == "send timed out"), "expected a send timeout, got {err:?}");
+    }
+
+    #[tokio::test]
+    async fn status_reflects_an_open_circuit_breaker() {
+        let cb = CircuitBreaker::new(1, Duration::from_secs(60));
+        let conn = Connection::spawn_with_circuit_breaker(drain(), cb);
+        assert_eq!(conn.status(), ConnectionStatus::Connected);
+
+        conn.circuit_breaker.record_failure().await;
+        assert_eq!(conn.status(), ConnectionStatus::Degraded);
+    }
+}
@@ -0,0 +1,144 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A message that failed processing, captured so it can be inspected or
+/// replayed later via [`retry_dead_letters`] instead of being silently
+/// dropped.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetterEntry {
+    pub id: Uuid,
+    pub message: serde_json::Value,
+    pub error: String,
+    pub retry_count: u32,
+}
+
+/// Captures messages a handler failed to process. Implementations only
+/// need to persist entries and hand them back out — replay logic lives in
+/// [`retry_dead_letters`], which is generic over both the store and the
+/// caller-supplied handler.
+#[async_trait]
+pub trait DeadLetterStore: Send + Sync {
+    /// Record `message` as failed with `error`, returning the id it was
+    /// stored under.
+    async fn capture(&self, message: serde_json::Value, error: String) -> Uuid;
+
+    /// Snapshot every currently dead-lettered entry.
+    async fn entries(&self) -> Vec<DeadLetterEntry>;
+
+    /// Remove `id`, e.g. once it's been successfully replayed.
+    async fn remove(&self, id: Uuid) -> Option<DeadLetterEntry>;
+
+    /// Bump `id`'s retry count and refresh its error after a replay
+    /// attempt failed again.
+    async fn record_retry_failure(&self, id: Uuid, error: String);
+}
+
+/// Default in-memory [`DeadLetterStore`]. Entries don't survive a
+/// restart — use a persistent implementation (e.g. `rig-sqlite`'s) where
+/// that matters.
+#[derive(Clone, Default)]
+pub struct InMemoryDeadLetterStore {
+    entries: Arc<Mutex<HashMap<Uuid, DeadLetterEntry>>>,
+}
+
+impl InMemoryDeadLetterStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl DeadLetterStore for InMemoryDeadLetterStore {
+    async fn capture(&self, message: serde_json::Value, error: String) -> Uuid {
+        let id = Uuid::new_v4();
+        self.entries.lock().unwrap().insert(id, DeadLetterEntry { id, message, error, retry_count: 0 });
+        id
+    }
+
+    async fn entries(&self) -> Vec<DeadLetterEntry> {
+        self.entries.lock().unwrap().values().cloned().collect()
+    }
+
+    async fn remove(&self, id: Uuid) -> Option<DeadLetterEntry> {
+        self.entries.lock().unwrap().remove(&id)
+    }
+
+    async fn record_retry_failure(&self, id: Uuid, error: String) {
+        if let Some(entry) = self.entries.lock().unwrap().get_mut(&id) {
+            entry.retry_count += 1;
+            entry.error = error;
+        }
+    }
+}
+
+/// Replay every entry currently in `store` through `handler`, removing it
+/// on success and bumping its retry count otherwise. Returns how many
+/// entries were successfully replayed.
+pub async fn retry_dead_letters<S, F, Fut>(store: &S, mut handler: F) -> usize
+where
+    S: DeadLetterStore + ?Sized,
+    F: FnMut(serde_json::Value) -> Fut,
+    Fut: Future<Output = Result<(), String>>,
+{
+    let mut replayed = 0;
+    for entry in store.entries().await {
+        match handler(entry.message.clone()).await {
+            Ok(()) => {
+                store.remove(entry.id).await;
+                replayed += 1;
+            }
+            Err(err) => {
+                store.record_retry_failure(entry.id, err).await;
+            }
+        }
+    }
+    replayed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn captured_entries_start_with_a_zero_retry_count() {
+        let store = InMemoryDeadLetterStore::new();
+        let id = store.capture(serde_json::json!({"n": 1}), "boom".to_string()).await;
+
+        let entries = store.entries().await;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].id, id);
+        assert_eq!(entries[0].error, "boom");
+        assert_eq!(entries[0].retry_count, 0);
+    }
+
+    #[tokio::test]
+    async fn retry_dead_letters_removes_entries_the_handler_accepts() {
+        let store = InMemoryDeadLetterStore::new();
+        store.capture(serde_json::json!({"n": 1}), "boom".to_string()).await;
+
+        let replayed = retry_dead_letters(&store, |_message| async { Ok(()) }).await;
+
+        assert_eq!(replayed, 1);
+        assert!(store.entries().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn retry_dead_letters_bumps_the_retry_count_on_repeated_failure() {
+        let store = InMemoryDeadLetterStore::new();
+        let id = store.capture(serde_json::json!({"n": 1}), "boom".to_string()).await;
+
+        let replayed = retry_dead_letters(&store, |_message| async { Err("still broken".to_string()) }).await;
+
+        assert_eq!(replayed, 0);
+        let entries = store.entries().await;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].id, id);
+        assert_eq!(entries[0].retry_count, 1);
+        assert_eq!(entries[0].error, "still broken");
+    }
+}
@@ -0,0 +1,72 @@
+use std::time::Duration;
+
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    /// Boxed because `tungstenite::Error` is large enough on its own to
+    /// make every `Result<_, Error>` in this crate pay for it, per
+    /// `clippy::result_large_err`.
+    #[error("websocket error: {0}")]
+    WebSocket(#[from] Box<tokio_tungstenite::tungstenite::Error>),
+
+    #[error("i/o error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("tls error: {0}")]
+    Tls(#[from] rustls::Error),
+
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("connection '{0}' not found")]
+    ConnectionNotFound(String),
+
+    #[error("connection '{0}' is closed")]
+    ConnectionClosed(String),
+
+    #[error("rate limited{}", .retry_after.map(|d| format!(", retry after {d:?}")).unwrap_or_default())]
+    RateLimited { retry_after: Option<Duration> },
+
+    #[error("circuit breaker open for connection '{0}'")]
+    CircuitOpen(String),
+
+    #[error("connection error: {0}")]
+    Connection(String),
+
+    #[error("failed to encrypt message")]
+    Encryption,
+
+    #[error("failed to decrypt message")]
+    Decryption,
+
+    #[error("rejected replayed message nonce")]
+    NonceReuse,
+
+    #[error("failed to decompress message")]
+    Decompression,
+
+    #[error("wire format error: {0}")]
+    WireFormat(String),
+
+    #[error("vector dimension mismatch: expected {expected}, got {actual}")]
+    DimensionMismatch { expected: usize, actual: usize },
+
+    #[error("authentication failed")]
+    Authentication,
+
+    #[error("no recorded interaction matches this request")]
+    UnmatchedReplay,
+
+    #[error("peer '{peer}' lacks required capability '{capability}'")]
+    MissingCapability { peer: String, capability: String },
+
+    #[error("broker error: {0}")]
+    Broker(#[from] rig_broker::BrokerError),
+}
+
+impl From<tokio_tungstenite::tungstenite::Error> for Error {
+    fn from(err: tokio_tungstenite::tungstenite::Error) -> Self {
+        Error::WebSocket(Box::new(err))
+    }
+}
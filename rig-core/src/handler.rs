@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::protocol::{MessageType, ProtocolMessage};
+
+#[async_trait]
+pub trait ProtocolHandler: Send + Sync {
+    async fn handle(&self, message: &ProtocolMessage);
+}
+
+/// Dispatches incoming messages to only the handlers registered for their
+/// `message_type`, rather than fanning every message out to every handler
+/// and making each one check whether it cares.
+#[derive(Default)]
+pub struct ProtocolRouter {
+    handlers: HashMap<MessageType, Vec<Arc<dyn ProtocolHandler>>>,
+}
+
+impl ProtocolRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, message_type: MessageType, handler: Arc<dyn ProtocolHandler>) {
+        self.handlers.entry(message_type).or_default().push(handler);
+    }
+
+    /// Invoke every handler registered for `message.message_type`. Types
+    /// with no registered handler are simply dropped.
+    pub async fn dispatch(&self, message: &ProtocolMessage) {
+        if let Some(handlers) = self.handlers.get(&message.message_type) {
+            for handler in handlers {
+                handler.handle(message).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    struct CountingHandler(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl ProtocolHandler for CountingHandler {
+        async fn handle(&self, _message: &ProtocolMessage) {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[tokio::test]
+    async fn only_matching_handler_is_invoked() {
+        let data_calls = Arc::new(AtomicUsize::new(0));
+        let heartbeat_calls = Arc::new(AtomicUsize::new(0));
+
+        let mut router = ProtocolRouter::new();
+        router.register(MessageType::Data, Arc::new(CountingHandler(data_calls.clone())));
+        router.register(
+            MessageType::Heartbeat,
+            Arc::new(CountingHandler(heartbeat_calls.clone())),
+        );
+
+        router.dispatch(&ProtocolMessage::heartbeat()).await;
+
+        assert_eq!(data_calls.load(Ordering::SeqCst), 0);
+        assert_eq!(heartbeat_calls.load(Ordering::SeqCst), 1);
+    }
+}
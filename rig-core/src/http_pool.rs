@@ -0,0 +1,21 @@
+use std::time::Duration;
+
+/// How long an idle pooled HTTP connection is kept open, and how many idle
+/// connections are kept per host — passed straight through to
+/// [`reqwest::ClientBuilder`]. Shared by every crate in this workspace that
+/// builds its own `reqwest::Client` (`rig-deepseek`'s `ThrottledClient`,
+/// `rig-gateway`'s `Gateway`) so tuning one knob doesn't drift out of sync
+/// with the other.
+#[derive(Debug, Clone, Copy)]
+pub struct HttpPoolConfig {
+    pub idle_timeout: Duration,
+    pub max_idle_per_host: usize,
+}
+
+impl Default for HttpPoolConfig {
+    fn default() -> Self {
+        // Mirrors reqwest's own defaults, named here so callers tuning one
+        // knob don't have to guess what the other currently is.
+        Self { idle_timeout: Duration::from_secs(90), max_idle_per_host: usize::MAX }
+    }
+}
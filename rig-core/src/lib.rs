@@ -0,0 +1,64 @@
+mod agent;
+mod agent_registry;
+mod backoff;
+mod circuit_breaker;
+mod circuit_breaker_registry;
+pub mod compression;
+mod connection;
+mod dead_letter;
+mod error;
+mod handler;
+mod http_pool;
+mod load_balancer;
+mod logging;
+mod memory_profiler;
+pub mod metrics;
+pub mod outbox;
+#[cfg(feature = "otel")]
+pub mod otel;
+mod performance;
+mod pool;
+pub mod protocol;
+mod rate_limiter;
+pub mod retry;
+mod router;
+mod schema;
+mod security;
+mod server;
+pub mod similarity;
+mod tls;
+pub mod wire_format;
+
+pub use agent::{Agent, AgentAction, AgentStatus};
+pub use agent_registry::ConnectedAgent;
+pub use backoff::{backoff_delay, DEFAULT_BASE_DELAY, DEFAULT_MAX_DELAY};
+pub use circuit_breaker::CircuitBreaker;
+pub use circuit_breaker_registry::CircuitBreakerRegistry;
+pub use compression::{CompressionConfig, Codec};
+pub use connection::{Connection, ConnectionStatus, DEFAULT_HEARTBEAT_INTERVAL};
+pub use dead_letter::{retry_dead_letters, DeadLetterEntry, DeadLetterStore, InMemoryDeadLetterStore};
+pub use error::Error;
+pub use handler::{ProtocolHandler, ProtocolRouter};
+pub use http_pool::HttpPoolConfig;
+pub use load_balancer::{BalanceStrategy, BalancerConfig, LoadBalancer};
+pub use logging::{LogCollector, LogLevel, LogQuery, LogRecord, MeapLogger};
+pub use memory_profiler::MemoryProfiler;
+pub use metrics::{ConnectionMetrics, ConnectionStats};
+pub use metrics::exporter::{handle_metrics, render as render_prometheus_metrics, ConnectionLabel, MetricsExporter};
+pub use outbox::{InMemoryOutboxStore, OutboxEntry, OutboxStore};
+pub use performance::PerformanceTracker;
+pub use pool::{ConnectionPool, EvictionPolicy, PoolConfig, PoolStats};
+pub use protocol::{
+    degraded_response, BackendError, ErrorCode, MeapProtocol, MessageType, Protocol, ProtocolMessage, ProtocolVersion,
+    PROTOCOL_VERSION,
+};
+pub use protocol::replay::{RecordedInteraction, RecordingProtocol, ReplayProtocol};
+pub use rate_limiter::{RateLimitConfig, RateLimiter, Strategy};
+pub use retry::{with_backoff, BackoffPolicy};
+pub use router::MessageRouter;
+pub use schema::{validate_content, ContentValidationError};
+pub use security::SecurityManager;
+pub use server::MeapServer;
+pub use similarity::{cosine, dot, euclidean, rerank};
+pub use tls::{client_connector, server_acceptor};
+pub use wire_format::WireFormat;
@@ -0,0 +1,328 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use rand::Rng;
+use tokio::sync::RwLock;
+
+use crate::circuit_breaker_registry::CircuitBreakerRegistry;
+
+/// Number of virtual nodes placed on the hash ring per real backend node,
+/// for [`BalanceStrategy::ConsistentHash`]. More virtual nodes smooth out
+/// the distribution at the cost of a larger ring to search.
+const VIRTUAL_NODES_PER_NODE: usize = 100;
+
+/// Strategy used by [`LoadBalancer`] to pick which backend a new
+/// connection should be routed to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BalanceStrategy {
+    /// Cycle through nodes in order, regardless of load.
+    #[default]
+    RoundRobin,
+    /// Pick a node uniformly at random, regardless of load.
+    Random,
+    /// Pick the node with the fewest active connections, breaking ties by
+    /// node order. Needs each node's live connection count, unlike the
+    /// other strategies.
+    LeastConnections,
+    /// Hash the routing key (e.g. an agent id) onto a ring of backends, so
+    /// the same key always lands on the same node and only the keys that
+    /// land on an added/removed node's virtual nodes ever remap. Used for
+    /// session affinity with stateful agents.
+    ConsistentHash,
+}
+
+/// Configuration for a [`LoadBalancer`]: which strategy to pick nodes
+/// with, and optionally a [`CircuitBreakerRegistry`] to route around
+/// currently-failing nodes regardless of strategy.
+#[derive(Clone, Default)]
+pub struct BalancerConfig {
+    pub strategy: BalanceStrategy,
+    pub circuit_breakers: Option<CircuitBreakerRegistry>,
+}
+
+impl BalancerConfig {
+    pub fn new(strategy: BalanceStrategy) -> Self {
+        Self {
+            strategy,
+            circuit_breakers: None,
+        }
+    }
+
+    /// Skip nodes whose breaker in `registry` is open when picking,
+    /// falling back to every node (even open ones) only if that would
+    /// otherwise leave zero candidates.
+    pub fn with_circuit_breakers(mut self, registry: CircuitBreakerRegistry) -> Self {
+        self.circuit_breakers = Some(registry);
+        self
+    }
+}
+
+fn hash_key(key: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+struct HashRing {
+    ring: BTreeMap<u64, String>,
+}
+
+impl HashRing {
+    fn build(nodes: &[String]) -> Self {
+        let mut ring = BTreeMap::new();
+        for node in nodes {
+            for i in 0..VIRTUAL_NODES_PER_NODE {
+                ring.insert(hash_key(&format!("{node}#{i}")), node.clone());
+            }
+        }
+        Self { ring }
+    }
+
+    /// Walk clockwise from `key`'s position to the nearest virtual node,
+    /// wrapping around to the start of the ring if `key` hashes past the
+    /// last one.
+    fn node_for(&self, key: &str) -> Option<&str> {
+        let hash = hash_key(key);
+        self.ring
+            .range(hash..)
+            .next()
+            .or_else(|| self.ring.iter().next())
+            .map(|(_, node)| node.as_str())
+    }
+}
+
+/// Picks a backend node for each new connection out of a dynamic set of
+/// candidates, according to a [`BalancerConfig`].
+#[derive(Clone)]
+pub struct LoadBalancer {
+    config: BalancerConfig,
+    nodes: Arc<RwLock<Vec<String>>>,
+    round_robin_cursor: Arc<AtomicUsize>,
+}
+
+impl LoadBalancer {
+    pub fn new(nodes: Vec<String>, config: BalancerConfig) -> Self {
+        Self {
+            config,
+            nodes: Arc::new(RwLock::new(nodes)),
+            round_robin_cursor: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Add `node` to the pool of candidates.
+    pub async fn add_node(&self, node: String) {
+        self.nodes.write().await.push(node);
+    }
+
+    /// Remove `node` from the pool of candidates. With
+    /// [`BalanceStrategy::ConsistentHash`], only the keys that happened to
+    /// land on `node`'s virtual nodes remap; every other key's pick is
+    /// unaffected.
+    pub async fn remove_node(&self, node: &str) {
+        self.nodes.write().await.retain(|n| n != node);
+    }
+
+    /// Pick the next node for a connection with no natural routing key.
+    /// [`BalanceStrategy::ConsistentHash`] has no key to hash here, so it
+    /// falls back to hashing an empty key — callers that want affinity
+    /// should use [`Self::next_node_for_key`] instead.
+    pub async fn next_node(&self, live_connections: &HashMap<String, usize>) -> Option<String> {
+        self.pick(None, live_connections).await
+    }
+
+    /// Pick the next node for a connection, using `key` (e.g. an agent
+    /// id) for [`BalanceStrategy::ConsistentHash`] affinity. Other
+    /// strategies ignore `key`.
+    pub async fn next_node_for_key(&self, key: &str) -> Option<String> {
+        self.pick(Some(key), &HashMap::new()).await
+    }
+
+    async fn pick(&self, key: Option<&str>, live_connections: &HashMap<String, usize>) -> Option<String> {
+        let nodes = self.nodes.read().await;
+        if nodes.is_empty() {
+            return None;
+        }
+
+        let candidates = self.healthy_candidates(&nodes).await;
+
+        match self.config.strategy {
+            BalanceStrategy::RoundRobin => {
+                let index = self.round_robin_cursor.fetch_add(1, Ordering::SeqCst) % candidates.len();
+                Some(candidates[index].clone())
+            }
+            BalanceStrategy::Random => {
+                let index = rand::thread_rng().gen_range(0..candidates.len());
+                Some(candidates[index].clone())
+            }
+            BalanceStrategy::LeastConnections => candidates
+                .iter()
+                .min_by_key(|node| live_connections.get(node.as_str()).copied().unwrap_or(0))
+                .cloned(),
+            BalanceStrategy::ConsistentHash => {
+                HashRing::build(&candidates).node_for(key.unwrap_or_default()).map(str::to_string)
+            }
+        }
+    }
+
+    /// Nodes whose circuit breaker currently permits calls, falling back
+    /// to every node if the registry would otherwise leave zero
+    /// candidates (every node open). With no registry configured, every
+    /// node is a candidate.
+    async fn healthy_candidates(&self, nodes: &[String]) -> Vec<String> {
+        let Some(registry) = &self.config.circuit_breakers else {
+            return nodes.to_vec();
+        };
+
+        let mut healthy = Vec::new();
+        for node in nodes {
+            if registry.is_available(node).await {
+                healthy.push(node.clone());
+            }
+        }
+
+        if healthy.is_empty() {
+            nodes.to_vec()
+        } else {
+            healthy
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn round_robin_cycles_through_every_node_before_repeating() {
+        let balancer = LoadBalancer::new(
+            vec!["a".into(), "b".into(), "c".into()],
+            BalancerConfig::new(BalanceStrategy::RoundRobin),
+        );
+        let live = HashMap::new();
+
+        let mut picks = Vec::new();
+        for _ in 0..4 {
+            picks.push(balancer.next_node(&live).await.unwrap());
+        }
+        assert_eq!(picks, ["a", "b", "c", "a"]);
+    }
+
+    #[tokio::test]
+    async fn least_connections_picks_the_idle_node_over_a_busy_one() {
+        let balancer = LoadBalancer::new(
+            vec!["busy".into(), "idle".into()],
+            BalancerConfig::new(BalanceStrategy::LeastConnections),
+        );
+        let live = HashMap::from([("busy".to_string(), 2)]);
+
+        assert_eq!(balancer.next_node(&live).await, Some("idle".to_string()));
+    }
+
+    #[tokio::test]
+    async fn least_connections_falls_back_to_zero_for_nodes_missing_from_the_live_map() {
+        let balancer = LoadBalancer::new(
+            vec!["a".into(), "b".into()],
+            BalancerConfig::new(BalanceStrategy::LeastConnections),
+        );
+        let live = HashMap::from([("a".to_string(), 1)]);
+
+        assert_eq!(balancer.next_node(&live).await, Some("b".to_string()));
+    }
+
+    #[tokio::test]
+    async fn an_empty_node_list_yields_no_pick() {
+        let balancer = LoadBalancer::new(Vec::new(), BalancerConfig::new(BalanceStrategy::RoundRobin));
+        assert_eq!(balancer.next_node(&HashMap::new()).await, None);
+    }
+
+    #[tokio::test]
+    async fn consistent_hash_maps_the_same_key_to_the_same_node_every_time() {
+        let balancer = LoadBalancer::new(
+            vec!["node-a".into(), "node-b".into(), "node-c".into()],
+            BalancerConfig::new(BalanceStrategy::ConsistentHash),
+        );
+
+        let first = balancer.next_node_for_key("agent-42").await;
+        for _ in 0..10 {
+            assert_eq!(balancer.next_node_for_key("agent-42").await, first);
+        }
+    }
+
+    #[tokio::test]
+    async fn removing_a_node_only_remaps_a_fraction_of_keys() {
+        let nodes: Vec<String> = (0..4).map(|i| format!("node-{i}")).collect();
+        let balancer = LoadBalancer::new(nodes, BalancerConfig::new(BalanceStrategy::ConsistentHash));
+
+        let keys: Vec<String> = (0..200).map(|i| format!("agent-{i}")).collect();
+        let mut before = HashMap::new();
+        for key in &keys {
+            before.insert(key.clone(), balancer.next_node_for_key(key).await);
+        }
+
+        balancer.remove_node("node-0").await;
+
+        let mut remapped = 0;
+        for key in &keys {
+            if balancer.next_node_for_key(key).await != before[key] {
+                remapped += 1;
+            }
+        }
+
+        // With 4 nodes, removing one should remap roughly a quarter of
+        // keys (exactly those that landed on its virtual nodes), not all
+        // of them.
+        assert!(remapped > 0, "removing a node should remap at least some keys");
+        assert!(
+            remapped < keys.len() / 2,
+            "removing one of four nodes remapped {remapped}/{} keys, expected well under half",
+            keys.len()
+        );
+    }
+
+    #[tokio::test]
+    async fn traffic_avoids_an_open_breaker_until_it_recovers() {
+        let registry = CircuitBreakerRegistry::new();
+        for _ in 0..5 {
+            registry.record_failure("node-b").await;
+        }
+
+        let balancer = LoadBalancer::new(
+            vec!["node-a".into(), "node-b".into(), "node-c".into()],
+            BalancerConfig::new(BalanceStrategy::RoundRobin).with_circuit_breakers(registry.clone()),
+        );
+
+        let live = HashMap::new();
+        for _ in 0..10 {
+            assert_ne!(balancer.next_node(&live).await, Some("node-b".to_string()));
+        }
+
+        registry.record_success("node-b").await;
+        let mut saw_recovered_node = false;
+        for _ in 0..10 {
+            if balancer.next_node(&live).await == Some("node-b".to_string()) {
+                saw_recovered_node = true;
+            }
+        }
+        assert!(saw_recovered_node, "node-b should be picked again once its breaker recovers");
+    }
+
+    #[tokio::test]
+    async fn every_breaker_open_falls_back_to_routing_anyway() {
+        let registry = CircuitBreakerRegistry::new();
+        for node in ["node-a", "node-b"] {
+            for _ in 0..5 {
+                registry.record_failure(node).await;
+            }
+        }
+
+        let balancer = LoadBalancer::new(
+            vec!["node-a".into(), "node-b".into()],
+            BalancerConfig::new(BalanceStrategy::RoundRobin).with_circuit_breakers(registry),
+        );
+
+        assert!(balancer.next_node(&HashMap::new()).await.is_some());
+    }
+}
@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+/// A single structured log line, as both written to the file sink and kept
+/// in-memory for [`LogCollector::query`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogRecord {
+    pub timestamp_ms: u64,
+    pub level: LogLevel,
+    pub target: String,
+    pub message: String,
+    /// Free-form structured context, e.g. a `trace_id` propagated from the
+    /// inbound request so a correlation id can be grepped straight out of
+    /// the log file. Defaults to empty so records logged before this field
+    /// existed still deserialize.
+    #[serde(default)]
+    pub fields: HashMap<String, String>,
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// In-memory index of recently logged records, queryable by target and
+/// time range so operators can inspect recent activity without grepping
+/// the log file.
+#[derive(Clone, Default)]
+pub struct LogCollector {
+    records: Arc<Mutex<Vec<LogRecord>>>,
+}
+
+impl LogCollector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&self, record: LogRecord) {
+        self.records.lock().unwrap().push(record);
+    }
+
+    /// Return every collected record matching every dimension set on
+    /// `filter` (AND semantics) — dimensions left `None` are ignored.
+    pub fn query(&self, filter: LogQuery) -> Vec<LogRecord> {
+        self.records
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| filter.level.map(|l| r.level == l).unwrap_or(true))
+            .filter(|r| {
+                filter
+                    .target_contains
+                    .as_deref()
+                    .map(|needle| r.target.contains(needle))
+                    .unwrap_or(true)
+            })
+            .filter(|r| filter.since.map(|s| r.timestamp_ms >= s).unwrap_or(true))
+            .filter(|r| filter.until.map(|u| r.timestamp_ms <= u).unwrap_or(true))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Filter dimensions for [`LogCollector::query`], combined with AND
+/// semantics. Every field defaults to `None`, meaning "don't filter on
+/// this dimension".
+#[derive(Debug, Clone, Default)]
+pub struct LogQuery {
+    pub level: Option<LogLevel>,
+    pub target_contains: Option<String>,
+    pub since: Option<u64>,
+    pub until: Option<u64>,
+}
+
+/// Structured JSON logger with a rotating file sink and an in-memory
+/// [`LogCollector`] for querying recent activity.
+#[derive(Clone)]
+pub struct MeapLogger {
+    collector: LogCollector,
+    min_level: LogLevel,
+    writer: Arc<Mutex<tracing_appender::non_blocking::NonBlocking>>,
+}
+
+impl MeapLogger {
+    /// Create a logger that writes daily-rotated JSON files under
+    /// `directory`, dropping anything below `min_level` before it reaches
+    /// either the file sink or the in-memory collector.
+    ///
+    /// The returned [`WorkerGuard`] must be kept alive for the process
+    /// lifetime or buffered writes may be lost on exit.
+    pub fn with_file_sink(directory: impl AsRef<std::path::Path>, min_level: LogLevel) -> (Self, WorkerGuard) {
+        let appender: RollingFileAppender = tracing_appender::rolling::Builder::new()
+            .rotation(Rotation::DAILY)
+            .filename_prefix("meap")
+            .filename_suffix("log")
+            .build(directory)
+            .expect("failed to build rolling file appender");
+        let (writer, guard) = tracing_appender::non_blocking(appender);
+
+        (
+            Self {
+                collector: LogCollector::new(),
+                min_level,
+                writer: Arc::new(Mutex::new(writer)),
+            },
+            guard,
+        )
+    }
+
+    pub fn collector(&self) -> &LogCollector {
+        &self.collector
+    }
+
+    pub fn set_min_level(&mut self, min_level: LogLevel) {
+        self.min_level = min_level;
+    }
+
+    pub fn log(&self, level: LogLevel, target: &str, message: impl Into<String>) {
+        self.log_with_fields(level, target, message, HashMap::new());
+    }
+
+    /// Like [`Self::log`], but attaches `fields` (e.g. a `trace_id`
+    /// propagated from the originating request) to the emitted record.
+    pub fn log_with_fields(
+        &self,
+        level: LogLevel,
+        target: &str,
+        message: impl Into<String>,
+        fields: HashMap<String, String>,
+    ) {
+        if level < self.min_level {
+            return;
+        }
+
+        let record = LogRecord {
+            timestamp_ms: now_ms(),
+            level,
+            target: target.to_string(),
+            message: message.into(),
+            fields,
+        };
+        self.collector.push(record.clone());
+
+        if let Ok(line) = serde_json::to_string(&record) {
+            let mut writer = self.writer.lock().unwrap();
+            let _ = writeln!(writer, "{line}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn logged_records_are_collected_for_query() {
+        let (logger, _guard) = MeapLogger::with_file_sink(std::env::temp_dir(), LogLevel::Trace);
+        logger.log(LogLevel::Info, "agent.connect", "agent-1 connected");
+
+        let records = logger.collector().query(LogQuery::default());
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].message, "agent-1 connected");
+    }
+
+    #[test]
+    fn entries_below_min_level_are_dropped() {
+        let (logger, _guard) = MeapLogger::with_file_sink(std::env::temp_dir(), LogLevel::Warn);
+        logger.log(LogLevel::Debug, "agent.connect", "ignored");
+        logger.log(LogLevel::Error, "agent.connect", "kept");
+
+        let records = logger.collector().query(LogQuery::default());
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].message, "kept");
+    }
+
+    #[test]
+    fn a_trace_id_logged_with_a_message_appears_in_the_collected_record() {
+        let (logger, _guard) = MeapLogger::with_file_sink(std::env::temp_dir(), LogLevel::Trace);
+        let trace_id = "req-abc-123".to_string();
+
+        let mut fields = HashMap::new();
+        fields.insert("trace_id".to_string(), trace_id.clone());
+        logger.log_with_fields(LogLevel::Info, "rig.gateway", "inbound request handled", fields);
+
+        let records = logger.collector().query(LogQuery::default());
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].fields.get("trace_id"), Some(&trace_id));
+    }
+
+    fn seed() -> LogCollector {
+        let collector = LogCollector::new();
+        collector.push(LogRecord {
+            timestamp_ms: 100,
+            level: LogLevel::Info,
+            target: "rig.gateway".into(),
+            message: "a".into(),
+            fields: HashMap::new(),
+        });
+        collector.push(LogRecord {
+            timestamp_ms: 200,
+            level: LogLevel::Warn,
+            target: "rig.broker".into(),
+            message: "b".into(),
+            fields: HashMap::new(),
+        });
+        collector
+    }
+
+    #[test]
+    fn query_filters_by_target_substring() {
+        let matches = seed().query(LogQuery {
+            target_contains: Some("gateway".into()),
+            ..Default::default()
+        });
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].message, "a");
+    }
+
+    #[test]
+    fn query_filters_by_time_range() {
+        let matches = seed().query(LogQuery {
+            since: Some(150),
+            until: Some(250),
+            ..Default::default()
+        });
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].message, "b");
+    }
+
+    #[test]
+    fn query_filters_by_level() {
+        let matches = seed().query(LogQuery {
+            level: Some(LogLevel::Warn),
+            ..Default::default()
+        });
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].message, "b");
+    }
+
+    #[test]
+    fn query_combines_filters_with_and_semantics() {
+        let matches = seed().query(LogQuery {
+            target_contains: Some("gateway".into()),
+            level: Some(LogLevel::Warn),
+            ..Default::default()
+        });
+        assert!(matches.is_empty());
+    }
+}
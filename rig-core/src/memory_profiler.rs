@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Tracks approximate outstanding memory attributable to pool resources
+/// (connection buffers, etc.), bucketed by allocation size. This is a
+/// coarse accounting tool for dashboards, not a precise allocator.
+#[derive(Clone, Default)]
+pub struct MemoryProfiler {
+    /// Outstanding allocation count per size class.
+    live_by_size: Arc<Mutex<HashMap<usize, u64>>>,
+    live_bytes: Arc<AtomicUsize>,
+}
+
+impl MemoryProfiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record_alloc(&self, size: usize) {
+        let mut live = self.live_by_size.lock().unwrap();
+        *live.entry(size).or_insert(0) += 1;
+        self.live_bytes.fetch_add(size, Ordering::Relaxed);
+    }
+
+    /// Record that one allocation of `size` was freed. Multiple live
+    /// allocations commonly collide on the same size (e.g. every
+    /// connection's read buffer is the same estimated size), so this must
+    /// retire exactly one of them rather than wiping out the whole bucket
+    /// — removing the bucket outright would silently "free" every other
+    /// still-live allocation of that size.
+    pub fn record_free(&self, size: usize) {
+        let mut live = self.live_by_size.lock().unwrap();
+        if let Some(count) = live.get_mut(&size) {
+            *count -= 1;
+            if *count == 0 {
+                live.remove(&size);
+            }
+            self.live_bytes.fetch_sub(size, Ordering::Relaxed);
+        }
+    }
+
+    pub fn live_bytes(&self) -> usize {
+        self.live_bytes.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn freeing_one_of_two_same_size_allocations_keeps_the_other_tracked() {
+        let profiler = MemoryProfiler::new();
+        profiler.record_alloc(1024);
+        profiler.record_alloc(1024);
+        assert_eq!(profiler.live_bytes(), 2048);
+
+        profiler.record_free(1024);
+        assert_eq!(profiler.live_bytes(), 1024);
+
+        profiler.record_free(1024);
+        assert_eq!(profiler.live_bytes(), 0);
+    }
+
+    #[test]
+    fn freeing_an_untracked_size_is_a_no_op() {
+        let profiler = MemoryProfiler::new();
+        profiler.record_free(4096);
+        assert_eq!(profiler.live_bytes(), 0);
+    }
+}
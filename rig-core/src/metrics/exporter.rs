@@ -0,0 +1,150 @@
+use std::fmt::Write as _;
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+
+use super::ConnectionMetrics;
+use crate::performance::PerformanceTracker;
+use crate::pool::ConnectionPool;
+
+/// One connection's metrics, labeled for export.
+pub struct ConnectionLabel<'a> {
+    pub connection_id: &'a str,
+    pub service: &'a str,
+    pub metrics: &'a ConnectionMetrics,
+}
+
+/// Shared state for the `GET /metrics` handler: whatever connection
+/// metrics and latency tracker the caller wants scraped.
+#[derive(Clone)]
+pub struct MetricsExporter {
+    service: Arc<str>,
+    tracker: Arc<PerformanceTracker>,
+    pool: ConnectionPool,
+}
+
+impl MetricsExporter {
+    pub fn new(service: impl Into<Arc<str>>, tracker: Arc<PerformanceTracker>, pool: ConnectionPool) -> Self {
+        Self {
+            service: service.into(),
+            tracker,
+            pool,
+        }
+    }
+}
+
+/// Render `connections` and `tracker` in Prometheus text exposition
+/// format. Connection counters get a `connection`/`service` label pair;
+/// latency percentiles get a `quantile` label, matching the summary
+/// convention Prometheus client libraries use.
+pub fn render(connections: &[ConnectionLabel<'_>], tracker: &PerformanceTracker) -> String {
+    let mut out = String::new();
+
+    writeln!(out, "# HELP meap_connection_messages_sent Messages sent on a connection.").ok();
+    writeln!(out, "# TYPE meap_connection_messages_sent counter").ok();
+    for conn in connections {
+        writeln!(
+            out,
+            "meap_connection_messages_sent{{connection=\"{}\",service=\"{}\"}} {}",
+            conn.connection_id,
+            conn.service,
+            conn.metrics.messages_sent()
+        )
+        .ok();
+    }
+
+    writeln!(out, "# HELP meap_connection_bytes_sent Bytes sent on a connection.").ok();
+    writeln!(out, "# TYPE meap_connection_bytes_sent counter").ok();
+    for conn in connections {
+        writeln!(
+            out,
+            "meap_connection_bytes_sent{{connection=\"{}\",service=\"{}\"}} {}",
+            conn.connection_id,
+            conn.service,
+            conn.metrics.bytes_sent()
+        )
+        .ok();
+    }
+
+    writeln!(out, "# HELP meap_latency_seconds Request latency percentiles.").ok();
+    writeln!(out, "# TYPE meap_latency_seconds summary").ok();
+    for (quantile, label) in [(50.0, "0.5"), (90.0, "0.9"), (99.0, "0.99")] {
+        if let Some(latency) = tracker.percentile(quantile) {
+            writeln!(out, "meap_latency_seconds{{quantile=\"{label}\"}} {}", latency.as_secs_f64()).ok();
+        }
+    }
+
+    out
+}
+
+/// `GET /metrics` handler. Exports both the latency tracker and every
+/// connection currently held by [`MetricsExporter`]'s pool.
+pub async fn handle_metrics(State(exporter): State<MetricsExporter>) -> Response {
+    let connections = exporter.pool.connections().await;
+    let connection_ids: Vec<String> = connections.iter().map(|conn| conn.id.to_string()).collect();
+    let labels: Vec<ConnectionLabel> = connections
+        .iter()
+        .zip(connection_ids.iter())
+        .map(|(conn, id)| ConnectionLabel {
+            connection_id: id,
+            service: &exporter.service,
+            metrics: &conn.metrics,
+        })
+        .collect();
+
+    let body = render(&labels, &exporter.tracker);
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], body).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn render_includes_expected_metric_names_and_parseable_values() {
+        let metrics = ConnectionMetrics::default();
+        metrics.record_sent(128);
+        let tracker = PerformanceTracker::new(10);
+        tracker.record(Duration::from_millis(50));
+
+        let output = render(
+            &[ConnectionLabel {
+                connection_id: "conn-1",
+                service: "gateway",
+                metrics: &metrics,
+            }],
+            &tracker,
+        );
+
+        let sent_line = output
+            .lines()
+            .find(|line| line.starts_with("meap_connection_messages_sent{connection=\"conn-1\""))
+            .expect("messages_sent line present");
+        let value: u64 = sent_line.rsplit(' ').next().unwrap().parse().expect("parseable value");
+        assert_eq!(value, 1);
+
+        assert!(output.contains("meap_latency_seconds{quantile=\"0.5\"}"));
+    }
+
+    #[tokio::test]
+    async fn handle_metrics_reports_real_pooled_connections() {
+        use crate::connection::Connection;
+
+        let pool = ConnectionPool::new();
+        let connection = Connection::spawn(futures::sink::drain());
+        connection.metrics.record_sent(42);
+        let id = connection.id;
+        pool.add_connection(connection).await.unwrap();
+
+        let exporter = MetricsExporter::new("gateway", Arc::new(PerformanceTracker::new(10)), pool);
+        let response = handle_metrics(State(exporter)).await;
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+
+        assert!(text.contains(&format!("connection=\"{id}\"")));
+    }
+}
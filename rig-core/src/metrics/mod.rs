@@ -0,0 +1,187 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+pub mod exporter;
+
+/// Lightweight, lock-free counters tracked per [`crate::Connection`].
+/// Latency samples are the one piece of state that needs a lock, since
+/// computing a percentile needs the whole sample set rather than a single
+/// running number.
+#[derive(Clone, Default)]
+pub struct ConnectionMetrics {
+    messages_sent: Arc<AtomicU64>,
+    messages_received: Arc<AtomicU64>,
+    bytes_sent: Arc<AtomicU64>,
+    bytes_received: Arc<AtomicU64>,
+    latencies_micros: Arc<Mutex<Vec<u64>>>,
+    bytes_before_compression: Arc<AtomicU64>,
+    bytes_after_compression: Arc<AtomicU64>,
+}
+
+/// Point-in-time snapshot of a [`ConnectionMetrics`], for reporting or
+/// logging without holding a reference to the live connection.
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionStats {
+    pub messages_sent: u64,
+    pub messages_received: u64,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub average_latency: Option<Duration>,
+    pub p99_latency: Option<Duration>,
+    pub bytes_before_compression: u64,
+    pub bytes_after_compression: u64,
+    /// `bytes_after_compression / bytes_before_compression`: below `1.0`
+    /// means compression is saving bytes, closer to `0.0` means it's
+    /// saving more. `None` until at least one compressed frame has been
+    /// sent, rather than misleadingly reporting `0.0`.
+    pub compression_ratio: Option<f64>,
+}
+
+impl ConnectionMetrics {
+    pub fn record_sent(&self, bytes: usize) {
+        self.messages_sent.fetch_add(1, Ordering::Relaxed);
+        self.bytes_sent.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub fn record_received(&self, bytes: usize) {
+        self.messages_received.fetch_add(1, Ordering::Relaxed);
+        self.bytes_received.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+
+    pub fn messages_sent(&self) -> u64 {
+        self.messages_sent.load(Ordering::Relaxed)
+    }
+
+    pub fn messages_received(&self) -> u64 {
+        self.messages_received.load(Ordering::Relaxed)
+    }
+
+    pub fn bytes_sent(&self) -> u64 {
+        self.bytes_sent.load(Ordering::Relaxed)
+    }
+
+    pub fn bytes_received(&self) -> u64 {
+        self.bytes_received.load(Ordering::Relaxed)
+    }
+
+    /// Record a round-trip latency sample (e.g. time between sending a
+    /// request and receiving its response).
+    pub fn record_latency(&self, latency: Duration) {
+        self.latencies_micros.lock().unwrap().push(latency.as_micros() as u64);
+    }
+
+    pub fn latency_samples(&self) -> Vec<Duration> {
+        self.latencies_micros.lock().unwrap().iter().map(|&micros| Duration::from_micros(micros)).collect()
+    }
+
+    pub fn average_latency(&self) -> Option<Duration> {
+        average_latency(&self.latency_samples())
+    }
+
+    pub fn p99_latency(&self) -> Option<Duration> {
+        percentile_latency(&self.latency_samples(), 0.99)
+    }
+
+    /// Record a frame's size before and after compression, for
+    /// [`ConnectionStats::compression_ratio`]. Only called when
+    /// compression is actually configured and applied.
+    pub fn record_compression(&self, before: usize, after: usize) {
+        self.bytes_before_compression.fetch_add(before as u64, Ordering::Relaxed);
+        self.bytes_after_compression.fetch_add(after as u64, Ordering::Relaxed);
+    }
+
+    pub fn bytes_before_compression(&self) -> u64 {
+        self.bytes_before_compression.load(Ordering::Relaxed)
+    }
+
+    pub fn bytes_after_compression(&self) -> u64 {
+        self.bytes_after_compression.load(Ordering::Relaxed)
+    }
+
+    /// Build a point-in-time [`ConnectionStats`] snapshot.
+    pub fn stats(&self) -> ConnectionStats {
+        let bytes_before_compression = self.bytes_before_compression();
+        let bytes_after_compression = self.bytes_after_compression();
+        ConnectionStats {
+            messages_sent: self.messages_sent(),
+            messages_received: self.messages_received(),
+            bytes_sent: self.bytes_sent(),
+            bytes_received: self.bytes_received(),
+            average_latency: self.average_latency(),
+            p99_latency: self.p99_latency(),
+            bytes_before_compression,
+            bytes_after_compression,
+            compression_ratio: (bytes_before_compression > 0)
+                .then(|| bytes_after_compression as f64 / bytes_before_compression as f64),
+        }
+    }
+}
+
+/// Mean of `samples`, or `None` if empty.
+pub fn average_latency(samples: &[Duration]) -> Option<Duration> {
+    if samples.is_empty() {
+        return None;
+    }
+    Some(samples.iter().sum::<Duration>() / samples.len() as u32)
+}
+
+/// Nearest-rank percentile (`0.0..=1.0`) of `samples`, or `None` if empty.
+pub fn percentile_latency(samples: &[Duration], percentile: f64) -> Option<Duration> {
+    if samples.is_empty() {
+        return None;
+    }
+    let mut sorted = samples.to_vec();
+    sorted.sort();
+    let index = (((sorted.len() - 1) as f64) * percentile).round() as usize;
+    sorted.get(index).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn average_and_p99_latency_are_none_for_an_idle_connection() {
+        let metrics = ConnectionMetrics::default();
+        assert_eq!(metrics.average_latency(), None);
+        assert_eq!(metrics.p99_latency(), None);
+    }
+
+    #[test]
+    fn average_latency_is_the_mean_of_recorded_samples() {
+        let metrics = ConnectionMetrics::default();
+        metrics.record_latency(Duration::from_millis(10));
+        metrics.record_latency(Duration::from_millis(20));
+        assert_eq!(metrics.average_latency(), Some(Duration::from_millis(15)));
+    }
+
+    #[test]
+    fn compression_ratio_is_none_until_a_compressed_frame_is_recorded() {
+        let metrics = ConnectionMetrics::default();
+        assert_eq!(metrics.stats().compression_ratio, None);
+    }
+
+    #[test]
+    fn compression_ratio_is_the_running_after_over_before_fraction() {
+        let metrics = ConnectionMetrics::default();
+        metrics.record_compression(1000, 400);
+        metrics.record_compression(1000, 200);
+
+        let stats = metrics.stats();
+        assert_eq!(stats.bytes_before_compression, 2000);
+        assert_eq!(stats.bytes_after_compression, 600);
+        assert_eq!(stats.compression_ratio, Some(0.3));
+    }
+
+    #[test]
+    fn p99_latency_is_near_the_tail_of_a_large_sample_set() {
+        let metrics = ConnectionMetrics::default();
+        for ms in 1..=100 {
+            metrics.record_latency(Duration::from_millis(ms));
+        }
+        // Nearest-rank index for p99 of 100 samples is round(99 * 0.99) = 98,
+        // i.e. the 99th-smallest sample (1-indexed), not the very last one.
+        assert_eq!(metrics.p99_latency(), Some(Duration::from_millis(99)));
+    }
+}
@@ -0,0 +1,78 @@
+//! Optional OpenTelemetry span export, enabled by the `otel` cargo feature
+//! so crates that don't want the dependency weight (`opentelemetry*`,
+//! `tracing-opentelemetry`) never pull it in. Everything already emits
+//! `tracing` spans (see [`crate::connection::Connection::send`]); this
+//! module only adds a layer that exports them over OTLP.
+
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::TracerProvider;
+use opentelemetry_sdk::{runtime, Resource};
+use tracing_subscriber::layer::SubscriberExt;
+
+/// Where to export spans, and what service name to tag them with.
+#[derive(Debug, Clone)]
+pub struct TracingConfig {
+    pub otlp_endpoint: String,
+    pub service_name: String,
+}
+
+impl TracingConfig {
+    pub fn new(otlp_endpoint: impl Into<String>, service_name: impl Into<String>) -> Self {
+        Self {
+            otlp_endpoint: otlp_endpoint.into(),
+            service_name: service_name.into(),
+        }
+    }
+}
+
+/// Build an OTLP trace pipeline for `config` and install it as a layer on
+/// the global `tracing` subscriber, so every existing `#[tracing::instrument]`
+/// span is exported from here on. Returns the [`TracerProvider`] so the
+/// caller can flush/shut it down on exit.
+pub fn init(config: &TracingConfig) -> Result<TracerProvider, opentelemetry::trace::TraceError> {
+    let provider = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(config.otlp_endpoint.clone()),
+        )
+        .with_trace_config(opentelemetry_sdk::trace::config().with_resource(Resource::new(vec![
+            KeyValue::new("service.name", config.service_name.clone()),
+        ])))
+        .install_batch(runtime::Tokio)?;
+
+    let tracer = provider.tracer(config.service_name.clone());
+    let subscriber = tracing_subscriber::Registry::default().with(tracing_opentelemetry::layer().with_tracer(tracer));
+    let _ = tracing::subscriber::set_global_default(subscriber);
+
+    Ok(provider)
+}
+
+#[cfg(test)]
+mod tests {
+    use opentelemetry::trace::{Tracer, TracerProvider as _};
+    use opentelemetry_sdk::testing::trace::InMemorySpanExporter;
+    use opentelemetry_sdk::trace::TracerProvider;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn a_span_created_under_the_installed_layer_reaches_the_exporter() {
+        let exporter = InMemorySpanExporter::default();
+        let provider = TracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let tracer = provider.tracer("test");
+        let subscriber = tracing_subscriber::Registry::default().with(tracing_opentelemetry::layer().with_tracer(tracer));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("connection.send");
+            let _entered = span.enter();
+        });
+        provider.force_flush();
+
+        assert_eq!(exporter.get_finished_spans().unwrap().len(), 1);
+    }
+}
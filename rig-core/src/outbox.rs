@@ -0,0 +1,77 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A send captured before it goes out over the wire, so a crash between
+/// [`crate::Agent::send_message`] accepting the call and the network write
+/// actually landing doesn't silently drop it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxEntry {
+    pub id: Uuid,
+    pub target: Uuid,
+    pub action: String,
+    pub payload: serde_json::Value,
+}
+
+/// Durable record of outbound messages an [`crate::Agent`] hasn't confirmed
+/// delivered yet. Implementations only need to persist/list/clear entries
+/// — replay logic lives in [`crate::Agent::replay_outbox`].
+#[async_trait]
+pub trait OutboxStore: Send + Sync {
+    /// Record `entry` as not-yet-delivered.
+    async fn persist(&self, entry: OutboxEntry);
+
+    /// Remove `id`, once its send has been confirmed.
+    async fn mark_delivered(&self, id: Uuid);
+
+    /// Snapshot every entry that hasn't been marked delivered.
+    async fn undelivered(&self) -> Vec<OutboxEntry>;
+}
+
+/// Default in-memory [`OutboxStore`]. Entries don't survive a process
+/// restart — use a persistent implementation (e.g. `rig-sqlite`'s) where
+/// that matters.
+#[derive(Clone, Default)]
+pub struct InMemoryOutboxStore {
+    entries: Arc<Mutex<HashMap<Uuid, OutboxEntry>>>,
+}
+
+impl InMemoryOutboxStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl OutboxStore for InMemoryOutboxStore {
+    async fn persist(&self, entry: OutboxEntry) {
+        self.entries.lock().unwrap().insert(entry.id, entry);
+    }
+
+    async fn mark_delivered(&self, id: Uuid) {
+        self.entries.lock().unwrap().remove(&id);
+    }
+
+    async fn undelivered(&self) -> Vec<OutboxEntry> {
+        self.entries.lock().unwrap().values().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn persisted_entries_are_undelivered_until_marked() {
+        let store = InMemoryOutboxStore::new();
+        let entry = OutboxEntry { id: Uuid::new_v4(), target: Uuid::new_v4(), action: "chat".to_string(), payload: serde_json::json!({}) };
+        store.persist(entry.clone()).await;
+
+        assert_eq!(store.undelivered().await.len(), 1);
+        store.mark_delivered(entry.id).await;
+        assert!(store.undelivered().await.is_empty());
+    }
+}
@@ -0,0 +1,90 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Tracks recent operation latencies in a bounded sliding window and
+/// reports percentiles over them.
+pub struct PerformanceTracker {
+    window: Mutex<VecDeque<Duration>>,
+    capacity: usize,
+}
+
+impl PerformanceTracker {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            window: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        }
+    }
+
+    pub fn record(&self, latency: Duration) {
+        let mut window = self.window.lock().unwrap();
+        if window.len() == self.capacity {
+            window.pop_front();
+        }
+        window.push_back(latency);
+    }
+
+    /// The `p`th percentile (0.0..=100.0) of the current window, using
+    /// nearest-rank interpolation. Returns `None` on an empty window
+    /// rather than dividing by a zero-length sample.
+    pub fn percentile(&self, p: f64) -> Option<Duration> {
+        let window = self.window.lock().unwrap();
+        if window.is_empty() {
+            return None;
+        }
+
+        let mut sorted: Vec<Duration> = window.iter().copied().collect();
+        sorted.sort();
+
+        let rank = (p / 100.0 * (sorted.len() - 1) as f64).round() as usize;
+        let rank = rank.min(sorted.len() - 1);
+        Some(sorted[rank])
+    }
+
+    pub fn len(&self) -> usize {
+        self.window.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.window.lock().unwrap().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentile_on_empty_window_is_none() {
+        let tracker = PerformanceTracker::new(10);
+        assert_eq!(tracker.percentile(50.0), None);
+    }
+
+    #[test]
+    fn p100_is_the_maximum_observed_latency() {
+        let tracker = PerformanceTracker::new(10);
+        for ms in [10, 50, 20, 100, 30] {
+            tracker.record(Duration::from_millis(ms));
+        }
+        assert_eq!(tracker.percentile(100.0), Some(Duration::from_millis(100)));
+    }
+
+    #[test]
+    fn p0_is_the_minimum_observed_latency() {
+        let tracker = PerformanceTracker::new(10);
+        for ms in [10, 50, 20, 100, 30] {
+            tracker.record(Duration::from_millis(ms));
+        }
+        assert_eq!(tracker.percentile(0.0), Some(Duration::from_millis(10)));
+    }
+
+    #[test]
+    fn window_evicts_oldest_beyond_capacity() {
+        let tracker = PerformanceTracker::new(2);
+        tracker.record(Duration::from_millis(1));
+        tracker.record(Duration::from_millis(2));
+        tracker.record(Duration::from_millis(3));
+        assert_eq!(tracker.len(), 2);
+    }
+}
@@ -0,0 +1,442 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::RwLock;
+use tracing::debug;
+use uuid::Uuid;
+
+use crate::agent_registry::{AgentRegistry, ConnectedAgent};
+use crate::backoff::{self, DEFAULT_BASE_DELAY, DEFAULT_MAX_DELAY};
+use crate::connection::{Connection, ConnectionStatus, DEFAULT_HEARTBEAT_INTERVAL};
+use crate::error::Error;
+use crate::memory_profiler::MemoryProfiler;
+use crate::metrics;
+use crate::security::SecurityManager;
+
+/// Estimated per-connection overhead (read/write buffers, metrics, etc.)
+/// charged to the pool's [`MemoryProfiler`] on add/remove.
+const ESTIMATED_CONNECTION_BYTES: usize = 64 * 1024;
+
+/// What [`ConnectionPool::add_connection`] does once
+/// [`PoolConfig::max_connections`] is reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EvictionPolicy {
+    /// Reject the new connection with [`Error::Connection`].
+    #[default]
+    Reject,
+    /// Evict the least-recently-touched connection (added or fetched via
+    /// [`ConnectionPool::get_connection`]) to make room for the new one.
+    EvictLeastRecentlyUsed,
+}
+
+/// Size-limit configuration for a [`ConnectionPool`]. The default (no
+/// limit) preserves today's unbounded behavior.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PoolConfig {
+    pub max_connections: Option<usize>,
+    pub eviction_policy: EvictionPolicy,
+}
+
+impl PoolConfig {
+    pub fn new(max_connections: usize) -> Self {
+        Self { max_connections: Some(max_connections), eviction_policy: EvictionPolicy::default() }
+    }
+
+    pub fn with_eviction_policy(mut self, policy: EvictionPolicy) -> Self {
+        self.eviction_policy = policy;
+        self
+    }
+}
+
+/// Holds every live [`Connection`], keyed by id, and owns the background
+/// heartbeat task that keeps them alive.
+#[derive(Clone, Default)]
+pub struct ConnectionPool {
+    connections: Arc<RwLock<HashMap<Uuid, Connection>>>,
+    /// When each connection was last added or fetched, for
+    /// [`EvictionPolicy::EvictLeastRecentlyUsed`] to pick a victim.
+    last_touched: Arc<RwLock<HashMap<Uuid, Instant>>>,
+    memory_profiler: MemoryProfiler,
+    agents: AgentRegistry,
+    config: PoolConfig,
+}
+
+impl ConnectionPool {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Like [`Self::new`], but enforcing `config`'s connection limit.
+    pub fn with_config(config: PoolConfig) -> Self {
+        Self { config, ..Self::default() }
+    }
+
+    pub fn memory_profiler(&self) -> &MemoryProfiler {
+        &self.memory_profiler
+    }
+
+    /// Insert `connection`, rejecting or evicting per [`PoolConfig`] once
+    /// [`PoolConfig::max_connections`] is reached.
+    pub async fn add_connection(&self, connection: Connection) -> Result<(), Error> {
+        let Some(max) = self.config.max_connections else {
+            self.insert(connection).await;
+            return Ok(());
+        };
+
+        let mut connections = self.connections.write().await;
+        if connections.len() >= max {
+            match self.config.eviction_policy {
+                EvictionPolicy::Reject => return Err(Error::Connection("pool full".to_string())),
+                EvictionPolicy::EvictLeastRecentlyUsed => {
+                    let mut touched = self.last_touched.write().await;
+                    if let Some(victim) = touched.iter().min_by_key(|(_, &instant)| instant).map(|(&id, _)| id) {
+                        connections.remove(&victim);
+                        touched.remove(&victim);
+                        self.memory_profiler.record_free(ESTIMATED_CONNECTION_BYTES);
+                        self.agents.remove(victim).await;
+                    }
+                }
+            }
+        }
+
+        let id = connection.id;
+        connections.insert(id, connection);
+        self.last_touched.write().await.insert(id, Instant::now());
+        self.memory_profiler.record_alloc(ESTIMATED_CONNECTION_BYTES);
+        Ok(())
+    }
+
+    /// Insert `connection` with no size limit applied, for the common case
+    /// where [`PoolConfig::max_connections`] isn't set.
+    async fn insert(&self, connection: Connection) {
+        let id = connection.id;
+        self.connections.write().await.insert(id, connection);
+        self.last_touched.write().await.insert(id, Instant::now());
+        self.memory_profiler.record_alloc(ESTIMATED_CONNECTION_BYTES);
+    }
+
+    pub async fn get_connection(&self, id: Uuid) -> Option<Connection> {
+        let connection = self.connections.read().await.get(&id).cloned();
+        if connection.is_some() {
+            self.last_touched.write().await.insert(id, Instant::now());
+        }
+        connection
+    }
+
+    /// Snapshot every connection currently pooled, for callers (like the
+    /// `/metrics` exporter) that need to report on each one individually
+    /// rather than just the aggregate [`Self::stats`].
+    pub async fn connections(&self) -> Vec<Connection> {
+        self.connections.read().await.values().cloned().collect()
+    }
+
+    /// Add `connection` with `security` applied, so its traffic goes
+    /// through the encrypted path. Equivalent to
+    /// `connection.with_security(security)` followed by
+    /// [`Self::add_connection`], except it hands back the now-secured
+    /// handle the caller should keep using.
+    pub async fn add_secure_connection(&self, connection: Connection, security: SecurityManager) -> Result<Connection, Error> {
+        let connection = connection.with_security(security);
+        self.add_connection(connection.clone()).await?;
+        Ok(connection)
+    }
+
+    pub async fn len(&self) -> usize {
+        self.connections.read().await.len()
+    }
+
+    pub async fn is_empty(&self) -> bool {
+        self.connections.read().await.is_empty()
+    }
+
+    /// Remove `id` from the pool, returning the evicted connection if it
+    /// was present. Does not itself close the underlying socket — the
+    /// connection's background write task exits once the `Connection` (and
+    /// its cloned handles) are dropped.
+    pub async fn remove_connection(&self, id: Uuid) -> Option<Connection> {
+        let removed = self.connections.write().await.remove(&id);
+        if removed.is_some() {
+            self.last_touched.write().await.remove(&id);
+            self.memory_profiler.record_free(ESTIMATED_CONNECTION_BYTES);
+        }
+        self.agents.remove(id).await;
+        removed
+    }
+
+    /// Record `id` as a registered agent advertising `capabilities`, parsed
+    /// from its registration message. Called once per connection, right
+    /// after the handshake, so [`Self::list_agents`] reflects what a peer
+    /// can actually do.
+    pub async fn register_agent(&self, id: Uuid, capabilities: Vec<String>) {
+        self.agents.register(id, capabilities).await;
+    }
+
+    /// List every agent that has completed registration, with the
+    /// capabilities it advertised.
+    pub async fn list_agents(&self) -> Vec<ConnectedAgent> {
+        self.agents.list_agents().await
+    }
+
+    /// Send a WebSocket close frame to every pooled connection, give their
+    /// write tasks a moment to flush it out, then drop the pool via
+    /// [`Self::shutdown`]. Used for graceful server shutdown, where peers
+    /// should see a clean close rather than the socket just disappearing.
+    pub async fn close_all(&self) {
+        {
+            let connections = self.connections.read().await;
+            for connection in connections.values() {
+                let _ = connection.send_close();
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        self.shutdown().await;
+    }
+
+    /// Drop every pooled connection, letting their write tasks wind down.
+    /// Intended for server shutdown: call this once no new connections will
+    /// be accepted and existing ones should be released cleanly.
+    pub async fn shutdown(&self) {
+        let mut connections = self.connections.write().await;
+        let count = connections.len();
+        connections.clear();
+        self.last_touched.write().await.clear();
+        for _ in 0..count {
+            self.memory_profiler.record_free(ESTIMATED_CONNECTION_BYTES);
+        }
+        debug!(count, "connection pool shut down");
+    }
+
+    /// Send `payload` to every connection in the pool, continuing past
+    /// individual failures and returning the ids that could not be
+    /// delivered to.
+    pub async fn broadcast(&self, payload: impl Into<String>) -> Vec<Uuid> {
+        let payload = payload.into();
+        let connections = self.connections.read().await;
+        let mut failed = Vec::new();
+        for connection in connections.values() {
+            if connection.send(payload.clone()).await.is_err() {
+                failed.push(connection.id);
+            }
+        }
+        failed
+    }
+
+    /// Like [`Self::broadcast`], but skips `exclude`. Used for presence
+    /// updates, where an agent doesn't need to be told about its own
+    /// connect or disconnect.
+    pub async fn broadcast_except(&self, exclude: Uuid, payload: impl Into<String>) -> Vec<Uuid> {
+        let payload = payload.into();
+        let connections = self.connections.read().await;
+        let mut failed = Vec::new();
+        for connection in connections.values() {
+            if connection.id == exclude {
+                continue;
+            }
+            if connection.send(payload.clone()).await.is_err() {
+                failed.push(connection.id);
+            }
+        }
+        failed
+    }
+
+    /// Send `payload` to the given subset of connection ids (multicast),
+    /// skipping any id not currently present in the pool.
+    pub async fn multicast(&self, ids: &[Uuid], payload: impl Into<String>) -> Vec<Uuid> {
+        let payload = payload.into();
+        let connections = self.connections.read().await;
+        let mut failed = Vec::new();
+        for id in ids {
+            match connections.get(id) {
+                Some(connection) => {
+                    if connection.send(payload.clone()).await.is_err() {
+                        failed.push(*id);
+                    }
+                }
+                None => failed.push(*id),
+            }
+        }
+        failed
+    }
+
+    /// Start a background task that pings every pooled connection on
+    /// `interval`, so dead peers get noticed before a send is attempted.
+    pub fn start_heartbeat(&self, interval: Duration) {
+        let pool = self.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let connections = pool.connections.read().await;
+                for connection in connections.values() {
+                    if let Err(err) = connection.send_ping() {
+                        debug!(id = %connection.id, %err, "heartbeat ping failed");
+                    }
+                }
+            }
+        });
+    }
+
+    /// Convenience wrapper around [`Self::start_heartbeat`] using
+    /// [`DEFAULT_HEARTBEAT_INTERVAL`].
+    pub fn start_default_heartbeat(&self) {
+        self.start_heartbeat(DEFAULT_HEARTBEAT_INTERVAL);
+    }
+
+    /// How long a caller retrying a failed connection attempt for the
+    /// `attempt`'th time (0-indexed) should wait before trying again.
+    /// Capped exponential backoff with full jitter — see
+    /// [`crate::backoff::backoff_delay`] — so many agents that dropped at
+    /// once don't all reconnect in lockstep.
+    pub fn reconnect_delay(&self, attempt: u32) -> Duration {
+        backoff::backoff_delay(attempt, DEFAULT_BASE_DELAY, DEFAULT_MAX_DELAY)
+    }
+
+    /// Snapshot aggregate health and traffic across every pooled
+    /// connection, for health dashboards that shouldn't have to walk
+    /// `list_agents` and every connection's metrics themselves.
+    pub async fn stats(&self) -> PoolStats {
+        let connections = self.connections.read().await;
+
+        let mut stats = PoolStats { total_connections: connections.len(), ..PoolStats::default() };
+        let mut latencies = Vec::new();
+        for connection in connections.values() {
+            *stats.by_status.entry(connection.status()).or_insert(0) += 1;
+            stats.total_messages_sent += connection.metrics.messages_sent();
+            stats.total_messages_received += connection.metrics.messages_received();
+            stats.total_bytes_sent += connection.metrics.bytes_sent();
+            stats.total_bytes_received += connection.metrics.bytes_received();
+            latencies.extend(connection.metrics.latency_samples());
+        }
+        stats.average_latency = metrics::average_latency(&latencies);
+        stats.p99_latency = metrics::percentile_latency(&latencies, 0.99);
+        stats
+    }
+}
+
+/// Aggregate snapshot returned by [`ConnectionPool::stats`].
+#[derive(Debug, Clone, Default)]
+pub struct PoolStats {
+    pub total_connections: usize,
+    pub by_status: HashMap<ConnectionStatus, usize>,
+    pub total_messages_sent: u64,
+    pub total_messages_received: u64,
+    pub total_bytes_sent: u64,
+    pub total_bytes_received: u64,
+    pub average_latency: Option<Duration>,
+    pub p99_latency: Option<Duration>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connection::Connection;
+    use futures::sink::drain;
+
+    #[tokio::test]
+    async fn remove_connection_evicts_from_pool() {
+        let pool = ConnectionPool::new();
+        let conn = Connection::spawn(drain());
+        let id = conn.id;
+        pool.add_connection(conn).await.unwrap();
+        assert_eq!(pool.len().await, 1);
+
+        let removed = pool.remove_connection(id).await;
+        assert!(removed.is_some());
+        assert_eq!(pool.len().await, 0);
+    }
+
+    #[tokio::test]
+    async fn memory_profiler_tracks_connection_lifecycle() {
+        let pool = ConnectionPool::new();
+        let conn = Connection::spawn(drain());
+        let id = conn.id;
+        pool.add_connection(conn).await.unwrap();
+        assert!(pool.memory_profiler().live_bytes() > 0);
+
+        pool.remove_connection(id).await;
+        assert_eq!(pool.memory_profiler().live_bytes(), 0);
+    }
+
+    #[tokio::test]
+    async fn agent_created_with_a_security_config_connects_via_the_secure_path() {
+        let pool = ConnectionPool::new();
+        let security = crate::security::SecurityManager::new(crate::security::SecurityManager::generate_key());
+
+        let connection = pool.add_secure_connection(Connection::spawn(drain()), security).await.unwrap();
+        assert_eq!(pool.len().await, 1);
+
+        // With security configured, `send` must succeed by encrypting the
+        // payload rather than sending it as plaintext.
+        connection.send("hello").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn stats_aggregates_status_and_traffic_across_connections() {
+        let pool = ConnectionPool::new();
+
+        let healthy = Connection::spawn(drain());
+        healthy.metrics.record_sent(100);
+        healthy.metrics.record_latency(Duration::from_millis(10));
+        pool.add_connection(healthy).await.unwrap();
+
+        let degraded = Connection::spawn_with_circuit_breaker(drain(), crate::circuit_breaker::CircuitBreaker::new(1, Duration::from_secs(60)));
+        degraded.circuit_breaker.record_failure().await;
+        degraded.metrics.record_received(50);
+        degraded.metrics.record_latency(Duration::from_millis(30));
+        pool.add_connection(degraded).await.unwrap();
+
+        let stats = pool.stats().await;
+        assert_eq!(stats.total_connections, 2);
+        assert_eq!(stats.by_status.get(&crate::connection::ConnectionStatus::Connected), Some(&1));
+        assert_eq!(stats.by_status.get(&crate::connection::ConnectionStatus::Degraded), Some(&1));
+        assert_eq!(stats.total_bytes_sent, 100);
+        assert_eq!(stats.total_bytes_received, 50);
+        assert_eq!(stats.average_latency, Some(Duration::from_millis(20)));
+    }
+
+    #[tokio::test]
+    async fn shutdown_clears_all_connections() {
+        let pool = ConnectionPool::new();
+        pool.add_connection(Connection::spawn(drain())).await.unwrap();
+        pool.add_connection(Connection::spawn(drain())).await.unwrap();
+        pool.shutdown().await;
+        assert_eq!(pool.len().await, 0);
+    }
+
+    #[tokio::test]
+    async fn a_third_connection_is_rejected_once_max_connections_is_reached() {
+        let pool = ConnectionPool::with_config(PoolConfig::new(2));
+        pool.add_connection(Connection::spawn(drain())).await.unwrap();
+        pool.add_connection(Connection::spawn(drain())).await.unwrap();
+        assert_eq!(pool.len().await, 2);
+
+        let err = pool.add_connection(Connection::spawn(drain())).await.unwrap_err();
+        assert!(matches!(err, Error::Connection(ref msg) if msg == "pool full"));
+        assert_eq!(pool.len().await, 2);
+    }
+
+    #[tokio::test]
+    async fn a_third_connection_evicts_the_least_recently_used_one_when_configured_to() {
+        let pool = ConnectionPool::with_config(PoolConfig::new(2).with_eviction_policy(EvictionPolicy::EvictLeastRecentlyUsed));
+
+        let first = Connection::spawn(drain());
+        let first_id = first.id;
+        pool.add_connection(first).await.unwrap();
+
+        let second = Connection::spawn(drain());
+        let second_id = second.id;
+        pool.add_connection(second).await.unwrap();
+
+        // Touch `second` so `first` is the least recently used of the two.
+        pool.get_connection(second_id).await;
+
+        let third = Connection::spawn(drain());
+        let third_id = third.id;
+        pool.add_connection(third).await.unwrap();
+
+        assert_eq!(pool.len().await, 2);
+        assert!(pool.get_connection(first_id).await.is_none());
+        assert!(pool.get_connection(second_id).await.is_some());
+        assert!(pool.get_connection(third_id).await.is_some());
+    }
+}
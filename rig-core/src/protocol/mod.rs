@@ -0,0 +1,260 @@
+pub mod replay;
+pub mod stream;
+
+use async_trait::async_trait;
+use ring::rand::SystemRandom;
+use ring::signature::{Ed25519KeyPair, KeyPair, UnparsedPublicKey, ED25519};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::error::Error;
+
+/// Current wire version for [`ProtocolMessage`]. Bump whenever the
+/// envelope shape changes in a way that isn't backwards compatible.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// A peer's advertised protocol version, as carried on every
+/// [`ProtocolMessage::protocol_version`] field, checked against
+/// [`PROTOCOL_VERSION`] during the connect handshake.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProtocolVersion(pub u32);
+
+impl ProtocolVersion {
+    /// Whether a peer speaking this version can talk to this build.
+    /// There's no cross-version negotiation on the wire yet, so
+    /// compatibility currently just means an exact match.
+    pub fn is_compatible(&self) -> bool {
+        self.0 == PROTOCOL_VERSION
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageType {
+    Connect,
+    Disconnect,
+    Data,
+    Ack,
+    Error,
+    /// Presence/roster update, e.g. an agent connecting or disconnecting.
+    /// See [`crate::MeapServer`], which broadcasts these.
+    Status,
+    /// Keepalive sent/expected on the application protocol layer, distinct
+    /// from the transport-level WebSocket ping used by `ConnectionPool`.
+    Heartbeat,
+}
+
+/// The envelope every message exchanged between agents is wrapped in.
+/// `protocol_version` must be set on every message so older peers can
+/// detect and reject envelopes they don't understand, rather than
+/// misinterpreting fields that shifted meaning.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProtocolMessage {
+    pub id: Uuid,
+    pub protocol_version: u32,
+    pub message_type: MessageType,
+    pub payload: serde_json::Value,
+    /// Detached Ed25519 signature over every other field, set by
+    /// [`MeapProtocol::sign`]. Absent on messages that haven't been signed.
+    pub signature: Option<Vec<u8>>,
+    /// Caller-supplied key identifying this logical operation across
+    /// retries, so a store that sees the same key twice (e.g. because an
+    /// at-least-once delivery redelivered the message) can skip the
+    /// duplicate instead of processing it again. `None` means no dedup is
+    /// requested.
+    pub idempotency_key: Option<String>,
+}
+
+impl ProtocolMessage {
+    pub fn new(message_type: MessageType, payload: serde_json::Value) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            protocol_version: PROTOCOL_VERSION,
+            message_type,
+            payload,
+            signature: None,
+            idempotency_key: None,
+        }
+    }
+
+    /// Attach an idempotency key so stores can dedup retried deliveries
+    /// of this message.
+    pub fn with_idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.idempotency_key = Some(key.into());
+        self
+    }
+
+    pub fn heartbeat() -> Self {
+        Self::new(MessageType::Heartbeat, serde_json::Value::Null)
+    }
+
+    /// Bytes covered by the signature: every field except `signature`
+    /// itself.
+    fn signable_bytes(&self) -> Vec<u8> {
+        serde_json::json!({
+            "id": self.id,
+            "protocol_version": self.protocol_version,
+            "message_type": self.message_type,
+            "payload": self.payload,
+            "idempotency_key": self.idempotency_key,
+        })
+        .to_string()
+        .into_bytes()
+    }
+}
+
+/// Signs and verifies [`ProtocolMessage`] envelopes with Ed25519, so a
+/// receiving agent can confirm a message wasn't tampered with in transit.
+pub struct MeapProtocol {
+    keypair: Ed25519KeyPair,
+}
+
+impl MeapProtocol {
+    /// Generate a fresh signing keypair.
+    pub fn generate() -> Result<Self, Error> {
+        let rng = SystemRandom::new();
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&rng).map_err(|_| Error::Authentication)?;
+        let keypair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).map_err(|_| Error::Authentication)?;
+        Ok(Self { keypair })
+    }
+
+    pub fn public_key(&self) -> Vec<u8> {
+        self.keypair.public_key().as_ref().to_vec()
+    }
+
+    /// Sign `message` in place, populating its `signature` field.
+    pub fn sign(&self, message: &mut ProtocolMessage) {
+        let signature = self.keypair.sign(&message.signable_bytes());
+        message.signature = Some(signature.as_ref().to_vec());
+    }
+
+    /// Verify `message`'s signature against `public_key`, failing if the
+    /// signature is missing, malformed, or doesn't match.
+    pub fn verify(message: &ProtocolMessage, public_key: &[u8]) -> Result<(), Error> {
+        let signature = message.signature.as_ref().ok_or(Error::Authentication)?;
+        let verifier = UnparsedPublicKey::new(&ED25519, public_key);
+        verifier
+            .verify(&message.signable_bytes(), signature)
+            .map_err(|_| Error::Authentication)
+    }
+}
+
+/// Machine-readable classification for a [`MessageType::Error`] response
+/// built by [`degraded_response`], so a caller can branch on failure kind
+/// instead of pattern-matching the human-readable message string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// A storage backend (Qdrant, MongoDB, etc.) was unreachable or
+    /// refused the call — distinct from [`ErrorCode::Internal`] because
+    /// it's usually safe to retry once the backend recovers.
+    BackendUnavailable,
+    /// Anything else: a bug, a malformed request, or similar — retrying
+    /// without changing anything won't help.
+    Internal,
+}
+
+/// Implemented by a store's own error type so [`degraded_response`] can
+/// turn it into a structured reply without the caller needing to know
+/// that backend's driver-specific error shape.
+pub trait BackendError: std::fmt::Display {
+    fn code(&self) -> ErrorCode;
+    fn is_retryable(&self) -> bool;
+}
+
+/// Build a [`MessageType::Error`] response carrying `err`'s machine
+/// readable [`ErrorCode`] and retryability, instead of leaking the raw
+/// driver error string straight to the caller when a backend like Qdrant
+/// or MongoDB is unavailable.
+pub fn degraded_response(err: &impl BackendError) -> ProtocolMessage {
+    ProtocolMessage::new(
+        MessageType::Error,
+        serde_json::json!({
+            "code": err.code(),
+            "retryable": err.is_retryable(),
+            "message": err.to_string(),
+        }),
+    )
+}
+
+/// Implemented by anything that can drive a message stream for an agent
+/// connection. There is exactly one `handle_stream` entry point — earlier
+/// revisions of this trait accidentally declared it twice across default
+/// and required methods, which made it ambiguous which one callers hit.
+#[async_trait]
+pub trait Protocol: Send + Sync {
+    async fn handle_stream(&self, receiver: stream::StreamReceiver);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_constructed_message_carries_the_current_protocol_version() {
+        let msg = ProtocolMessage::new(MessageType::Data, serde_json::json!({"k": "v"}));
+        assert_eq!(msg.protocol_version, PROTOCOL_VERSION);
+        assert_eq!(ProtocolMessage::heartbeat().protocol_version, PROTOCOL_VERSION);
+    }
+
+    #[test]
+    fn a_matching_protocol_version_is_compatible() {
+        assert!(ProtocolVersion(PROTOCOL_VERSION).is_compatible());
+    }
+
+    #[test]
+    fn a_different_protocol_version_is_incompatible() {
+        assert!(!ProtocolVersion(PROTOCOL_VERSION + 1).is_compatible());
+    }
+
+    #[test]
+    fn heartbeat_constructor_sets_heartbeat_type() {
+        assert_eq!(ProtocolMessage::heartbeat().message_type, MessageType::Heartbeat);
+    }
+
+    #[test]
+    fn signed_message_verifies_with_matching_public_key() {
+        let protocol = MeapProtocol::generate().unwrap();
+        let mut msg = ProtocolMessage::new(MessageType::Data, serde_json::json!({"k": "v"}));
+        protocol.sign(&mut msg);
+        assert!(MeapProtocol::verify(&msg, &protocol.public_key()).is_ok());
+    }
+
+    #[test]
+    fn tampered_payload_fails_verification() {
+        let protocol = MeapProtocol::generate().unwrap();
+        let mut msg = ProtocolMessage::new(MessageType::Data, serde_json::json!({"k": "v"}));
+        protocol.sign(&mut msg);
+        msg.payload = serde_json::json!({"k": "tampered"});
+        assert!(MeapProtocol::verify(&msg, &protocol.public_key()).is_err());
+    }
+
+    #[derive(Debug)]
+    struct FakeConnectionRefused;
+
+    impl std::fmt::Display for FakeConnectionRefused {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "connection refused")
+        }
+    }
+
+    impl BackendError for FakeConnectionRefused {
+        fn code(&self) -> ErrorCode {
+            ErrorCode::BackendUnavailable
+        }
+
+        fn is_retryable(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn a_backend_connection_failure_degrades_to_a_structured_retryable_error() {
+        let response = degraded_response(&FakeConnectionRefused);
+
+        assert_eq!(response.message_type, MessageType::Error);
+        assert_eq!(response.payload["code"], serde_json::json!("BACKEND_UNAVAILABLE"));
+        assert_eq!(response.payload["retryable"], serde_json::json!(true));
+        assert_eq!(response.payload["message"], serde_json::json!("connection refused"));
+    }
+}
@@ -0,0 +1,148 @@
+//! Record/replay wrappers for writing deterministic tests against anything
+//! that serves JSON request/response pairs — a Deepseek chat completion, a
+//! vector store query — without the real network or backend in CI.
+//! Requests and responses are kept as raw [`serde_json::Value`] rather than
+//! a generic trait over every caller's types, since the callers this is
+//! for (an HTTP client, a store driver) don't share one.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+/// One recorded request/response pair, stored one per line as
+/// newline-delimited JSON.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RecordedInteraction {
+    pub request: serde_json::Value,
+    pub response: serde_json::Value,
+}
+
+/// Wraps live calls, appending every `(request, response)` pair observed to
+/// an in-memory log that [`Self::save`] flushes to disk as NDJSON for a
+/// later [`ReplayProtocol`] to consume.
+#[derive(Default)]
+pub struct RecordingProtocol {
+    interactions: Mutex<Vec<RecordedInteraction>>,
+}
+
+impl RecordingProtocol {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record `request`'s `response`, in call order.
+    pub fn record(&self, request: serde_json::Value, response: serde_json::Value) {
+        self.interactions.lock().unwrap().push(RecordedInteraction { request, response });
+    }
+
+    /// Write every recorded interaction to `path`, one JSON object per
+    /// line, in the order [`Self::record`] was called.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let mut file = File::create(path)?;
+        for interaction in self.interactions.lock().unwrap().iter() {
+            writeln!(file, "{}", serde_json::to_string(interaction)?)?;
+        }
+        Ok(())
+    }
+}
+
+/// Serves responses recorded by [`RecordingProtocol`] in place of a live
+/// backend, matching each call against the recording by exact request
+/// equality and consuming it so the same interaction isn't replayed twice.
+#[derive(Default)]
+pub struct ReplayProtocol {
+    remaining: Mutex<Vec<RecordedInteraction>>,
+}
+
+impl ReplayProtocol {
+    /// Load a recording written by [`RecordingProtocol::save`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let reader = BufReader::new(File::open(path)?);
+        let mut remaining = Vec::new();
+        for line in reader.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            remaining.push(serde_json::from_str(&line)?);
+        }
+        Ok(Self { remaining: Mutex::new(remaining) })
+    }
+
+    /// Return the response recorded for `request`, erroring if no
+    /// not-yet-consumed interaction matches it.
+    pub fn replay(&self, request: &serde_json::Value) -> Result<serde_json::Value, Error> {
+        let mut remaining = self.remaining.lock().unwrap();
+        let index = remaining
+            .iter()
+            .position(|interaction| &interaction.request == request)
+            .ok_or(Error::UnmatchedReplay)?;
+        Ok(remaining.remove(index).response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaying_recorded_calls_returns_byte_identical_responses() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("recording.ndjson");
+
+        let recorder = RecordingProtocol::new();
+        recorder.record(
+            serde_json::json!({"prompt": "hello"}),
+            serde_json::json!({"completion": "hi there"}),
+        );
+        recorder.record(
+            serde_json::json!({"prompt": "bye"}),
+            serde_json::json!({"completion": "see you"}),
+        );
+        recorder.save(&path).unwrap();
+
+        let player = ReplayProtocol::load(&path).unwrap();
+        assert_eq!(
+            player.replay(&serde_json::json!({"prompt": "bye"})).unwrap(),
+            serde_json::json!({"completion": "see you"})
+        );
+        assert_eq!(
+            player.replay(&serde_json::json!({"prompt": "hello"})).unwrap(),
+            serde_json::json!({"completion": "hi there"})
+        );
+    }
+
+    #[test]
+    fn replaying_an_unrecorded_request_errors_instead_of_hitting_a_real_backend() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("recording.ndjson");
+
+        let recorder = RecordingProtocol::new();
+        recorder.record(serde_json::json!({"prompt": "hello"}), serde_json::json!({"completion": "hi"}));
+        recorder.save(&path).unwrap();
+
+        let player = ReplayProtocol::load(&path).unwrap();
+        let err = player.replay(&serde_json::json!({"prompt": "unrecorded"})).unwrap_err();
+        assert!(matches!(err, Error::UnmatchedReplay));
+    }
+
+    #[test]
+    fn a_consumed_interaction_cannot_be_replayed_twice() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("recording.ndjson");
+
+        let recorder = RecordingProtocol::new();
+        recorder.record(serde_json::json!({"prompt": "hello"}), serde_json::json!({"completion": "hi"}));
+        recorder.save(&path).unwrap();
+
+        let player = ReplayProtocol::load(&path).unwrap();
+        player.replay(&serde_json::json!({"prompt": "hello"})).unwrap();
+        let err = player.replay(&serde_json::json!({"prompt": "hello"})).unwrap_err();
+        assert!(matches!(err, Error::UnmatchedReplay));
+    }
+}
@@ -0,0 +1,181 @@
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+
+use super::{MessageType, ProtocolMessage};
+use crate::error::Error;
+
+/// Default capacity of the bounded channel backing a [`StreamSender`].
+pub const DEFAULT_STREAM_CAPACITY: usize = 256;
+
+/// The sending half of a bounded message stream between two agents.
+/// Bounded so a slow receiver applies backpressure to the sender instead
+/// of letting an unbounded channel grow without limit.
+pub struct StreamSender {
+    tx: mpsc::Sender<ProtocolMessage>,
+}
+
+/// The receiving half paired with a [`StreamSender`].
+pub struct StreamReceiver {
+    rx: mpsc::Receiver<ProtocolMessage>,
+}
+
+/// Create a bounded sender/receiver pair with `capacity` buffered messages.
+pub fn channel(capacity: usize) -> (StreamSender, StreamReceiver) {
+    let (tx, rx) = mpsc::channel(capacity);
+    (StreamSender { tx }, StreamReceiver { rx })
+}
+
+impl StreamSender {
+    /// Send `message`, waiting for buffer space if the channel is full.
+    pub async fn send(&self, message: ProtocolMessage) -> Result<(), ProtocolMessage> {
+        self.tx.send(message).await.map_err(|e| e.0)
+    }
+
+    /// Send `message` only if there's buffer space right now, instead of
+    /// waiting for backpressure to clear.
+    pub fn try_send(&self, message: ProtocolMessage) -> Result<(), ProtocolMessage> {
+        self.tx.try_send(message).map_err(|e| match e {
+            mpsc::error::TrySendError::Full(m) => m,
+            mpsc::error::TrySendError::Closed(m) => m,
+        })
+    }
+}
+
+/// One piece of a chunked text response, carried as a `MessageType::Data`
+/// [`ProtocolMessage`] whose payload is `{"index", "content", "final"}`.
+/// `index` lets chunks be reassembled even if they arrive out of order;
+/// `final` marks the last chunk of the stream.
+struct Chunk {
+    index: usize,
+    content: String,
+    is_final: bool,
+}
+
+fn parse_chunk(message: &ProtocolMessage) -> Option<Chunk> {
+    if message.message_type != MessageType::Data {
+        return None;
+    }
+    Some(Chunk {
+        index: message.payload.get("index")?.as_u64()? as usize,
+        content: message.payload.get("content")?.as_str()?.to_string(),
+        is_final: message.payload.get("final").and_then(|v| v.as_bool()).unwrap_or(false),
+    })
+}
+
+impl StreamReceiver {
+    pub async fn recv(&mut self) -> Option<ProtocolMessage> {
+        self.rx.recv().await
+    }
+
+    /// Drain this stream's chunks and reassemble them into the text they
+    /// represent, in index order regardless of arrival order. Errors if
+    /// the stream closes before a final chunk arrives, if an index is
+    /// missing, or if a `MessageType::Error` message is received.
+    pub async fn collect_string(mut self) -> Result<String, Error> {
+        let mut chunks: Vec<Option<String>> = Vec::new();
+
+        loop {
+            let message = self
+                .recv()
+                .await
+                .ok_or_else(|| Error::WireFormat("stream closed before a final chunk was received".to_string()))?;
+
+            if message.message_type == MessageType::Error {
+                let reason = message
+                    .payload
+                    .get("message")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("stream reported an error")
+                    .to_string();
+                return Err(Error::WireFormat(reason));
+            }
+
+            let Some(chunk) = parse_chunk(&message) else { continue };
+            if chunks.len() <= chunk.index {
+                chunks.resize(chunk.index + 1, None);
+            }
+            chunks[chunk.index] = Some(chunk.content);
+
+            if chunk.is_final {
+                break;
+            }
+        }
+
+        chunks
+            .into_iter()
+            .enumerate()
+            .map(|(index, content)| content.ok_or_else(|| Error::WireFormat(format!("missing chunk at index {index}"))))
+            .collect()
+    }
+
+    /// Like [`Self::collect_string`], but gives up after `timeout` instead
+    /// of waiting forever on a stream that stalls without closing or
+    /// sending a final chunk.
+    pub async fn collect_with_timeout(self, timeout: Duration) -> Result<String, Error> {
+        tokio::time::timeout(timeout, self.collect_string())
+            .await
+            .map_err(|_| Error::Connection("stream collection timed out".to_string()))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::MessageType;
+
+    #[tokio::test]
+    async fn try_send_fails_once_buffer_is_full() {
+        let (tx, mut rx) = channel(1);
+        tx.try_send(ProtocolMessage::heartbeat()).unwrap();
+        assert!(tx.try_send(ProtocolMessage::heartbeat()).is_err());
+
+        let received = rx.recv().await.unwrap();
+        assert_eq!(received.message_type, MessageType::Heartbeat);
+    }
+
+    fn chunk(index: usize, content: &str, is_final: bool) -> ProtocolMessage {
+        ProtocolMessage::new(
+            MessageType::Data,
+            serde_json::json!({ "index": index, "content": content, "final": is_final }),
+        )
+    }
+
+    #[tokio::test]
+    async fn out_of_order_chunks_reassemble_by_index_not_arrival_order() {
+        let (tx, rx) = channel(8);
+        tx.send(chunk(1, "world", false)).await.unwrap();
+        tx.send(chunk(0, "hello ", false)).await.unwrap();
+        tx.send(chunk(2, "!", true)).await.unwrap();
+
+        assert_eq!(rx.collect_string().await.unwrap(), "hello world!");
+    }
+
+    #[tokio::test]
+    async fn a_missing_index_errors_instead_of_silently_skipping_it() {
+        let (tx, rx) = channel(8);
+        tx.send(chunk(0, "hello ", false)).await.unwrap();
+        tx.send(chunk(2, "!", true)).await.unwrap();
+
+        assert!(rx.collect_string().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn a_stream_error_message_aborts_collection() {
+        let (tx, rx) = channel(8);
+        tx.send(chunk(0, "hello ", false)).await.unwrap();
+        tx.send(ProtocolMessage::new(MessageType::Error, serde_json::json!({ "message": "upstream failed" })))
+            .await
+            .unwrap();
+
+        let err = rx.collect_string().await.unwrap_err();
+        assert!(matches!(err, Error::WireFormat(ref msg) if msg == "upstream failed"));
+    }
+
+    #[tokio::test]
+    async fn collect_with_timeout_gives_up_on_a_stalled_stream() {
+        let (_tx, rx) = channel(8);
+        let err = rx.collect_with_timeout(Duration::from_millis(50)).await.unwrap_err();
+        assert!(matches!(err, Error::Connection(_)));
+    }
+}
@@ -0,0 +1,146 @@
+use std::future::Future;
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::error::Error;
+
+/// Backoff schedule for [`with_backoff`]: capped exponential growth
+/// between attempts, with optional full jitter to avoid every retrying
+/// caller waking up at the same instant.
+#[derive(Debug, Clone, Copy)]
+pub struct BackoffPolicy {
+    pub initial: Duration,
+    pub max: Duration,
+    pub factor: f64,
+    pub jitter: bool,
+    /// Total number of attempts, including the first — `1` means no
+    /// retries at all.
+    pub max_attempts: u32,
+}
+
+impl Default for BackoffPolicy {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_millis(100),
+            max: Duration::from_secs(30),
+            factor: 2.0,
+            jitter: true,
+            max_attempts: 5,
+        }
+    }
+}
+
+impl BackoffPolicy {
+    fn delay_for(&self, attempt: u32) -> Duration {
+        let scaled = self.initial.as_secs_f64() * self.factor.powi(attempt as i32);
+        let capped = scaled.min(self.max.as_secs_f64());
+        if self.jitter {
+            Duration::from_secs_f64(rand::thread_rng().gen_range(0.0..=capped))
+        } else {
+            Duration::from_secs_f64(capped)
+        }
+    }
+}
+
+/// Retry `op` until it succeeds, `is_retryable` rejects its error, or
+/// `policy.max_attempts` is exhausted — whichever comes first.
+pub async fn with_backoff<T, F, Fut>(mut op: F, policy: BackoffPolicy, is_retryable: impl Fn(&Error) -> bool) -> Result<T, Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, Error>>,
+{
+    let mut attempt = 0;
+    loop {
+        match op().await {
+            Ok(value) => return Ok(value),
+            Err(err) if attempt + 1 < policy.max_attempts && is_retryable(&err) => {
+                tokio::time::sleep(policy.delay_for(attempt)).await;
+                attempt += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    use super::*;
+
+    fn fast_policy(max_attempts: u32) -> BackoffPolicy {
+        BackoffPolicy { initial: Duration::from_millis(1), max: Duration::from_millis(5), factor: 2.0, jitter: false, max_attempts }
+    }
+
+    #[tokio::test]
+    async fn succeeds_on_the_third_attempt() {
+        let attempts = Arc::new(AtomicU32::new(0));
+        let counted = attempts.clone();
+
+        let result = with_backoff(
+            move || {
+                let attempts = counted.clone();
+                async move {
+                    let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+                    if attempt < 2 {
+                        Err(Error::CircuitOpen("not yet".to_string()))
+                    } else {
+                        Ok(attempt)
+                    }
+                }
+            },
+            fast_policy(5),
+            |_| true,
+        )
+        .await;
+
+        assert_eq!(result.unwrap(), 2);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_once_max_attempts_is_exhausted() {
+        let attempts = Arc::new(AtomicU32::new(0));
+        let counted = attempts.clone();
+
+        let result: Result<(), Error> = with_backoff(
+            move || {
+                let attempts = counted.clone();
+                async move {
+                    attempts.fetch_add(1, Ordering::SeqCst);
+                    Err(Error::CircuitOpen("still down".to_string()))
+                }
+            },
+            fast_policy(3),
+            |_| true,
+        )
+        .await;
+
+        assert!(matches!(result, Err(Error::CircuitOpen(_))));
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn a_non_retryable_error_short_circuits_without_further_attempts() {
+        let attempts = Arc::new(AtomicU32::new(0));
+        let counted = attempts.clone();
+
+        let result: Result<(), Error> = with_backoff(
+            move || {
+                let attempts = counted.clone();
+                async move {
+                    attempts.fetch_add(1, Ordering::SeqCst);
+                    Err(Error::Authentication)
+                }
+            },
+            fast_policy(5),
+            |err| !matches!(err, Error::Authentication),
+        )
+        .await;
+
+        assert!(matches!(result, Err(Error::Authentication)));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::error::Error;
+use crate::pool::ConnectionPool;
+use crate::protocol::{MessageType, ProtocolMessage};
+
+/// Maximum number of messages queued for a single disconnected agent
+/// before the oldest is dropped to make room for the newest.
+const MAX_QUEUED_PER_AGENT: usize = 32;
+
+/// How long a queued message is held for redelivery before it's
+/// considered stale and dropped instead.
+const QUEUE_TTL: Duration = Duration::from_secs(300);
+
+struct QueuedMessage {
+    message: ProtocolMessage,
+    queued_at: Instant,
+}
+
+/// Routes [`ProtocolMessage`]s between agents pooled in a
+/// [`ConnectionPool`]. A message addressed to an agent that isn't
+/// currently connected is held in a bounded, per-agent queue and
+/// redelivered once that agent reconnects (see [`Self::deliver_queued`]);
+/// the sender gets a `MessageType::Error` reply so it knows delivery
+/// didn't happen yet, rather than the message silently vanishing.
+#[derive(Clone, Default)]
+pub struct MessageRouter {
+    queues: Arc<RwLock<HashMap<Uuid, VecDeque<QueuedMessage>>>>,
+}
+
+impl MessageRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Deliver `message` from `sender_id` to `target_id` via `pool`. If
+    /// `target_id` isn't currently pooled, the message is queued for
+    /// redelivery and `sender_id` is sent a `MessageType::Error` reply.
+    pub async fn route(
+        &self,
+        pool: &ConnectionPool,
+        sender_id: Uuid,
+        target_id: Uuid,
+        message: ProtocolMessage,
+    ) -> Result<(), Error> {
+        if let Some(target) = pool.get_connection(target_id).await {
+            return target.send(serde_json::to_string(&message)?).await;
+        }
+
+        self.enqueue(target_id, message).await;
+
+        if let Some(sender) = pool.get_connection(sender_id).await {
+            let error = ProtocolMessage::new(
+                MessageType::Error,
+                serde_json::json!({ "reason": "target not connected", "target": target_id }),
+            );
+            sender.send(serde_json::to_string(&error)?).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn enqueue(&self, target_id: Uuid, message: ProtocolMessage) {
+        let mut queues = self.queues.write().await;
+        let queue = queues.entry(target_id).or_default();
+        if queue.len() >= MAX_QUEUED_PER_AGENT {
+            queue.pop_front();
+        }
+        queue.push_back(QueuedMessage { message, queued_at: Instant::now() });
+    }
+
+    /// Redeliver every unexpired message queued for `target_id` now that
+    /// it's (re)connected. Intended to be called once per registration.
+    pub async fn deliver_queued(&self, pool: &ConnectionPool, target_id: Uuid) {
+        let queued = self.queues.write().await.remove(&target_id).unwrap_or_default();
+        let Some(target) = pool.get_connection(target_id).await else {
+            return;
+        };
+
+        for entry in queued {
+            if entry.queued_at.elapsed() > QUEUE_TTL {
+                continue;
+            }
+            if let Ok(payload) = serde_json::to_string(&entry.message) {
+                let _ = target.send(payload).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connection::Connection;
+    use tokio::sync::mpsc as test_mpsc;
+    use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+    type CapturingSink = std::pin::Pin<Box<dyn futures::Sink<WsMessage, Error = std::convert::Infallible> + Send>>;
+
+    /// A sink that forwards every frame it's given into an unbounded
+    /// channel, so tests can assert on what a `Connection` actually sent
+    /// instead of just whether the send succeeded.
+    fn capturing_sink() -> (CapturingSink, test_mpsc::UnboundedReceiver<WsMessage>) {
+        let (tx, rx) = test_mpsc::unbounded_channel::<WsMessage>();
+        let sink = futures::sink::unfold(tx, |tx, item: WsMessage| async move {
+            let _ = tx.send(item);
+            Ok::<_, std::convert::Infallible>(tx)
+        });
+        (Box::pin(sink), rx)
+    }
+
+    #[tokio::test]
+    async fn routing_to_a_disconnected_target_queues_the_message_and_errors_the_sender() {
+        let pool = ConnectionPool::new();
+        let router = MessageRouter::new();
+
+        let (sink, mut rx) = capturing_sink();
+        let sender = Connection::spawn(sink);
+        pool.add_connection(sender.clone()).await.unwrap();
+
+        let target_id = Uuid::new_v4();
+        let message = ProtocolMessage::new(MessageType::Data, serde_json::json!({"target": target_id}));
+        router.route(&pool, sender.id, target_id, message).await.unwrap();
+
+        let delivered = rx.recv().await.expect("sender should get an error reply");
+        let WsMessage::Text(text) = delivered else {
+            panic!("expected a text frame");
+        };
+        let parsed: ProtocolMessage = serde_json::from_str(&text).unwrap();
+        assert_eq!(parsed.message_type, MessageType::Error);
+    }
+
+    #[tokio::test]
+    async fn a_queued_message_is_redelivered_once_the_target_reconnects() {
+        let pool = ConnectionPool::new();
+        let router = MessageRouter::new();
+
+        let target_id = Uuid::new_v4();
+        let message = ProtocolMessage::new(MessageType::Data, serde_json::json!({"target": target_id}));
+        router.route(&pool, Uuid::new_v4(), target_id, message).await.unwrap();
+
+        let (sink, mut rx) = capturing_sink();
+        let mut target = Connection::spawn(sink);
+        target.id = target_id;
+        pool.add_connection(target).await.unwrap();
+
+        router.deliver_queued(&pool, target_id).await;
+
+        let delivered = rx.recv().await.expect("queued message should be redelivered");
+        let WsMessage::Text(text) = delivered else {
+            panic!("expected a text frame");
+        };
+        let parsed: ProtocolMessage = serde_json::from_str(&text).unwrap();
+        assert_eq!(parsed.message_type, MessageType::Data);
+    }
+}
@@ -0,0 +1,71 @@
+use jsonschema::JSONSchema;
+use serde_json::Value;
+
+/// Precise validation failure for a single `(action, content)` pair,
+/// naming the offending field instead of the generic "missing field"
+/// strings handlers used to hand-roll by plucking fields out one at a
+/// time.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("invalid '{action}' payload at '{field}': {reason}")]
+pub struct ContentValidationError {
+    pub action: String,
+    pub field: String,
+    pub reason: String,
+}
+
+/// Validate `content` against `schema` for `action`, returning the first
+/// [`ContentValidationError`] JSON Schema reports.
+///
+/// `schema` is expected to already be a valid JSON Schema document — it's
+/// declared by the `Protocol` impl itself, not derived from untrusted
+/// input, so a malformed schema is a programming error and panics rather
+/// than being folded into the `Result`.
+pub fn validate_content(action: &str, schema: &Value, content: &Value) -> Result<(), ContentValidationError> {
+    let compiled = JSONSchema::compile(schema).expect("a Protocol impl's declared schema must be valid JSON Schema");
+
+    let mut errors = match compiled.validate(content) {
+        Ok(()) => return Ok(()),
+        Err(errors) => errors,
+    };
+    let error = errors.next().expect("validate() returned Err with no errors");
+    Err(ContentValidationError {
+        action: action.to_string(),
+        field: error.instance_path.to_string(),
+        reason: error.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn prompt_schema() -> Value {
+        json!({
+            "type": "object",
+            "required": ["prompt"],
+            "properties": {
+                "prompt": { "type": "string" }
+            }
+        })
+    }
+
+    #[test]
+    fn valid_content_passes() {
+        let result = validate_content("generate_code", &prompt_schema(), &json!({"prompt": "write a test"}));
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn missing_required_field_names_the_field() {
+        let err = validate_content("generate_code", &prompt_schema(), &json!({})).unwrap_err();
+        assert_eq!(err.action, "generate_code");
+        assert!(err.reason.contains("prompt"), "error should mention the missing field: {}", err.reason);
+    }
+
+    #[test]
+    fn wrong_type_names_the_offending_path() {
+        let err = validate_content("generate_code", &prompt_schema(), &json!({"prompt": 5})).unwrap_err();
+        assert_eq!(err.field, "/prompt");
+    }
+}
@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::hkdf::{KeyType, Salt, HKDF_SHA256};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+/// How long a seen nonce is remembered before [`SecurityManager::decrypt`]
+/// is willing to forget it — bounds `seen_nonces`'s memory the same way
+/// `rig-broker`'s dedup window bounds its own seen-message set, rather than
+/// growing it forever over a long-lived connection.
+const SEEN_NONCE_WINDOW: Duration = Duration::from_secs(300);
+
+/// Length marker for [`ring::hkdf::Prk::expand`], which needs a [`KeyType`]
+/// rather than a plain `usize` to know how many bytes to fill.
+struct Hkdf32;
+
+impl KeyType for Hkdf32 {
+    fn len(&self) -> usize {
+        32
+    }
+}
+
+/// Derive the JWT-signing secret from the AEAD `key_material` via
+/// HKDF-SHA256 with a context label distinct from encryption, so the two
+/// primitives never end up signing and encrypting with the same bytes.
+fn derive_jwt_secret(key_material: &[u8; 32]) -> [u8; 32] {
+    let prk = Salt::new(HKDF_SHA256, b"meap SecurityManager JWT secret v1").extract(key_material);
+    let okm = prk.expand(&[b"jwt-hmac-secret"], Hkdf32).expect("HKDF expand of a fixed 32-byte output never fails");
+    let mut secret = [0u8; 32];
+    okm.fill(&mut secret).expect("HKDF fill of a buffer matching the requested length never fails");
+    secret
+}
+
+/// Claims embedded in agent authentication tokens.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentClaims {
+    /// Agent id the token authenticates.
+    pub sub: String,
+    /// Unix timestamp the token expires at.
+    pub exp: usize,
+}
+
+/// Symmetric-key message encryption for connections that opt into it.
+///
+/// Nonces are never reused: each [`SecurityManager`] is seeded with a
+/// random 4-byte salt at construction and combines it with a monotonic
+/// counter to derive each message's nonce, so two managers (or two
+/// messages from the same one) can never collide. [`Self::decrypt`]
+/// additionally tracks every nonce it has seen within [`SEEN_NONCE_WINDOW`]
+/// and rejects replays.
+#[derive(Clone)]
+pub struct SecurityManager {
+    key: Arc<LessSafeKey>,
+    jwt_secret: Arc<[u8; 32]>,
+    salt: [u8; 4],
+    counter: Arc<AtomicU64>,
+    seen_nonces: Arc<Mutex<HashMap<[u8; NONCE_LEN], Instant>>>,
+}
+
+impl SecurityManager {
+    pub fn new(key_material: [u8; 32]) -> Self {
+        let unbound = UnboundKey::new(&AES_256_GCM, &key_material).expect("valid AES-256-GCM key");
+        let rng = SystemRandom::new();
+        let mut salt = [0u8; 4];
+        rng.fill(&mut salt).expect("system RNG is available");
+
+        Self {
+            key: Arc::new(LessSafeKey::new(unbound)),
+            jwt_secret: Arc::new(derive_jwt_secret(&key_material)),
+            salt,
+            counter: Arc::new(AtomicU64::new(0)),
+            seen_nonces: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Issue a signed JWT authenticating `agent_id`, valid for `ttl`.
+    pub fn issue_token(&self, agent_id: &str, ttl: std::time::Duration) -> Result<String, Error> {
+        let exp = (std::time::SystemTime::now() + ttl)
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_err(|_| Error::Authentication)?
+            .as_secs() as usize;
+        let claims = AgentClaims {
+            sub: agent_id.to_string(),
+            exp,
+        };
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(self.jwt_secret.as_slice()),
+        )
+        .map_err(|_| Error::Authentication)
+    }
+
+    /// Verify a JWT previously issued by [`Self::issue_token`], returning
+    /// its claims if the signature and expiry are valid.
+    pub fn verify_token(&self, token: &str) -> Result<AgentClaims, Error> {
+        decode::<AgentClaims>(
+            token,
+            &DecodingKey::from_secret(self.jwt_secret.as_slice()),
+            &Validation::default(),
+        )
+        .map(|data| data.claims)
+        .map_err(|_| Error::Authentication)
+    }
+
+    /// Derive the next nonce: 4 bytes of per-manager salt followed by an
+    /// 8-byte big-endian counter, guaranteeing uniqueness for as long as
+    /// the counter doesn't wrap (2^64 messages).
+    fn next_nonce(&self) -> [u8; NONCE_LEN] {
+        let count = self.counter.fetch_add(1, Ordering::SeqCst);
+        let mut nonce = [0u8; NONCE_LEN];
+        nonce[..4].copy_from_slice(&self.salt);
+        nonce[4..].copy_from_slice(&count.to_be_bytes());
+        nonce
+    }
+
+    /// Generate a fresh 256-bit key from a cryptographically secure RNG.
+    pub fn generate_key() -> [u8; 32] {
+        let rng = SystemRandom::new();
+        let mut key = [0u8; 32];
+        rng.fill(&mut key).expect("system RNG is available");
+        key
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, Error> {
+        let nonce_bytes = self.next_nonce();
+        let nonce = Nonce::assume_unique_for_key(nonce_bytes);
+
+        let mut in_out = plaintext.to_vec();
+        self.key
+            .seal_in_place_append_tag(nonce, Aad::empty(), &mut in_out)
+            .map_err(|_| Error::Encryption)?;
+
+        let mut out = nonce_bytes.to_vec();
+        out.append(&mut in_out);
+        Ok(out)
+    }
+
+    pub fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, Error> {
+        if ciphertext.len() < NONCE_LEN {
+            return Err(Error::Decryption);
+        }
+        let (nonce_bytes, sealed) = ciphertext.split_at(NONCE_LEN);
+        let mut nonce_array = [0u8; NONCE_LEN];
+        nonce_array.copy_from_slice(nonce_bytes);
+
+        {
+            let mut seen_nonces = self.seen_nonces.lock().unwrap();
+            let now = Instant::now();
+            seen_nonces.retain(|_, seen_at| now.duration_since(*seen_at) < SEEN_NONCE_WINDOW);
+            if seen_nonces.contains_key(&nonce_array) {
+                return Err(Error::NonceReuse);
+            }
+        }
+
+        let nonce = Nonce::try_assume_unique_for_key(nonce_bytes).map_err(|_| Error::Decryption)?;
+
+        let mut in_out = sealed.to_vec();
+        let plaintext = self
+            .key
+            .open_in_place(nonce, Aad::empty(), &mut in_out)
+            .map_err(|_| Error::Decryption)?;
+
+        self.seen_nonces.lock().unwrap().insert(nonce_array, Instant::now());
+        Ok(plaintext.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate_key_is_not_deterministic() {
+        assert_ne!(SecurityManager::generate_key(), SecurityManager::generate_key());
+    }
+
+    #[test]
+    fn successive_encryptions_use_distinct_nonces() {
+        let manager = SecurityManager::new(SecurityManager::generate_key());
+        let a = manager.encrypt(b"one").unwrap();
+        let b = manager.encrypt(b"one").unwrap();
+        assert_ne!(a[..NONCE_LEN], b[..NONCE_LEN]);
+    }
+
+    #[test]
+    fn replayed_ciphertext_is_rejected() {
+        let manager = SecurityManager::new(SecurityManager::generate_key());
+        let ciphertext = manager.encrypt(b"hello agent").unwrap();
+        manager.decrypt(&ciphertext).unwrap();
+        let err = manager.decrypt(&ciphertext).unwrap_err();
+        assert!(matches!(err, Error::NonceReuse));
+    }
+
+    #[test]
+    fn issued_token_verifies_with_matching_agent_id() {
+        let manager = SecurityManager::new(SecurityManager::generate_key());
+        let token = manager
+            .issue_token("agent-42", std::time::Duration::from_secs(60))
+            .unwrap();
+        let claims = manager.verify_token(&token).unwrap();
+        assert_eq!(claims.sub, "agent-42");
+    }
+
+    #[test]
+    fn token_signed_by_different_manager_is_rejected() {
+        let a = SecurityManager::new(SecurityManager::generate_key());
+        let b = SecurityManager::new(SecurityManager::generate_key());
+        let token = a.issue_token("agent-42", std::time::Duration::from_secs(60)).unwrap();
+        assert!(b.verify_token(&token).is_err());
+    }
+
+    #[test]
+    fn encrypt_then_decrypt_round_trips() {
+        let manager = SecurityManager::new(SecurityManager::generate_key());
+        let ciphertext = manager.encrypt(b"hello agent").unwrap();
+        assert_ne!(ciphertext, b"hello agent");
+        let plaintext = manager.decrypt(&ciphertext).unwrap();
+        assert_eq!(plaintext, b"hello agent");
+    }
+}
@@ -0,0 +1,258 @@
+use std::future::Future;
+use std::net::SocketAddr;
+
+use futures::StreamExt;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::TlsAcceptor;
+use tracing::{info, warn};
+
+use uuid::Uuid;
+
+use crate::connection::Connection;
+use crate::error::Error;
+use crate::pool::ConnectionPool;
+use crate::protocol::{MessageType, ProtocolMessage, ProtocolVersion};
+use crate::router::MessageRouter;
+
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+/// Standalone WebSocket server: accepts TCP (optionally TLS) connections,
+/// upgrades each to a WebSocket, and hands the result to a
+/// [`ConnectionPool`] so it can be broadcast to, heartbeated, and tracked
+/// like any other pooled connection. Data messages carrying a `target`
+/// agent id are handed to a [`MessageRouter`] for delivery.
+#[derive(Clone, Default)]
+pub struct MeapServer {
+    pool: ConnectionPool,
+    router: MessageRouter,
+}
+
+impl MeapServer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn pool(&self) -> &ConnectionPool {
+        &self.pool
+    }
+
+    /// Bind `addr` (use port `0` to let the OS choose one) and accept
+    /// connections in the background, upgrading each to a WebSocket
+    /// (through `tls` first, if given) and adding it to [`Self::pool`].
+    /// Returns the address actually bound to, so callers that asked for an
+    /// ephemeral port can find out which one they got.
+    pub async fn start(&self, addr: SocketAddr, tls: Option<TlsAcceptor>) -> Result<SocketAddr, Error> {
+        let listener = TcpListener::bind(addr).await?;
+        let local_addr = listener.local_addr()?;
+        info!(%local_addr, tls = tls.is_some(), "MEAP server listening");
+
+        let pool = self.pool.clone();
+        let router = self.router.clone();
+        tokio::spawn(async move {
+            loop {
+                let (stream, peer) = match listener.accept().await {
+                    Ok(accepted) => accepted,
+                    Err(err) => {
+                        warn!(%err, "MEAP server listener errored, shutting down");
+                        break;
+                    }
+                };
+                let pool = pool.clone();
+                let router = router.clone();
+                let tls = tls.clone();
+                tokio::spawn(async move {
+                    if let Err(err) = Self::accept_connection(stream, tls, pool, router).await {
+                        warn!(%peer, %err, "failed to accept connection");
+                    }
+                });
+            }
+        });
+
+        Ok(local_addr)
+    }
+
+    /// Like [`Self::start`], but stops accepting and closes every pooled
+    /// connection as soon as `shutdown` resolves, rather than running
+    /// forever. Peers get a WebSocket close frame instead of the socket
+    /// just dropping.
+    pub async fn start_with_shutdown(
+        &self,
+        addr: SocketAddr,
+        tls: Option<TlsAcceptor>,
+        shutdown: impl Future<Output = ()> + Send + 'static,
+    ) -> Result<SocketAddr, Error> {
+        let listener = TcpListener::bind(addr).await?;
+        let local_addr = listener.local_addr()?;
+        info!(%local_addr, tls = tls.is_some(), "MEAP server listening");
+
+        let pool = self.pool.clone();
+        let router = self.router.clone();
+        tokio::spawn(async move {
+            tokio::pin!(shutdown);
+            loop {
+                tokio::select! {
+                    accepted = listener.accept() => {
+                        let (stream, peer) = match accepted {
+                            Ok(accepted) => accepted,
+                            Err(err) => {
+                                warn!(%err, "MEAP server listener errored, shutting down");
+                                break;
+                            }
+                        };
+                        let pool = pool.clone();
+                        let router = router.clone();
+                        let tls = tls.clone();
+                        tokio::spawn(async move {
+                            if let Err(err) = Self::accept_connection(stream, tls, pool, router).await {
+                                warn!(%peer, %err, "failed to accept connection");
+                            }
+                        });
+                    }
+                    _ = &mut shutdown => {
+                        info!("MEAP server shutdown requested, closing connections");
+                        pool.close_all().await;
+                        break;
+                    }
+                }
+            }
+        });
+
+        Ok(local_addr)
+    }
+
+    async fn accept_connection(
+        stream: TcpStream,
+        tls: Option<TlsAcceptor>,
+        pool: ConnectionPool,
+        router: MessageRouter,
+    ) -> Result<(), Error> {
+        match tls {
+            Some(acceptor) => Self::upgrade_and_pool(acceptor.accept(stream).await?, pool, router).await,
+            None => Self::upgrade_and_pool(stream, pool, router).await,
+        }
+    }
+
+    async fn upgrade_and_pool<S>(stream: S, pool: ConnectionPool, router: MessageRouter) -> Result<(), Error>
+    where
+        S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let ws = tokio_tungstenite::accept_async(stream).await?;
+        let (sink, mut incoming) = ws.split();
+
+        let connection = Connection::spawn(sink);
+        let id = connection.id;
+        if let Err(err) = pool.add_connection(connection.clone()).await {
+            warn!(%id, %err, "rejecting connection, pool is full");
+            let _ = connection.send_close();
+            return Ok(());
+        }
+
+        // Register immediately with no capabilities, rather than waiting
+        // on a frame that may never arrive: a `Connect` message updates
+        // them later (see the drain loop below), but the agent is
+        // considered present as soon as it's pooled.
+        pool.register_agent(id, Vec::new()).await;
+        router.deliver_queued(&pool, id).await;
+        broadcast_presence(&pool, id, "connected").await;
+        info!(%id, "agent connected");
+
+        if let Err(err) = send_handshake(&connection).await {
+            warn!(%id, %err, "failed to send handshake");
+        }
+
+        tokio::spawn(async move {
+            while let Some(frame) = incoming.next().await {
+                let Ok(frame) = frame else { continue };
+                if let Some(message) = connect_message_from_frame(&frame) {
+                    let peer_version = ProtocolVersion(message.protocol_version);
+                    if !peer_version.is_compatible() {
+                        warn!(%id, peer_version = message.protocol_version, "closing connection with incompatible protocol version");
+                        let _ = connection.send_close();
+                        break;
+                    }
+                    if let Some(capabilities) = capabilities_from_message(&message) {
+                        pool.register_agent(id, capabilities).await;
+                    }
+                    continue;
+                }
+                if let Some((target_id, message)) = target_from_frame(&frame) {
+                    if let Err(err) = router.route(&pool, id, target_id, message).await {
+                        warn!(%id, %target_id, %err, "failed to route message");
+                    }
+                }
+            }
+            pool.remove_connection(id).await;
+            broadcast_presence(&pool, id, "disconnected").await;
+            info!(%id, "agent disconnected");
+        });
+
+        Ok(())
+    }
+}
+
+/// Send the initial `Connect` handshake carrying this side's
+/// [`PROTOCOL_VERSION`](crate::protocol::PROTOCOL_VERSION) (set
+/// automatically by [`ProtocolMessage::new`]) and capabilities, so the peer
+/// can detect a mismatch before exchanging anything else.
+async fn send_handshake(connection: &Connection) -> Result<(), Error> {
+    let handshake = ProtocolMessage::new(MessageType::Connect, serde_json::json!({ "capabilities": Vec::<String>::new() }));
+    let payload = serde_json::to_string(&handshake)?;
+    connection.send(payload).await
+}
+
+/// Broadcast a `MessageType::Status` presence update for `agent_id` to
+/// every other connected agent, so rosters built from registration events
+/// don't go stale as agents join and leave.
+async fn broadcast_presence(pool: &ConnectionPool, agent_id: Uuid, status: &str) {
+    let message = ProtocolMessage::new(
+        MessageType::Status,
+        serde_json::json!({ "agent_id": agent_id, "status": status }),
+    );
+    if let Ok(payload) = serde_json::to_string(&message) {
+        pool.broadcast_except(agent_id, payload).await;
+    }
+}
+
+/// Parse `frame` as a [`ProtocolMessage`] addressed to another agent via a
+/// `target` payload field. Returns `None` for anything that isn't a
+/// recognizable `MessageType::Data` envelope with a valid target id.
+fn target_from_frame(frame: &WsMessage) -> Option<(Uuid, ProtocolMessage)> {
+    let message: ProtocolMessage = match frame {
+        WsMessage::Text(text) => serde_json::from_str(text).ok()?,
+        WsMessage::Binary(bytes) => serde_json::from_slice(bytes).ok()?,
+        _ => return None,
+    };
+    if message.message_type != MessageType::Data {
+        return None;
+    }
+    let target_id = Uuid::parse_str(message.payload.get("target")?.as_str()?).ok()?;
+    Some((target_id, message))
+}
+
+/// Parse `frame` as a [`MessageType::Connect`] [`ProtocolMessage`] — the
+/// registration/handshake frame carrying the peer's protocol version and
+/// capabilities. Returns `None` for anything else (e.g. a ping, malformed
+/// JSON, or a message of a different type).
+fn connect_message_from_frame(frame: &WsMessage) -> Option<ProtocolMessage> {
+    let message: ProtocolMessage = match frame {
+        WsMessage::Text(text) => serde_json::from_str(text).ok()?,
+        WsMessage::Binary(bytes) => serde_json::from_slice(bytes).ok()?,
+        _ => return None,
+    };
+    (message.message_type == MessageType::Connect).then_some(message)
+}
+
+/// Pull `message`'s `capabilities` payload field, if present. Returns
+/// `None` for a registration message that didn't include one at all, as
+/// distinct from one that simply advertised no capabilities (`Some(vec![])`).
+fn capabilities_from_message(message: &ProtocolMessage) -> Option<Vec<String>> {
+    let capabilities = message
+        .payload
+        .get("capabilities")?
+        .as_array()?
+        .iter()
+        .filter_map(|value| value.as_str().map(str::to_string))
+        .collect();
+    Some(capabilities)
+}
@@ -0,0 +1,86 @@
+use crate::error::Error;
+
+/// Dot product of two equal-length vectors.
+pub fn dot(a: &[f32], b: &[f32]) -> Result<f32, Error> {
+    check_dimensions(a, b)?;
+    Ok(a.iter().zip(b).map(|(x, y)| x * y).sum())
+}
+
+/// Euclidean (L2) distance between two equal-length vectors.
+pub fn euclidean(a: &[f32], b: &[f32]) -> Result<f32, Error> {
+    check_dimensions(a, b)?;
+    Ok(a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum::<f32>().sqrt())
+}
+
+/// Cosine similarity between two equal-length vectors, in `[-1, 1]`.
+/// Zero if either vector has zero magnitude.
+pub fn cosine(a: &[f32], b: &[f32]) -> Result<f32, Error> {
+    check_dimensions(a, b)?;
+    let magnitude_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let magnitude_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if magnitude_a == 0.0 || magnitude_b == 0.0 {
+        return Ok(0.0);
+    }
+    Ok(dot(a, b)? / (magnitude_a * magnitude_b))
+}
+
+/// Sort `candidates` by cosine similarity to `query`, highest first, and
+/// keep the top `top_k`.
+pub fn rerank<T>(query: &[f32], candidates: Vec<(T, &[f32])>, top_k: usize) -> Result<Vec<T>, Error> {
+    let mut scored = candidates
+        .into_iter()
+        .map(|(item, vector)| cosine(query, vector).map(|score| (score, item)))
+        .collect::<Result<Vec<_>, _>>()?;
+    scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    scored.truncate(top_k);
+    Ok(scored.into_iter().map(|(_, item)| item).collect())
+}
+
+fn check_dimensions(a: &[f32], b: &[f32]) -> Result<(), Error> {
+    if a.len() != b.len() {
+        Err(Error::DimensionMismatch { expected: a.len(), actual: b.len() })
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orthogonal_vectors_have_zero_cosine_similarity() {
+        let similarity = cosine(&[1.0, 0.0], &[0.0, 1.0]).unwrap();
+        assert!(similarity.abs() < 1e-6);
+    }
+
+    #[test]
+    fn identical_vectors_have_unit_cosine_similarity() {
+        let similarity = cosine(&[1.0, 2.0, 3.0], &[1.0, 2.0, 3.0]).unwrap();
+        assert!((similarity - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn mismatched_lengths_are_rejected() {
+        let err = cosine(&[1.0, 2.0], &[1.0]).unwrap_err();
+        assert!(matches!(err, Error::DimensionMismatch { expected: 2, actual: 1 }));
+    }
+
+    #[test]
+    fn rerank_sorts_candidates_by_similarity_to_the_query() {
+        let query = [1.0, 0.0];
+        let close = [0.9, 0.1];
+        let far = [0.0, 1.0];
+        let ranked = rerank(&query, vec![("far", far.as_slice()), ("close", close.as_slice())], 2).unwrap();
+        assert_eq!(ranked, vec!["close", "far"]);
+    }
+
+    #[test]
+    fn rerank_truncates_to_top_k() {
+        let query = [1.0, 0.0];
+        let a = [0.9, 0.1];
+        let b = [0.0, 1.0];
+        let ranked = rerank(&query, vec![("a", a.as_slice()), ("b", b.as_slice())], 1).unwrap();
+        assert_eq!(ranked, vec!["a"]);
+    }
+}
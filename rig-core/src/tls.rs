@@ -0,0 +1,75 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::Arc;
+
+use rustls::{ClientConfig, RootCertStore, ServerConfig};
+use tokio_rustls::{TlsAcceptor, TlsConnector};
+
+use crate::error::Error;
+
+/// Build a client [`ClientConfig`] that performs real certificate chain and
+/// hostname verification against the Mozilla root store, plus `extra_ca_cert`
+/// (a PEM-encoded CA certificate) if given — for dialing a server whose
+/// certificate is signed by a private CA rather than a public one.
+pub fn client_tls_config(extra_ca_cert: Option<&Path>) -> Result<Arc<ClientConfig>, Error> {
+    let mut roots = RootCertStore::empty();
+    roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+
+    if let Some(path) = extra_ca_cert {
+        let certs = rustls_pemfile::certs(&mut BufReader::new(File::open(path)?)).collect::<Result<Vec<_>, _>>()?;
+        for cert in certs {
+            roots.add(cert).map_err(|_| {
+                Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, "invalid CA certificate"))
+            })?;
+        }
+    }
+
+    let config = ClientConfig::builder()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+
+    Ok(Arc::new(config))
+}
+
+/// Build a [`TlsConnector`] that performs real certificate chain and
+/// hostname verification against the Mozilla root store — previously the
+/// connector here skipped verification entirely, which let a
+/// man-in-the-middle present any certificate without being rejected.
+pub fn client_connector() -> TlsConnector {
+    TlsConnector::from(client_tls_config(None).expect("no extra CA cert means this cannot fail"))
+}
+
+/// Build a [`TlsAcceptor`] for [`crate::MeapServer`] from a PEM-encoded
+/// certificate chain and private key on disk.
+pub fn server_acceptor(cert_path: impl AsRef<Path>, key_path: impl AsRef<Path>) -> Result<TlsAcceptor, Error> {
+    let certs = rustls_pemfile::certs(&mut BufReader::new(File::open(cert_path)?))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut keys = rustls_pemfile::pkcs8_private_keys(&mut BufReader::new(File::open(key_path)?))
+        .collect::<Result<Vec<_>, _>>()?;
+    let key = keys.pop().ok_or_else(|| {
+        Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, "no private key found in key file"))
+    })?;
+
+    let config = ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(certs, key.into())?;
+
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn client_connector_builds_successfully() {
+        let _connector = client_connector();
+    }
+
+    #[test]
+    fn server_acceptor_errors_on_missing_files() {
+        let result = server_acceptor("/no/such/cert.pem", "/no/such/key.pem");
+        assert!(result.is_err());
+    }
+}
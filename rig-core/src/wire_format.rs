@@ -0,0 +1,85 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+use crate::error::Error;
+use crate::protocol::ProtocolMessage;
+
+/// Wire encoding used for a [`crate::Connection`]'s outbound messages.
+/// `Json` is the historical default; `MsgPack` trades human-readability
+/// for a denser binary encoding on throughput-sensitive deployments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WireFormat {
+    #[default]
+    Json,
+    MsgPack,
+}
+
+/// Serialize `value` per `format`.
+pub fn encode<T: Serialize>(value: &T, format: WireFormat) -> Result<Vec<u8>, Error> {
+    match format {
+        WireFormat::Json => serde_json::to_vec(value).map_err(Error::from),
+        WireFormat::MsgPack => rmp_serde::to_vec(value).map_err(|err| Error::WireFormat(err.to_string())),
+    }
+}
+
+/// Deserialize bytes previously produced by [`encode`] with the same
+/// `format`.
+pub fn decode<T: DeserializeOwned>(bytes: &[u8], format: WireFormat) -> Result<T, Error> {
+    match format {
+        WireFormat::Json => serde_json::from_slice(bytes).map_err(Error::from),
+        WireFormat::MsgPack => rmp_serde::from_slice(bytes).map_err(|err| Error::WireFormat(err.to_string())),
+    }
+}
+
+/// Decode an incoming WebSocket frame into a [`ProtocolMessage`] per
+/// `format` — the read-side counterpart to [`crate::Connection::send_message`].
+/// Callers feeding frames into a [`crate::ProtocolRouter`] should decode
+/// with this rather than assuming `Text` means JSON, since a `MsgPack`
+/// connection always sends `Binary` frames.
+pub fn decode_incoming(frame: &WsMessage, format: WireFormat) -> Result<ProtocolMessage, Error> {
+    match frame {
+        WsMessage::Text(text) => decode(text.as_bytes(), format),
+        WsMessage::Binary(bytes) => decode(bytes, format),
+        _ => Err(Error::WireFormat("expected a text or binary frame".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::MessageType;
+
+    #[test]
+    fn msgpack_encodes_smaller_than_json_for_the_same_message() {
+        let message = ProtocolMessage::new(MessageType::Data, serde_json::json!({ "hello": "world", "count": 42 }));
+
+        let json_bytes = encode(&message, WireFormat::Json).unwrap();
+        let msgpack_bytes = encode(&message, WireFormat::MsgPack).unwrap();
+
+        assert!(
+            msgpack_bytes.len() < json_bytes.len(),
+            "msgpack ({} bytes) should be smaller than json ({} bytes)",
+            msgpack_bytes.len(),
+            json_bytes.len()
+        );
+    }
+
+    #[test]
+    fn json_round_trips() {
+        let message = ProtocolMessage::new(MessageType::Heartbeat, serde_json::Value::Null);
+        let bytes = encode(&message, WireFormat::Json).unwrap();
+        let decoded: ProtocolMessage = decode(&bytes, WireFormat::Json).unwrap();
+        assert_eq!(decoded.id, message.id);
+        assert_eq!(decoded.message_type, message.message_type);
+    }
+
+    #[test]
+    fn msgpack_round_trips() {
+        let message = ProtocolMessage::new(MessageType::Heartbeat, serde_json::Value::Null);
+        let bytes = encode(&message, WireFormat::MsgPack).unwrap();
+        let decoded: ProtocolMessage = decode(&bytes, WireFormat::MsgPack).unwrap();
+        assert_eq!(decoded.id, message.id);
+        assert_eq!(decoded.message_type, message.message_type);
+    }
+}
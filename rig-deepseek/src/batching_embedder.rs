@@ -0,0 +1,172 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::{mpsc, oneshot};
+
+use crate::deepseek_handler::DeepseekHandler;
+use crate::error::DeepseekError;
+
+/// A single caller's embed request, parked on the batcher's queue until a
+/// batch fires.
+struct PendingEmbed {
+    input: String,
+    result_tx: oneshot::Sender<Result<Vec<f32>, DeepseekError>>,
+}
+
+/// Coalesces concurrent [`DeepseekHandler::generate_embeddings`] calls into
+/// one request: each [`Self::embed`] enqueues onto a batching task (spawned
+/// in [`Self::new`]) that waits up to `window` — or until `max_batch`
+/// requests have piled up, whichever comes first — then issues a single
+/// `/v1/embeddings` call covering everything queued and fans the results
+/// back out via each caller's oneshot channel.
+pub struct BatchingEmbedder {
+    request_tx: mpsc::Sender<PendingEmbed>,
+}
+
+impl BatchingEmbedder {
+    pub fn new(handler: Arc<DeepseekHandler>, window: Duration, max_batch: usize) -> Self {
+        let (request_tx, request_rx) = mpsc::channel(256);
+        tokio::spawn(Self::run_batcher(request_rx, handler, window, max_batch));
+        Self { request_tx }
+    }
+
+    /// Embed `input`, joining whatever batch is currently forming (or
+    /// starting a new one if none is).
+    pub async fn embed(&self, input: impl Into<String>) -> Result<Vec<f32>, DeepseekError> {
+        let (result_tx, result_rx) = oneshot::channel();
+        self.request_tx
+            .send(PendingEmbed { input: input.into(), result_tx })
+            .await
+            .map_err(|_| DeepseekError::BatchFailed("batching embedder has shut down".to_string()))?;
+        result_rx.await.map_err(|_| DeepseekError::BatchFailed("batching embedder dropped the request".to_string()))?
+    }
+
+    /// Owns `request_rx` for the lifetime of the embedder: repeatedly
+    /// collects one batch's worth of requests and dispatches them together.
+    async fn run_batcher(
+        mut request_rx: mpsc::Receiver<PendingEmbed>,
+        handler: Arc<DeepseekHandler>,
+        window: Duration,
+        max_batch: usize,
+    ) {
+        while let Some(first) = request_rx.recv().await {
+            let mut batch = vec![first];
+            let deadline = tokio::time::sleep(window);
+            tokio::pin!(deadline);
+
+            while batch.len() < max_batch.max(1) {
+                tokio::select! {
+                    _ = &mut deadline => break,
+                    next = request_rx.recv() => {
+                        match next {
+                            Some(request) => batch.push(request),
+                            None => break,
+                        }
+                    }
+                }
+            }
+
+            Self::dispatch_batch(&handler, batch).await;
+        }
+    }
+
+    async fn dispatch_batch(handler: &DeepseekHandler, batch: Vec<PendingEmbed>) {
+        let inputs: Vec<String> = batch.iter().map(|request| request.input.clone()).collect();
+
+        match handler.generate_embeddings(&inputs).await {
+            Ok(embeddings) => {
+                for (request, embedding) in batch.into_iter().zip(embeddings) {
+                    let _ = request.result_tx.send(Ok(embedding));
+                }
+            }
+            Err(err) => {
+                let message = err.to_string();
+                for request in batch {
+                    let _ = request.result_tx.send(Err(DeepseekError::BatchFailed(message.clone())));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use axum::extract::State;
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use tokio::net::TcpListener;
+
+    use super::*;
+    use crate::model::{ModelCapability, ModelMetadata};
+    use crate::throttled_client::ThrottledClient;
+
+    fn embeddable_model() -> ModelMetadata {
+        ModelMetadata {
+            name: "deepseek-embed".to_string(),
+            context_window: 8192,
+            capabilities: vec![ModelCapability::Embeddings],
+            embedding_dimension: Some(3),
+            available: true,
+        }
+    }
+
+    async fn spawn_counting_embeddings_server() -> (String, Arc<AtomicU32>) {
+        let call_count = Arc::new(AtomicU32::new(0));
+
+        async fn embeddings_handler(State(call_count): State<Arc<AtomicU32>>, Json(body): Json<serde_json::Value>) -> Json<serde_json::Value> {
+            call_count.fetch_add(1, Ordering::SeqCst);
+            let input = body["input"].as_array().cloned().unwrap_or_default();
+            let data: Vec<serde_json::Value> =
+                input.iter().map(|_| serde_json::json!({ "embedding": [0.1, 0.2, 0.3] })).collect();
+            Json(serde_json::json!({ "data": data }))
+        }
+
+        let app = Router::new().route("/v1/embeddings", post(embeddings_handler)).with_state(call_count.clone());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        (format!("http://{addr}"), call_count)
+    }
+
+    #[tokio::test]
+    async fn ten_concurrent_single_item_requests_are_served_by_one_batch_call() {
+        let (base_url, call_count) = spawn_counting_embeddings_server().await;
+        let handler =
+            Arc::new(DeepseekHandler::new(ThrottledClient::new(reqwest::Client::new()), base_url, embeddable_model()));
+        let embedder = Arc::new(BatchingEmbedder::new(handler, Duration::from_millis(20), 32));
+
+        let mut handles = Vec::new();
+        for i in 0..10 {
+            let embedder = embedder.clone();
+            handles.push(tokio::spawn(async move { embedder.embed(format!("input {i}")).await }));
+        }
+
+        for handle in handles {
+            let embedding = handle.await.unwrap().unwrap();
+            assert_eq!(embedding, vec![0.1, 0.2, 0.3]);
+        }
+
+        assert_eq!(call_count.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn a_failed_batch_call_reports_the_same_error_to_every_waiter() {
+        let handler = Arc::new(DeepseekHandler::new(
+            ThrottledClient::new(reqwest::Client::new()),
+            "http://127.0.0.1:1".to_string(),
+            embeddable_model(),
+        ));
+        let embedder = Arc::new(BatchingEmbedder::new(handler, Duration::from_millis(10), 4));
+
+        let first = embedder.embed("a".to_string());
+        let second = embedder.embed("b".to_string());
+        let (first, second) = tokio::join!(first, second);
+
+        assert!(matches!(first, Err(DeepseekError::BatchFailed(_))));
+        assert!(matches!(second, Err(DeepseekError::BatchFailed(_))));
+    }
+}
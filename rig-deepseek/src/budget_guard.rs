@@ -0,0 +1,51 @@
+use std::sync::Mutex;
+
+/// Tracks cumulative spend against a fixed total budget, shared across
+/// concurrent callers via an internal lock (spend amounts are `f64`, so a
+/// plain atomic won't do).
+pub struct BudgetGuard {
+    total: f64,
+    spent: Mutex<f64>,
+}
+
+impl BudgetGuard {
+    pub fn new(total: f64) -> Self {
+        Self {
+            total,
+            spent: Mutex::new(0.0),
+        }
+    }
+
+    /// Budget left to spend. Never negative, even if `record_spend` has
+    /// overshot `total`.
+    pub fn remaining(&self) -> f64 {
+        (self.total - *self.spent.lock().unwrap()).max(0.0)
+    }
+
+    pub fn record_spend(&self, amount: f64) {
+        *self.spent.lock().unwrap() += amount;
+    }
+
+    pub fn total_spent(&self) -> f64 {
+        *self.spent.lock().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remaining_decreases_as_spend_is_recorded() {
+        let guard = BudgetGuard::new(10.0);
+        guard.record_spend(4.0);
+        assert_eq!(guard.remaining(), 6.0);
+    }
+
+    #[test]
+    fn remaining_never_goes_negative() {
+        let guard = BudgetGuard::new(5.0);
+        guard.record_spend(9.0);
+        assert_eq!(guard.remaining(), 0.0);
+    }
+}
@@ -0,0 +1,651 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::DeepseekError;
+use crate::model::{ModelCapability, ModelMetadata};
+use crate::model_selector::ModelSelector;
+use crate::throttled_client::ThrottledClient;
+
+#[derive(Serialize)]
+struct EmbeddingsRequest<'a> {
+    model: &'a str,
+    input: &'a [String],
+}
+
+#[derive(Deserialize)]
+struct EmbeddingsResponse {
+    data: Vec<EmbeddingData>,
+    #[serde(default)]
+    usage: Option<UsageField>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    embedding: Vec<f32>,
+}
+
+#[derive(Serialize)]
+struct ChatCompletionsRequest<'a> {
+    model: &'a str,
+    messages: &'a [Value],
+    #[serde(skip_serializing_if = "<[Value]>::is_empty")]
+    tools: &'a [Value],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct ChatCompletionsResponse {
+    choices: Vec<ChatChoice>,
+    #[serde(default)]
+    usage: Option<UsageField>,
+}
+
+/// Raw `usage` object Deepseek includes on completions/embeddings
+/// responses, before it's folded into a model's running [`TokenUsage`].
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+struct UsageField {
+    #[serde(default)]
+    prompt_tokens: u64,
+    #[serde(default)]
+    completion_tokens: u64,
+}
+
+/// Cumulative prompt/completion tokens spent against one model, as
+/// reported by [`DeepseekHandler::usage_report`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+impl TokenUsage {
+    pub fn total_tokens(&self) -> usize {
+        (self.prompt_tokens + self.completion_tokens) as usize
+    }
+
+    fn accumulate(&mut self, usage: UsageField) {
+        self.prompt_tokens += usage.prompt_tokens;
+        self.completion_tokens += usage.completion_tokens;
+    }
+}
+
+#[derive(Deserialize)]
+struct ChatChoice {
+    message: ChatMessage,
+}
+
+#[derive(Deserialize, Default)]
+struct ChatMessage {
+    #[serde(default)]
+    content: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<RawToolCall>,
+}
+
+/// Schema for the `"generate_code"` protocol action, declared up front so
+/// [`rig_core::validate_content`] can reject a malformed payload with a
+/// precise "which field, why" error instead of a generic one raised while
+/// plucking `payload["prompt"]` out by hand.
+fn generate_code_schema() -> Value {
+    json!({
+        "type": "object",
+        "required": ["prompt"],
+        "properties": {
+            "prompt": { "type": "string" }
+        }
+    })
+}
+
+#[derive(Deserialize)]
+struct RawToolCall {
+    id: String,
+    function: RawToolCallFunction,
+}
+
+#[derive(Deserialize)]
+struct RawToolCallFunction {
+    name: String,
+    arguments: String,
+}
+
+/// A tool invocation the model requested, with `arguments` already parsed
+/// from the wire format's JSON-encoded string into structured JSON.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct ToolCall {
+    pub id: String,
+    pub name: String,
+    pub arguments: Value,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct GenerationCacheKey {
+    model: String,
+    prompt: String,
+    temperature_bits: u64,
+    max_tokens: Option<u32>,
+}
+
+struct GenerationCacheEntry {
+    value: String,
+    inserted_at: Instant,
+}
+
+/// Caches [`DeepseekHandler::generate_code`] results keyed by
+/// `(model, prompt, temperature, max_tokens)`, evicting the
+/// least-recently-used entry once `capacity` is exceeded and treating any
+/// entry older than `ttl` as a miss. Off by default — construct one and
+/// pass it to [`DeepseekHandler::with_generation_cache`] to enable it.
+pub struct GenerationCache {
+    capacity: usize,
+    ttl: Duration,
+    /// Whether a non-zero temperature (non-deterministic generation) may
+    /// still be cached. Off by default, since caching a "random" response
+    /// silently makes it deterministic for the caller.
+    allow_nondeterministic: bool,
+    entries: Mutex<HashMap<GenerationCacheKey, GenerationCacheEntry>>,
+    /// Recency order, least-recently-used at the front.
+    order: Mutex<VecDeque<GenerationCacheKey>>,
+}
+
+impl GenerationCache {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            allow_nondeterministic: false,
+            entries: Mutex::new(HashMap::new()),
+            order: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    pub fn with_nondeterministic_caching(mut self, allow: bool) -> Self {
+        self.allow_nondeterministic = allow;
+        self
+    }
+
+    fn key_for(&self, model: &str, prompt: &str, temperature: f64, max_tokens: Option<u32>) -> Option<GenerationCacheKey> {
+        if temperature != 0.0 && !self.allow_nondeterministic {
+            return None;
+        }
+        Some(GenerationCacheKey {
+            model: model.to_string(),
+            prompt: prompt.to_string(),
+            temperature_bits: temperature.to_bits(),
+            max_tokens,
+        })
+    }
+
+    fn get(&self, key: &GenerationCacheKey) -> Option<String> {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.get(key)?;
+        if entry.inserted_at.elapsed() > self.ttl {
+            entries.remove(key);
+            self.order.lock().unwrap().retain(|k| k != key);
+            return None;
+        }
+        let value = entry.value.clone();
+
+        let mut order = self.order.lock().unwrap();
+        order.retain(|k| k != key);
+        order.push_back(key.clone());
+
+        Some(value)
+    }
+
+    fn put(&self, key: GenerationCacheKey, value: String) {
+        let mut entries = self.entries.lock().unwrap();
+        let mut order = self.order.lock().unwrap();
+
+        if !entries.contains_key(&key) && entries.len() >= self.capacity {
+            if let Some(oldest) = order.pop_front() {
+                entries.remove(&oldest);
+            }
+        }
+
+        order.retain(|k| k != &key);
+        order.push_back(key.clone());
+        entries.insert(key, GenerationCacheEntry { value, inserted_at: Instant::now() });
+    }
+}
+
+/// Talks to a single Deepseek model's API surface, dispatching MEAP
+/// protocol actions (`"embed"`, etc.) to the matching HTTP call.
+pub struct DeepseekHandler {
+    client: ThrottledClient,
+    base_url: String,
+    model: ModelMetadata,
+    usage: Mutex<HashMap<String, TokenUsage>>,
+    cache: Option<GenerationCache>,
+}
+
+impl DeepseekHandler {
+    pub fn new(client: ThrottledClient, base_url: impl Into<String>, model: ModelMetadata) -> Self {
+        Self {
+            client,
+            base_url: base_url.into(),
+            model,
+            usage: Mutex::new(HashMap::new()),
+            cache: None,
+        }
+    }
+
+    pub fn with_generation_cache(mut self, cache: GenerationCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Snapshot of tokens spent so far, per model this handler has made
+    /// requests for (in practice just this handler's own model, but keyed
+    /// by name so a caller tracking several handlers can merge reports).
+    pub fn usage_report(&self) -> HashMap<String, TokenUsage> {
+        self.usage.lock().unwrap().clone()
+    }
+
+    /// Running cost estimate for everything recorded in [`Self::usage_report`],
+    /// priced via `selector`.
+    pub fn cost_estimate(&self, selector: &ModelSelector) -> Result<f64, DeepseekError> {
+        let mut total = 0.0;
+        for (model, usage) in self.usage_report() {
+            total += selector.calculate_cost(&model, usage.total_tokens())?;
+        }
+        Ok(total)
+    }
+
+    fn record_usage(&self, usage: Option<UsageField>) {
+        let Some(usage) = usage else { return };
+        self.usage.lock().unwrap().entry(self.model.name.clone()).or_default().accumulate(usage);
+    }
+
+    /// Embed `inputs` via `/v1/embeddings`, returning one vector per input
+    /// in the same order.
+    pub async fn generate_embeddings(&self, inputs: &[String]) -> Result<Vec<Vec<f32>>, DeepseekError> {
+        if !self.model.supports(ModelCapability::Embeddings) {
+            return Err(DeepseekError::UnsupportedCapability {
+                model: self.model.name.clone(),
+                capability: "embeddings",
+            });
+        }
+
+        let url = format!("{}/v1/embeddings", self.base_url);
+        let body = EmbeddingsRequest {
+            model: &self.model.name,
+            input: inputs,
+        };
+
+        let response = self
+            .client
+            .send_request(|http| http.post(&url).json(&body))
+            .await?
+            .json::<EmbeddingsResponse>()
+            .await?;
+
+        self.record_usage(response.usage);
+        Ok(response.data.into_iter().map(|d| d.embedding).collect())
+    }
+
+    /// Send `messages` with the OpenAI-style `tools` JSON-schema array via
+    /// `/v1/chat/completions`, returning any tool calls the model emits.
+    /// Errors if the configured model doesn't advertise
+    /// [`ModelCapability::FunctionCalling`].
+    pub async fn call_with_tools(&self, messages: &[Value], tools: &[Value]) -> Result<Vec<ToolCall>, DeepseekError> {
+        if !self.model.supports(ModelCapability::FunctionCalling) {
+            return Err(DeepseekError::UnsupportedCapability {
+                model: self.model.name.clone(),
+                capability: "function_calling",
+            });
+        }
+
+        let url = format!("{}/v1/chat/completions", self.base_url);
+        let body = ChatCompletionsRequest {
+            model: &self.model.name,
+            messages,
+            tools,
+            temperature: None,
+            max_tokens: None,
+        };
+
+        let response = self
+            .client
+            .send_request(|http| http.post(&url).json(&body))
+            .await?
+            .json::<ChatCompletionsResponse>()
+            .await?;
+
+        self.record_usage(response.usage);
+
+        let message = response
+            .choices
+            .into_iter()
+            .next()
+            .map(|choice| choice.message)
+            .unwrap_or_default();
+
+        message
+            .tool_calls
+            .into_iter()
+            .map(|raw| {
+                Ok(ToolCall {
+                    id: raw.id,
+                    name: raw.function.name,
+                    arguments: serde_json::from_str(&raw.function.arguments)?,
+                })
+            })
+            .collect()
+    }
+
+    /// Generate code for `prompt` via `/v1/chat/completions`, returning
+    /// the model's raw text response. Short-circuits on a cache hit if
+    /// [`Self::with_generation_cache`] was configured and `temperature`
+    /// makes the call eligible for caching.
+    pub async fn generate_code(&self, prompt: &str, temperature: f64, max_tokens: Option<u32>) -> Result<String, DeepseekError> {
+        let cache_key = self.cache.as_ref().and_then(|cache| cache.key_for(&self.model.name, prompt, temperature, max_tokens));
+
+        if let (Some(cache), Some(key)) = (&self.cache, &cache_key) {
+            if let Some(cached) = cache.get(key) {
+                return Ok(cached);
+            }
+        }
+
+        let url = format!("{}/v1/chat/completions", self.base_url);
+        let body = ChatCompletionsRequest {
+            model: &self.model.name,
+            messages: &[json!({ "role": "user", "content": prompt })],
+            tools: &[],
+            temperature: Some(temperature),
+            max_tokens,
+        };
+
+        let response = self
+            .client
+            .send_request(|http| http.post(&url).json(&body))
+            .await?
+            .json::<ChatCompletionsResponse>()
+            .await?;
+
+        self.record_usage(response.usage);
+        let code = response.choices.into_iter().next().and_then(|choice| choice.message.content).unwrap_or_default();
+
+        if let (Some(cache), Some(key)) = (&self.cache, cache_key) {
+            cache.put(key, code.clone());
+        }
+
+        Ok(code)
+    }
+
+    /// Dispatch a protocol action by name, matching the string-keyed
+    /// actions MEAP agents use for Deepseek-backed tools.
+    pub async fn handle_action(&self, action: &str, payload: Value) -> Result<Value, DeepseekError> {
+        match action {
+            "embed" => {
+                let inputs: Vec<String> = serde_json::from_value(payload["inputs"].clone())?;
+                let embeddings = self.generate_embeddings(&inputs).await?;
+                Ok(json!({ "embeddings": embeddings }))
+            }
+            "call_with_tools" => {
+                let messages: Vec<Value> = serde_json::from_value(payload["messages"].clone())?;
+                let tools: Vec<Value> = serde_json::from_value(payload["tools"].clone())?;
+                let tool_calls = self.call_with_tools(&messages, &tools).await?;
+                Ok(json!({ "tool_calls": tool_calls }))
+            }
+            "generate_code" => {
+                rig_core::validate_content("generate_code", &generate_code_schema(), &payload)?;
+                let temperature = payload["temperature"].as_f64().unwrap_or(0.0);
+                let max_tokens = payload["max_tokens"].as_u64().map(|n| n as u32);
+                let code = self.generate_code(payload["prompt"].as_str().unwrap_or_default(), temperature, max_tokens).await?;
+                Ok(json!({ "code": code }))
+            }
+            other => Err(DeepseekError::UnknownAction(other.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Capability, ModelSpec};
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use tokio::net::TcpListener;
+
+    fn embeddable_model() -> ModelMetadata {
+        ModelMetadata {
+            name: "deepseek-embed".to_string(),
+            context_window: 8192,
+            capabilities: vec![ModelCapability::Embeddings],
+            embedding_dimension: Some(4),
+            available: true,
+        }
+    }
+
+    fn tool_calling_model() -> ModelMetadata {
+        ModelMetadata {
+            name: "deepseek-coder-33b-instruct".to_string(),
+            context_window: 16384,
+            capabilities: vec![ModelCapability::Chat, ModelCapability::FunctionCalling],
+            embedding_dimension: None,
+            available: true,
+        }
+    }
+
+    async fn embeddings_handler(Json(req): Json<Value>) -> Json<Value> {
+        let count = req["input"].as_array().map(|a| a.len()).unwrap_or(0);
+        let data: Vec<Value> = (0..count).map(|_| json!({ "embedding": [0.1, 0.2, 0.3, 0.4] })).collect();
+        Json(json!({ "data": data, "usage": { "prompt_tokens": 5, "completion_tokens": 0 } }))
+    }
+
+    async fn chat_completions_handler(Json(_req): Json<Value>) -> Json<Value> {
+        Json(json!({
+            "choices": [{
+                "message": {
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "function": {
+                            "name": "get_weather",
+                            "arguments": "{\"city\":\"Tokyo\"}"
+                        }
+                    }]
+                }
+            }],
+            "usage": { "prompt_tokens": 10, "completion_tokens": 20 }
+        }))
+    }
+
+    #[tokio::test]
+    async fn embed_action_returns_one_vector_of_model_dimension_per_input() {
+        let app = Router::new().route("/v1/embeddings", post(embeddings_handler));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let handler = DeepseekHandler::new(
+            ThrottledClient::new(reqwest::Client::new()),
+            format!("http://{addr}"),
+            embeddable_model(),
+        );
+
+        let inputs = vec!["one".to_string(), "two".to_string(), "three".to_string()];
+        let result = handler
+            .handle_action("embed", json!({ "inputs": inputs }))
+            .await
+            .unwrap();
+
+        let embeddings = result["embeddings"].as_array().unwrap();
+        assert_eq!(embeddings.len(), 3);
+        for vector in embeddings {
+            assert_eq!(vector.as_array().unwrap().len(), 4);
+        }
+    }
+
+    #[tokio::test]
+    async fn call_with_tools_parses_a_tool_call_into_a_typed_struct() {
+        let app = Router::new().route("/v1/chat/completions", post(chat_completions_handler));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let handler = DeepseekHandler::new(
+            ThrottledClient::new(reqwest::Client::new()),
+            format!("http://{addr}"),
+            tool_calling_model(),
+        );
+
+        let messages = vec![json!({ "role": "user", "content": "what's the weather in Tokyo?" })];
+        let tools = vec![json!({
+            "type": "function",
+            "function": { "name": "get_weather", "parameters": { "type": "object" } }
+        })];
+
+        let tool_calls = handler.call_with_tools(&messages, &tools).await.unwrap();
+
+        assert_eq!(
+            tool_calls,
+            vec![ToolCall {
+                id: "call_1".to_string(),
+                name: "get_weather".to_string(),
+                arguments: json!({ "city": "Tokyo" }),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn generate_code_action_rejects_a_payload_missing_prompt() {
+        let handler = DeepseekHandler::new(
+            ThrottledClient::new(reqwest::Client::new()),
+            "http://127.0.0.1:1",
+            tool_calling_model(),
+        );
+
+        let result = handler.handle_action("generate_code", json!({})).await;
+
+        match result {
+            Err(DeepseekError::InvalidPayload(err)) => {
+                assert_eq!(err.action, "generate_code");
+                assert!(err.reason.contains("prompt"), "error should name the missing field: {}", err.reason);
+            }
+            other => panic!("expected an InvalidPayload error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn usage_accumulates_across_calls_and_feeds_a_cost_estimate() {
+        let app = Router::new().route("/v1/chat/completions", post(chat_completions_handler));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let handler = DeepseekHandler::new(
+            ThrottledClient::new(reqwest::Client::new()),
+            format!("http://{addr}"),
+            tool_calling_model(),
+        );
+
+        handler.generate_code("first prompt", 0.0, None).await.unwrap();
+        handler.generate_code("second prompt", 0.0, None).await.unwrap();
+
+        let report = handler.usage_report();
+        let usage = report.get(&tool_calling_model().name).unwrap();
+        assert_eq!(usage.prompt_tokens, 20);
+        assert_eq!(usage.completion_tokens, 40);
+
+        let selector = ModelSelector::new(vec![ModelSpec {
+            name: tool_calling_model().name,
+            capability: Capability::Standard,
+            cost_per_1k_tokens: 0.01,
+            supported_capabilities: vec![ModelCapability::Chat, ModelCapability::FunctionCalling],
+            context_window: 16384,
+        }]);
+        let cost = handler.cost_estimate(&selector).unwrap();
+        assert!((cost - 0.01 * (60.0 / 1000.0)).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn a_repeated_zero_temperature_call_is_served_from_cache_without_hitting_the_client() {
+        use axum::extract::State;
+        use std::sync::atomic::{AtomicU32, Ordering};
+        use std::sync::Arc;
+
+        let calls = Arc::new(AtomicU32::new(0));
+        let app = Router::new()
+            .route(
+                "/v1/chat/completions",
+                post(move |State(calls): State<Arc<AtomicU32>>, body: Json<Value>| async move {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    chat_completions_handler(body).await
+                }),
+            )
+            .with_state(calls.clone());
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let handler = DeepseekHandler::new(
+            ThrottledClient::new(reqwest::Client::new()),
+            format!("http://{addr}"),
+            tool_calling_model(),
+        )
+        .with_generation_cache(GenerationCache::new(10, Duration::from_secs(60)));
+
+        handler.generate_code("repeat me", 0.0, None).await.unwrap();
+        handler.generate_code("repeat me", 0.0, None).await.unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1, "second identical call should have been served from cache");
+    }
+
+    #[tokio::test]
+    async fn a_nondeterministic_temperature_is_not_cached_unless_explicitly_allowed() {
+        let app = Router::new().route("/v1/chat/completions", post(chat_completions_handler));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let handler = DeepseekHandler::new(
+            ThrottledClient::new(reqwest::Client::new()),
+            format!("http://{addr}"),
+            tool_calling_model(),
+        )
+        .with_generation_cache(GenerationCache::new(10, Duration::from_secs(60)));
+
+        handler.generate_code("repeat me", 0.7, None).await.unwrap();
+        let report_after_first = handler.usage_report();
+        handler.generate_code("repeat me", 0.7, None).await.unwrap();
+        let report_after_second = handler.usage_report();
+
+        // A cache hit wouldn't record usage for the second call, so seeing
+        // usage double confirms the non-deterministic call actually hit
+        // the client both times rather than being cached.
+        let model = tool_calling_model().name;
+        assert_eq!(
+            report_after_second.get(&model).unwrap().prompt_tokens,
+            report_after_first.get(&model).unwrap().prompt_tokens * 2
+        );
+    }
+
+    #[tokio::test]
+    async fn call_with_tools_errors_when_model_lacks_function_calling() {
+        let handler = DeepseekHandler::new(
+            ThrottledClient::new(reqwest::Client::new()),
+            "http://127.0.0.1:1",
+            embeddable_model(),
+        );
+
+        let result = handler.call_with_tools(&[], &[]).await;
+        assert!(matches!(result, Err(DeepseekError::UnsupportedCapability { .. })));
+    }
+}
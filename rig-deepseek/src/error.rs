@@ -0,0 +1,133 @@
+#[derive(Debug, thiserror::Error)]
+pub enum DeepseekError {
+    #[error("rate limit exceeded")]
+    RateLimitExceeded,
+    #[error("http request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("exceeded {0} retries against the Deepseek API")]
+    MaxRetriesExceeded(u32),
+    #[error("unknown model: {0}")]
+    UnknownModel(String),
+    #[error("no model with the requested capability fits the remaining budget of {remaining_budget:.4}")]
+    NoModelFitsBudget { remaining_budget: f64 },
+    #[error("model {model} does not support {capability}")]
+    UnsupportedCapability { model: String, capability: &'static str },
+    #[error("unknown protocol action: {0}")]
+    UnknownAction(String),
+    #[error("failed to parse Deepseek API response: {0}")]
+    Serialization(#[from] serde_json::Error),
+    #[error("{0}")]
+    InvalidPayload(#[from] rig_core::ContentValidationError),
+    #[error("model '{id}' version '{version}' reached end-of-life and no default version is configured to upgrade to")]
+    ModelVersionExpired { id: String, version: String },
+    #[error("failed to read model registry file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("{0}")]
+    BatchFailed(String),
+}
+
+/// Lets a `DeepseekError` surfaced deep inside model selection, generation,
+/// or registry handling propagate through an `Agent`'s `?` chain without a
+/// manual `match` at every call site.
+impl From<DeepseekError> for rig_core::Error {
+    fn from(err: DeepseekError) -> Self {
+        match err {
+            DeepseekError::RateLimitExceeded => rig_core::Error::RateLimited { retry_after: None },
+            DeepseekError::Http(source) => rig_core::Error::Connection(source.to_string()),
+            DeepseekError::MaxRetriesExceeded(retries) => {
+                rig_core::Error::Connection(format!("exceeded {retries} retries against the Deepseek API"))
+            }
+            DeepseekError::UnknownModel(name) => rig_core::Error::ConnectionNotFound(name),
+            DeepseekError::NoModelFitsBudget { remaining_budget } => rig_core::Error::Connection(format!(
+                "no model with the requested capability fits the remaining budget of {remaining_budget:.4}"
+            )),
+            DeepseekError::UnsupportedCapability { model, capability } => {
+                rig_core::Error::MissingCapability { peer: model, capability: capability.to_string() }
+            }
+            DeepseekError::UnknownAction(action) => {
+                rig_core::Error::WireFormat(format!("unknown protocol action: {action}"))
+            }
+            DeepseekError::Serialization(source) => rig_core::Error::Serialization(source),
+            DeepseekError::InvalidPayload(source) => rig_core::Error::WireFormat(source.to_string()),
+            DeepseekError::ModelVersionExpired { id, version } => rig_core::Error::Connection(format!(
+                "model '{id}' version '{version}' reached end-of-life and no default version is configured to upgrade to"
+            )),
+            DeepseekError::Io(source) => rig_core::Error::Io(source),
+            DeepseekError::BatchFailed(message) => rig_core::Error::Connection(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_limit_exceeded_maps_to_rate_limited_with_no_retry_hint() {
+        let err: rig_core::Error = DeepseekError::RateLimitExceeded.into();
+        assert!(matches!(err, rig_core::Error::RateLimited { retry_after: None }));
+    }
+
+    #[test]
+    fn max_retries_exceeded_maps_to_connection() {
+        let err: rig_core::Error = DeepseekError::MaxRetriesExceeded(3).into();
+        assert!(matches!(err, rig_core::Error::Connection(_)));
+    }
+
+    #[test]
+    fn unknown_model_maps_to_connection_not_found() {
+        let err: rig_core::Error = DeepseekError::UnknownModel("deepseek-ghost".to_string()).into();
+        assert!(matches!(err, rig_core::Error::ConnectionNotFound(name) if name == "deepseek-ghost"));
+    }
+
+    #[test]
+    fn no_model_fits_budget_maps_to_connection() {
+        let err: rig_core::Error = DeepseekError::NoModelFitsBudget { remaining_budget: 0.5 }.into();
+        assert!(matches!(err, rig_core::Error::Connection(_)));
+    }
+
+    #[test]
+    fn unsupported_capability_maps_to_missing_capability() {
+        let err: rig_core::Error =
+            DeepseekError::UnsupportedCapability { model: "deepseek-lite".to_string(), capability: "embeddings" }
+                .into();
+        assert!(matches!(
+            err,
+            rig_core::Error::MissingCapability { peer, capability }
+                if peer == "deepseek-lite" && capability == "embeddings"
+        ));
+    }
+
+    #[test]
+    fn unknown_action_maps_to_wire_format() {
+        let err: rig_core::Error = DeepseekError::UnknownAction("teleport".to_string()).into();
+        assert!(matches!(err, rig_core::Error::WireFormat(_)));
+    }
+
+    #[test]
+    fn serialization_maps_to_serialization() {
+        let source = serde_json::from_str::<serde_json::Value>("{not json").unwrap_err();
+        let err: rig_core::Error = DeepseekError::Serialization(source).into();
+        assert!(matches!(err, rig_core::Error::Serialization(_)));
+    }
+
+    #[test]
+    fn model_version_expired_maps_to_connection() {
+        let err: rig_core::Error =
+            DeepseekError::ModelVersionExpired { id: "deepseek-chat".to_string(), version: "v1".to_string() }.into();
+        assert!(matches!(err, rig_core::Error::Connection(_)));
+    }
+
+    #[test]
+    fn batch_failed_maps_to_connection() {
+        let err: rig_core::Error = DeepseekError::BatchFailed("embeddings batch failed".to_string()).into();
+        assert!(matches!(err, rig_core::Error::Connection(_)));
+    }
+
+    #[test]
+    fn io_maps_to_io() {
+        let source = std::io::Error::new(std::io::ErrorKind::NotFound, "missing");
+        let err: rig_core::Error = DeepseekError::Io(source).into();
+        assert!(matches!(err, rig_core::Error::Io(_)));
+    }
+}
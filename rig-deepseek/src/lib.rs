@@ -0,0 +1,21 @@
+mod batching_embedder;
+mod budget_guard;
+mod deepseek_handler;
+mod error;
+mod model;
+mod model_registry;
+mod model_selector;
+mod rate_limiter;
+mod throttled_client;
+mod tokenize;
+
+pub use batching_embedder::BatchingEmbedder;
+pub use budget_guard::BudgetGuard;
+pub use deepseek_handler::{DeepseekHandler, GenerationCache, TokenUsage};
+pub use error::DeepseekError;
+pub use model::{Capability, ModelCapability, ModelMetadata, ModelSpec};
+pub use model_registry::{EolPolicy, ModelRegistry};
+pub use model_selector::{ModelRequirements, ModelSelector};
+pub use rate_limiter::{BurstConfig, RateLimiter};
+pub use throttled_client::ThrottledClient;
+pub use tokenize::{estimate_tokens, fit_to_context};
@@ -0,0 +1,53 @@
+/// Relative capability tier of a model, ordered so that `Advanced >
+/// Standard > Basic` for selection purposes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Capability {
+    Basic,
+    Standard,
+    Advanced,
+}
+
+/// A selectable Deepseek model and its pricing.
+#[derive(Debug, Clone)]
+pub struct ModelSpec {
+    pub name: String,
+    pub capability: Capability,
+    pub cost_per_1k_tokens: f64,
+    /// Features this model supports, for [`crate::ModelSelector::recommend`]'s
+    /// capability-coverage scoring — distinct from `capability`, which is
+    /// just this model's overall tier.
+    pub supported_capabilities: Vec<ModelCapability>,
+    pub context_window: usize,
+}
+
+/// A feature a model supports, as distinct from its [`Capability`] tier —
+/// a model can be `Advanced` and still lack `Embeddings`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum ModelCapability {
+    Chat,
+    Embeddings,
+    FunctionCalling,
+}
+
+/// Metadata needed to fit a prompt to a model's context window and to know
+/// which API calls it supports.
+#[derive(Debug, Clone)]
+pub struct ModelMetadata {
+    pub name: String,
+    pub context_window: usize,
+    pub capabilities: Vec<ModelCapability>,
+    /// Output vector length, for models with [`ModelCapability::Embeddings`].
+    pub embedding_dimension: Option<usize>,
+    /// Whether the model's endpoint most recently responded successfully.
+    /// Set once at construction and kept current by
+    /// [`crate::ModelRegistry::probe_availability`] — a model that's never
+    /// been registered with a registry simply keeps whatever it was built
+    /// with.
+    pub available: bool,
+}
+
+impl ModelMetadata {
+    pub fn supports(&self, capability: ModelCapability) -> bool {
+        self.capabilities.contains(&capability)
+    }
+}
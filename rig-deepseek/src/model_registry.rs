@@ -0,0 +1,449 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use notify::Watcher;
+use serde::Deserialize;
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+use crate::error::DeepseekError;
+use crate::model::{ModelCapability, ModelMetadata};
+use crate::throttled_client::ThrottledClient;
+
+struct RegisteredModel {
+    metadata: ModelMetadata,
+    health_endpoint: String,
+}
+
+/// Deprecation state for one version of a model family, as tracked by
+/// [`ModelRegistry::deprecate_version`].
+struct VersionDeprecation {
+    eol_date: SystemTime,
+}
+
+/// What [`ModelRegistry::resolve_version`] does once a requested version's
+/// `eol_date` has passed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EolPolicy {
+    /// Reject the request with [`DeepseekError::ModelVersionExpired`].
+    Error,
+    /// Silently resolve to the family's default version instead.
+    UpgradeToDefault,
+}
+
+/// Tracks the Deepseek models a client knows about, alongside whether each
+/// one's endpoint is actually reachable right now. [`ModelMetadata::available`]
+/// is just a flag on a struct someone built by hand; this is what keeps it
+/// honest after the fact.
+pub struct ModelRegistry {
+    client: ThrottledClient,
+    models: RwLock<HashMap<String, RegisteredModel>>,
+    /// Deprecated versions, keyed by `(family id, version)`.
+    deprecations: RwLock<HashMap<(String, String), VersionDeprecation>>,
+    /// The version [`Self::resolve_version`] upgrades to once a requested
+    /// version is past its EOL, keyed by family id.
+    default_versions: RwLock<HashMap<String, String>>,
+    eol_policy: EolPolicy,
+}
+
+impl ModelRegistry {
+    pub fn new(client: ThrottledClient) -> Self {
+        Self {
+            client,
+            models: RwLock::new(HashMap::new()),
+            deprecations: RwLock::new(HashMap::new()),
+            default_versions: RwLock::new(HashMap::new()),
+            eol_policy: EolPolicy::Error,
+        }
+    }
+
+    pub fn with_eol_policy(mut self, eol_policy: EolPolicy) -> Self {
+        self.eol_policy = eol_policy;
+        self
+    }
+
+    /// Register `metadata` against `health_endpoint`, the URL
+    /// [`Self::probe_availability`] pings to decide whether it's reachable.
+    /// `metadata.available` is left as given until the first probe runs.
+    pub async fn register(&self, metadata: ModelMetadata, health_endpoint: impl Into<String>) {
+        let health_endpoint = health_endpoint.into();
+        self.models.write().await.insert(metadata.name.clone(), RegisteredModel { metadata, health_endpoint });
+    }
+
+    /// Seed the registry with Deepseek's standard catalog, served from
+    /// `base_url`, then probe it immediately so `available` reflects
+    /// reality rather than the optimistic default.
+    pub async fn initialize_defaults(&self, base_url: &str) {
+        for (name, context_window, capabilities, embedding_dimension) in default_catalog() {
+            let metadata = ModelMetadata {
+                name: name.to_string(),
+                context_window,
+                capabilities,
+                embedding_dimension,
+                available: false,
+            };
+            self.register(metadata, format!("{base_url}/v1/models/{name}")).await;
+        }
+        self.probe_availability().await;
+    }
+
+    pub async fn metadata(&self, name: &str) -> Result<ModelMetadata, DeepseekError> {
+        self.models
+            .read()
+            .await
+            .get(name)
+            .map(|registered| registered.metadata.clone())
+            .ok_or_else(|| DeepseekError::UnknownModel(name.to_string()))
+    }
+
+    pub async fn all(&self) -> Vec<ModelMetadata> {
+        self.models.read().await.values().map(|registered| registered.metadata.clone()).collect()
+    }
+
+    /// Ping every registered model's health endpoint and update its
+    /// `available` flag to match. A request error or non-2xx response
+    /// both count as unavailable; this deliberately bypasses
+    /// [`ThrottledClient`]'s retry/backoff so a flaky model shows up as
+    /// flaky rather than being smoothed over.
+    pub async fn probe_availability(&self) {
+        let endpoints: Vec<(String, String)> = self
+            .models
+            .read()
+            .await
+            .iter()
+            .map(|(name, registered)| (name.clone(), registered.health_endpoint.clone()))
+            .collect();
+
+        for (name, endpoint) in endpoints {
+            let available = self
+                .client
+                .http()
+                .get(&endpoint)
+                .send()
+                .await
+                .map(|response| response.status().is_success())
+                .unwrap_or(false);
+
+            if let Some(registered) = self.models.write().await.get_mut(&name) {
+                if registered.metadata.available != available {
+                    warn!(model = %name, available, "model availability changed");
+                }
+                registered.metadata.available = available;
+            }
+        }
+    }
+
+    /// Mark `version` of model family `id` deprecated, past which
+    /// [`Self::resolve_version`] applies the registry's [`EolPolicy`].
+    /// Registering a family's default version first (see
+    /// [`Self::set_default_version`]) isn't required to deprecate a
+    /// version, only to upgrade away from one.
+    pub async fn deprecate_version(&self, id: &str, version: &str, eol_date: SystemTime) {
+        self.deprecations.write().await.insert((id.to_string(), version.to_string()), VersionDeprecation { eol_date });
+    }
+
+    /// Set the version [`Self::resolve_version`] upgrades `id` to once a
+    /// requested version is past its EOL under [`EolPolicy::UpgradeToDefault`].
+    pub async fn set_default_version(&self, id: &str, version: &str) {
+        self.default_versions.write().await.insert(id.to_string(), version.to_string());
+    }
+
+    /// Resolve `requested`, a version of model family `id`, to the version
+    /// that should actually be used. A version with no deprecation record,
+    /// or one not yet past its `eol_date`, resolves to itself. A version
+    /// past its `eol_date` is handled per the registry's [`EolPolicy`]:
+    /// `Error` rejects the request, `UpgradeToDefault` logs a warning and
+    /// resolves to the family's default version instead.
+    pub async fn resolve_version(&self, id: &str, requested: &str) -> Result<String, DeepseekError> {
+        let key = (id.to_string(), requested.to_string());
+        let Some(deprecation) = self.deprecations.read().await.get(&key).map(|d| d.eol_date) else {
+            return Ok(requested.to_string());
+        };
+
+        if SystemTime::now() < deprecation {
+            warn!(model_id = id, version = requested, "resolved a deprecated model version still before its EOL date");
+            return Ok(requested.to_string());
+        }
+
+        match self.eol_policy {
+            EolPolicy::Error => Err(DeepseekError::ModelVersionExpired { id: id.to_string(), version: requested.to_string() }),
+            EolPolicy::UpgradeToDefault => {
+                let default_version = self
+                    .default_versions
+                    .read()
+                    .await
+                    .get(id)
+                    .cloned()
+                    .ok_or_else(|| DeepseekError::ModelVersionExpired { id: id.to_string(), version: requested.to_string() })?;
+                warn!(model_id = id, requested, upgraded_to = %default_version, "requested model version past EOL, upgrading to default");
+                Ok(default_version)
+            }
+        }
+    }
+
+    /// Spawn a task that calls [`Self::probe_availability`] every
+    /// `interval`, for as long as the returned handle stays alive.
+    /// Aborting or dropping the handle stops the refresh.
+    pub fn spawn_background_refresh(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                self.probe_availability().await;
+            }
+        })
+    }
+
+    /// Replace the registry's model set with the one described by the JSON
+    /// file at `path`, then probe availability for the newly loaded set.
+    /// The file is fully parsed before anything is replaced, so a
+    /// malformed file leaves the current registry untouched.
+    pub async fn load_from_file(&self, path: &Path) -> Result<(), DeepseekError> {
+        let contents = std::fs::read_to_string(path)?;
+        let parsed: RegistryFile = serde_json::from_str(&contents)?;
+
+        let mut next = HashMap::with_capacity(parsed.models.len());
+        for entry in parsed.models {
+            next.insert(
+                entry.name.clone(),
+                RegisteredModel {
+                    metadata: ModelMetadata {
+                        name: entry.name,
+                        context_window: entry.context_window,
+                        capabilities: entry.capabilities,
+                        embedding_dimension: entry.embedding_dimension,
+                        available: false,
+                    },
+                    health_endpoint: entry.health_endpoint,
+                },
+            );
+        }
+
+        *self.models.write().await = next;
+        self.probe_availability().await;
+        Ok(())
+    }
+
+    /// Watch `path` for changes and call [`Self::load_from_file`] on every
+    /// one, logging which models were added or removed and rejecting (with
+    /// a logged warning, not a clobbered registry) any reload that fails to
+    /// parse. The returned watcher must be kept alive for as long as
+    /// watching should continue — dropping it stops the watch.
+    pub fn watch_file(self: Arc<Self>, path: PathBuf) -> notify::Result<notify::RecommendedWatcher> {
+        let handle = tokio::runtime::Handle::current();
+        let watched_path = path.clone();
+
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let Ok(event) = event else { return };
+            if !matches!(event.kind, notify::EventKind::Modify(_) | notify::EventKind::Create(_)) {
+                return;
+            }
+
+            let registry = self.clone();
+            let path = path.clone();
+            handle.spawn(async move { registry.reload_from_file_logging_diff(&path).await });
+        })?;
+        watcher.watch(&watched_path, notify::RecursiveMode::NonRecursive)?;
+        Ok(watcher)
+    }
+
+    async fn reload_from_file_logging_diff(&self, path: &Path) {
+        let before: HashSet<String> = self.models.read().await.keys().cloned().collect();
+
+        if let Err(err) = self.load_from_file(path).await {
+            warn!(path = %path.display(), error = %err, "rejected a malformed model registry reload, keeping the current set");
+            return;
+        }
+
+        let after: HashSet<String> = self.models.read().await.keys().cloned().collect();
+        for added in after.difference(&before) {
+            info!(model = %added, "model registry reload added a model");
+        }
+        for removed in before.difference(&after) {
+            info!(model = %removed, "model registry reload removed a model");
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RegistryFileEntry {
+    name: String,
+    context_window: usize,
+    #[serde(default)]
+    capabilities: Vec<ModelCapability>,
+    #[serde(default)]
+    embedding_dimension: Option<usize>,
+    health_endpoint: String,
+}
+
+#[derive(Deserialize)]
+struct RegistryFile {
+    models: Vec<RegistryFileEntry>,
+}
+
+fn default_catalog() -> Vec<(&'static str, usize, Vec<ModelCapability>, Option<usize>)> {
+    vec![
+        ("deepseek-chat", 32768, vec![ModelCapability::Chat, ModelCapability::FunctionCalling], None),
+        ("deepseek-coder-33b-instruct", 16384, vec![ModelCapability::Chat, ModelCapability::FunctionCalling], None),
+        ("deepseek-embed", 8192, vec![ModelCapability::Embeddings], Some(4)),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+    use axum::routing::get;
+    use axum::Router;
+    use tokio::net::TcpListener;
+
+    fn model(name: &str) -> ModelMetadata {
+        ModelMetadata {
+            name: name.to_string(),
+            context_window: 8192,
+            capabilities: vec![ModelCapability::Chat],
+            embedding_dimension: None,
+            available: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn probe_availability_reflects_each_endpoints_actual_status() {
+        let app = Router::new()
+            .route("/healthy", get(|| async { StatusCode::OK }))
+            .route("/unhealthy", get(|| async { StatusCode::SERVICE_UNAVAILABLE }));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let registry = ModelRegistry::new(ThrottledClient::new(reqwest::Client::new()));
+        registry.register(model("deepseek-chat"), format!("http://{addr}/healthy")).await;
+        registry.register(model("deepseek-embed"), format!("http://{addr}/unhealthy")).await;
+
+        registry.probe_availability().await;
+
+        assert!(registry.metadata("deepseek-chat").await.unwrap().available);
+        assert!(!registry.metadata("deepseek-embed").await.unwrap().available);
+    }
+
+    #[tokio::test]
+    async fn probing_an_unreachable_endpoint_marks_it_unavailable() {
+        let registry = ModelRegistry::new(ThrottledClient::new(reqwest::Client::new()));
+        registry.register(model("deepseek-chat"), "http://127.0.0.1:1/healthy".to_string()).await;
+
+        registry.probe_availability().await;
+
+        assert!(!registry.metadata("deepseek-chat").await.unwrap().available);
+    }
+
+    #[tokio::test]
+    async fn unknown_model_lookup_is_an_error() {
+        let registry = ModelRegistry::new(ThrottledClient::new(reqwest::Client::new()));
+        assert!(matches!(registry.metadata("nope").await, Err(DeepseekError::UnknownModel(_))));
+    }
+
+    #[tokio::test]
+    async fn a_deprecated_version_still_resolved_before_its_eol_date() {
+        let registry = ModelRegistry::new(ThrottledClient::new(reqwest::Client::new()));
+        registry.deprecate_version("deepseek-chat", "2024-01-01", SystemTime::now() + Duration::from_secs(3600)).await;
+
+        let resolved = registry.resolve_version("deepseek-chat", "2024-01-01").await.unwrap();
+        assert_eq!(resolved, "2024-01-01");
+    }
+
+    #[tokio::test]
+    async fn a_version_past_eol_errors_under_the_error_policy() {
+        let registry = ModelRegistry::new(ThrottledClient::new(reqwest::Client::new())).with_eol_policy(EolPolicy::Error);
+        registry.deprecate_version("deepseek-chat", "2024-01-01", SystemTime::now() - Duration::from_secs(1)).await;
+
+        let resolved = registry.resolve_version("deepseek-chat", "2024-01-01").await;
+        assert!(matches!(resolved, Err(DeepseekError::ModelVersionExpired { .. })));
+    }
+
+    #[tokio::test]
+    async fn a_version_past_eol_upgrades_to_the_default_under_that_policy() {
+        let registry =
+            ModelRegistry::new(ThrottledClient::new(reqwest::Client::new())).with_eol_policy(EolPolicy::UpgradeToDefault);
+        registry.deprecate_version("deepseek-chat", "2024-01-01", SystemTime::now() - Duration::from_secs(1)).await;
+        registry.set_default_version("deepseek-chat", "2024-11-01").await;
+
+        let resolved = registry.resolve_version("deepseek-chat", "2024-01-01").await.unwrap();
+        assert_eq!(resolved, "2024-11-01");
+    }
+
+    #[tokio::test]
+    async fn a_version_with_no_deprecation_record_resolves_to_itself() {
+        let registry = ModelRegistry::new(ThrottledClient::new(reqwest::Client::new()));
+        let resolved = registry.resolve_version("deepseek-chat", "2024-11-01").await.unwrap();
+        assert_eq!(resolved, "2024-11-01");
+    }
+
+    fn write_registry_file(path: &std::path::Path, model_names: &[&str]) {
+        let models: Vec<_> = model_names
+            .iter()
+            .map(|name| {
+                serde_json::json!({
+                    "name": name,
+                    "context_window": 8192,
+                    "capabilities": ["Chat"],
+                    "health_endpoint": "http://127.0.0.1:1/healthz",
+                })
+            })
+            .collect();
+        std::fs::write(path, serde_json::json!({ "models": models }).to_string()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn load_from_file_replaces_the_registrys_model_set() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        write_registry_file(file.path(), &["deepseek-chat", "deepseek-embed"]);
+
+        let registry = ModelRegistry::new(ThrottledClient::new(reqwest::Client::new()));
+        registry.load_from_file(file.path()).await.unwrap();
+
+        let names: HashSet<String> = registry.all().await.into_iter().map(|m| m.name).collect();
+        assert_eq!(names, HashSet::from(["deepseek-chat".to_string(), "deepseek-embed".to_string()]));
+    }
+
+    #[tokio::test]
+    async fn load_from_file_rejects_malformed_json_without_clobbering_the_current_set() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        write_registry_file(file.path(), &["deepseek-chat"]);
+
+        let registry = ModelRegistry::new(ThrottledClient::new(reqwest::Client::new()));
+        registry.load_from_file(file.path()).await.unwrap();
+
+        std::fs::write(file.path(), "not json").unwrap();
+        assert!(registry.load_from_file(file.path()).await.is_err());
+
+        let names: Vec<String> = registry.all().await.into_iter().map(|m| m.name).collect();
+        assert_eq!(names, vec!["deepseek-chat".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn watch_file_picks_up_a_model_added_after_a_rewrite() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("registry.json");
+        write_registry_file(&path, &["deepseek-chat"]);
+
+        let registry = Arc::new(ModelRegistry::new(ThrottledClient::new(reqwest::Client::new())));
+        registry.load_from_file(&path).await.unwrap();
+        let _watcher = registry.clone().watch_file(path.clone()).unwrap();
+
+        write_registry_file(&path, &["deepseek-chat", "deepseek-embed"]);
+
+        let mut names: HashSet<String> = HashSet::new();
+        for _ in 0..50 {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            names = registry.all().await.into_iter().map(|m| m.name).collect();
+            if names.contains("deepseek-embed") {
+                break;
+            }
+        }
+        assert!(names.contains("deepseek-embed"), "expected the watched reload to pick up the new model");
+    }
+}
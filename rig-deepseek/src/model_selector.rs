@@ -0,0 +1,189 @@
+use crate::error::DeepseekError;
+use crate::model::{Capability, ModelCapability, ModelSpec};
+
+/// Chooses a Deepseek model from a fixed catalog based on required
+/// capability, context size, and (optionally) remaining spend.
+pub struct ModelSelector {
+    models: Vec<ModelSpec>,
+}
+
+impl ModelSelector {
+    pub fn new(models: Vec<ModelSpec>) -> Self {
+        Self { models }
+    }
+
+    /// Projected cost of running `context_size` tokens through `model_name`.
+    pub fn calculate_cost(&self, model_name: &str, context_size: usize) -> Result<f64, DeepseekError> {
+        let model = self.model(model_name)?;
+        Ok(model.cost_per_1k_tokens * (context_size as f64 / 1000.0))
+    }
+
+    /// The most capable model meeting `capability` whose projected cost for
+    /// `context_size` tokens fits within `remaining_budget`. Ties in
+    /// capability are broken by lowest cost.
+    pub fn select_within_budget(
+        &self,
+        capability: Capability,
+        context_size: usize,
+        remaining_budget: f64,
+    ) -> Result<String, DeepseekError> {
+        self.models
+            .iter()
+            .filter(|model| model.capability >= capability)
+            .filter_map(|model| {
+                let cost = model.cost_per_1k_tokens * (context_size as f64 / 1000.0);
+                (cost <= remaining_budget).then_some((model, cost))
+            })
+            .max_by(|(a, a_cost), (b, b_cost)| {
+                a.capability
+                    .cmp(&b.capability)
+                    .then(b_cost.partial_cmp(a_cost).unwrap_or(std::cmp::Ordering::Equal))
+            })
+            .map(|(model, _)| model.name.clone())
+            .ok_or(DeepseekError::NoModelFitsBudget { remaining_budget })
+    }
+
+    fn model(&self, name: &str) -> Result<&ModelSpec, DeepseekError> {
+        self.models
+            .iter()
+            .find(|model| model.name == name)
+            .ok_or_else(|| DeepseekError::UnknownModel(name.to_string()))
+    }
+
+    /// Rank every model meeting `requirements.min_context` by a weighted
+    /// score of capability coverage, context fit, and cost — highest score
+    /// first. A model falling short of `min_context` can't serve the
+    /// request at all, so it's excluded outright rather than merely
+    /// scored down.
+    pub fn recommend(&self, requirements: &ModelRequirements) -> Vec<(String, f64)> {
+        let candidates: Vec<&ModelSpec> =
+            self.models.iter().filter(|model| model.context_window >= requirements.min_context).collect();
+        let max_cost = candidates.iter().map(|model| model.cost_per_1k_tokens).fold(0.0_f64, f64::max);
+
+        let mut scored: Vec<(String, f64)> = candidates
+            .into_iter()
+            .map(|model| {
+                let coverage = capability_coverage(&requirements.capabilities, &model.supported_capabilities);
+                let context_fit = context_fit(requirements.min_context, model.context_window);
+                let normalized_cost = if max_cost > 0.0 { model.cost_per_1k_tokens / max_cost } else { 0.0 };
+
+                let score = coverage + context_fit - requirements.cost_weight * normalized_cost;
+                (model.name.clone(), score)
+            })
+            .collect();
+
+        scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scored
+    }
+}
+
+/// What a candidate model must satisfy, and how heavily to weigh cost when
+/// several candidates qualify, for [`ModelSelector::recommend`].
+pub struct ModelRequirements {
+    pub capabilities: Vec<ModelCapability>,
+    pub min_context: usize,
+    /// How much a candidate's relative cost should pull its score down —
+    /// 0.0 ignores cost entirely, 1.0 weighs it as heavily as full
+    /// capability coverage.
+    pub cost_weight: f64,
+}
+
+/// Fraction of `required` present in `supported`, or `1.0` if nothing is
+/// required.
+fn capability_coverage(required: &[ModelCapability], supported: &[ModelCapability]) -> f64 {
+    if required.is_empty() {
+        return 1.0;
+    }
+    let covered = required.iter().filter(|capability| supported.contains(capability)).count();
+    covered as f64 / required.len() as f64
+}
+
+/// How well `context_window` matches `min_context`: `1.0` at an exact
+/// match, shrinking as the model overshoots what's actually needed, so an
+/// unnecessarily huge context window doesn't win purely by existing.
+fn context_fit(min_context: usize, context_window: usize) -> f64 {
+    if context_window == 0 {
+        return 0.0;
+    }
+    (min_context as f64 / context_window as f64).min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn catalog() -> ModelSelector {
+        ModelSelector::new(vec![
+            ModelSpec {
+                name: "deepseek-lite".to_string(),
+                capability: Capability::Basic,
+                cost_per_1k_tokens: 0.001,
+                supported_capabilities: vec![ModelCapability::Chat],
+                context_window: 4096,
+            },
+            ModelSpec {
+                name: "deepseek-chat".to_string(),
+                capability: Capability::Standard,
+                cost_per_1k_tokens: 0.01,
+                supported_capabilities: vec![ModelCapability::Chat, ModelCapability::FunctionCalling],
+                context_window: 32768,
+            },
+            ModelSpec {
+                name: "deepseek-reasoner".to_string(),
+                capability: Capability::Advanced,
+                cost_per_1k_tokens: 0.5,
+                supported_capabilities: vec![ModelCapability::Chat, ModelCapability::FunctionCalling],
+                context_window: 65536,
+            },
+        ])
+    }
+
+    #[test]
+    fn picks_the_most_capable_model_that_fits_the_budget() {
+        let selector = catalog();
+        let model = selector.select_within_budget(Capability::Basic, 10_000, 1.0).unwrap();
+        assert_eq!(model, "deepseek-chat");
+    }
+
+    #[test]
+    fn excludes_a_large_model_that_would_exceed_the_remaining_budget() {
+        let selector = catalog();
+        // deepseek-reasoner would cost 0.5 * 100 = 50.0 for 100k tokens,
+        // far beyond the 1.0 remaining budget, so it must be excluded even
+        // though it's the most capable match.
+        let model = selector.select_within_budget(Capability::Advanced, 100_000, 1.0);
+        assert!(matches!(model, Err(DeepseekError::NoModelFitsBudget { .. })));
+    }
+
+    #[test]
+    fn calculate_cost_scales_with_context_size() {
+        let selector = catalog();
+        let cost = selector.calculate_cost("deepseek-chat", 2_000).unwrap();
+        assert!((cost - 0.02).abs() < 1e-9);
+    }
+
+    #[test]
+    fn a_cheaper_model_with_all_required_capabilities_outranks_a_pricier_one() {
+        let selector = catalog();
+        let requirements = ModelRequirements {
+            capabilities: vec![ModelCapability::Chat, ModelCapability::FunctionCalling],
+            min_context: 16_000,
+            cost_weight: 1.0,
+        };
+
+        let recommendations = selector.recommend(&requirements);
+        let names: Vec<&str> = recommendations.iter().map(|(name, _)| name.as_str()).collect();
+
+        assert_eq!(names[0], "deepseek-chat");
+        assert!(names.contains(&"deepseek-reasoner"));
+    }
+
+    #[test]
+    fn a_model_below_min_context_is_excluded_entirely() {
+        let selector = catalog();
+        let requirements = ModelRequirements { capabilities: vec![], min_context: 16_000, cost_weight: 0.0 };
+
+        let recommendations = selector.recommend(&requirements);
+        assert!(!recommendations.iter().any(|(name, _)| name == "deepseek-lite"));
+    }
+}
@@ -0,0 +1,155 @@
+use std::time::{Duration, Instant};
+
+use tokio::sync::Mutex;
+
+/// Optional token-bucket layer smoothing short bursts: up to
+/// `capacity` requests may fire back-to-back, after which the caller is
+/// capped at `refill_per_second`. Checked before the per-minute/per-day
+/// windows, so it never raises the sustained rate — it only shapes bursts
+/// within it.
+#[derive(Debug, Clone, Copy)]
+pub struct BurstConfig {
+    pub capacity: u32,
+    pub refill_per_second: f64,
+}
+
+struct TokenBucket {
+    tokens: f64,
+    capacity: f64,
+    refill_per_second: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(config: BurstConfig) -> Self {
+        Self {
+            tokens: config.capacity as f64,
+            capacity: config.capacity as f64,
+            refill_per_second: config.refill_per_second,
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self) {
+        let elapsed = self.last_refill.elapsed().as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_second).min(self.capacity);
+        self.last_refill = Instant::now();
+    }
+}
+
+struct WindowState {
+    window_start: Instant,
+    count: u32,
+}
+
+impl WindowState {
+    fn new() -> Self {
+        Self {
+            window_start: Instant::now(),
+            count: 0,
+        }
+    }
+
+    fn check_and_increment(&mut self, limit: u32, window: Duration) -> bool {
+        if self.window_start.elapsed() >= window {
+            self.window_start = Instant::now();
+            self.count = 0;
+        }
+        if self.count < limit {
+            self.count += 1;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Per-minute/per-day request limiter for the Deepseek API client, with an
+/// optional token-bucket burst layer. Burst smoothing is off by default —
+/// pass `burst` to enable it.
+pub struct RateLimiter {
+    per_minute_limit: u32,
+    per_day_limit: u32,
+    minute_state: Mutex<WindowState>,
+    day_state: Mutex<WindowState>,
+    bucket: Option<Mutex<TokenBucket>>,
+}
+
+impl RateLimiter {
+    pub fn new(per_minute_limit: u32, per_day_limit: u32) -> Self {
+        Self {
+            per_minute_limit,
+            per_day_limit,
+            minute_state: Mutex::new(WindowState::new()),
+            day_state: Mutex::new(WindowState::new()),
+            bucket: None,
+        }
+    }
+
+    pub fn with_burst(mut self, burst: BurstConfig) -> Self {
+        self.bucket = Some(Mutex::new(TokenBucket::new(burst)));
+        self
+    }
+
+    /// Whether a request made right now should be allowed. Consults the
+    /// burst bucket first (if configured), then the minute and day
+    /// windows; all gates must pass before any state is consumed.
+    pub async fn check_request(&self) -> bool {
+        if let Some(bucket) = &self.bucket {
+            let mut bucket = bucket.lock().await;
+            bucket.refill();
+            if bucket.tokens < 1.0 {
+                return false;
+            }
+        }
+
+        let mut minute = self.minute_state.lock().await;
+        if !minute.check_and_increment(self.per_minute_limit, Duration::from_secs(60)) {
+            return false;
+        }
+
+        let mut day = self.day_state.lock().await;
+        if !day.check_and_increment(self.per_day_limit, Duration::from_secs(24 * 60 * 60)) {
+            minute.count -= 1;
+            return false;
+        }
+
+        if let Some(bucket) = &self.bucket {
+            bucket.lock().await.tokens -= 1.0;
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn burst_bucket_rejects_once_drained_then_refills_over_time() {
+        let limiter = RateLimiter::new(1_000, 1_000).with_burst(BurstConfig {
+            capacity: 3,
+            refill_per_second: 20.0,
+        });
+
+        for _ in 0..3 {
+            assert!(limiter.check_request().await);
+        }
+        assert!(!limiter.check_request().await, "bucket should be drained");
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(limiter.check_request().await, "bucket should have refilled by now");
+    }
+
+    #[tokio::test]
+    async fn per_minute_limit_applies_even_with_burst_capacity_available() {
+        let limiter = RateLimiter::new(1, 1_000).with_burst(BurstConfig {
+            capacity: 10,
+            refill_per_second: 1.0,
+        });
+
+        assert!(limiter.check_request().await);
+        assert!(!limiter.check_request().await);
+    }
+}
@@ -0,0 +1,169 @@
+use std::time::Duration;
+
+use rand::Rng;
+use reqwest::header::HeaderMap;
+use reqwest::{Response, StatusCode};
+
+use crate::error::DeepseekError;
+
+const RETRYABLE_STATUSES: [StatusCode; 2] = [StatusCode::TOO_MANY_REQUESTS, StatusCode::SERVICE_UNAVAILABLE];
+
+/// Wraps a [`reqwest::Client`] with retry/backoff for Deepseek's rate-limit
+/// responses. Requests are rebuilt from `build_request` on each attempt
+/// since a [`reqwest::RequestBuilder`] can't be cloned after it's been sent.
+pub struct ThrottledClient {
+    http: reqwest::Client,
+    max_retries: u32,
+    base_backoff: Duration,
+}
+
+impl ThrottledClient {
+    pub fn new(http: reqwest::Client) -> Self {
+        Self {
+            http,
+            max_retries: 5,
+            base_backoff: Duration::from_millis(500),
+        }
+    }
+
+    /// Build a [`ThrottledClient`] around a freshly built [`reqwest::Client`]
+    /// tuned by `pool_config`, so callers don't have to reach into
+    /// [`reqwest::ClientBuilder`] themselves just to size the connection
+    /// pool.
+    pub fn with_pool_config(pool_config: rig_core::HttpPoolConfig) -> Self {
+        let http = reqwest::Client::builder()
+            .pool_idle_timeout(pool_config.idle_timeout)
+            .pool_max_idle_per_host(pool_config.max_idle_per_host)
+            .build()
+            .expect("pool_config produces a valid reqwest::Client");
+        Self::new(http)
+    }
+
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    pub fn http(&self) -> &reqwest::Client {
+        &self.http
+    }
+
+    /// Send a request built fresh from `build_request` on every attempt,
+    /// retrying on 429/503 up to `max_retries` times. The wait before each
+    /// retry is the larger of the server's `Retry-After` hint (if present)
+    /// and a jittered exponential local backoff, so a server telling us to
+    /// wait longer than our own schedule is always honored.
+    pub async fn send_request(
+        &self,
+        build_request: impl Fn(&reqwest::Client) -> reqwest::RequestBuilder,
+    ) -> Result<Response, DeepseekError> {
+        for attempt in 0..=self.max_retries {
+            let response = build_request(&self.http).send().await?;
+
+            if !RETRYABLE_STATUSES.contains(&response.status()) {
+                return Ok(response);
+            }
+            if attempt == self.max_retries {
+                return Err(DeepseekError::MaxRetriesExceeded(self.max_retries));
+            }
+
+            let retry_after = parse_retry_after(response.headers());
+            let local_backoff = jittered_backoff(self.base_backoff, attempt);
+            tokio::time::sleep(retry_after.unwrap_or(Duration::ZERO).max(local_backoff)).await;
+        }
+
+        unreachable!("loop always returns via Ok or Err before exhausting its range")
+    }
+}
+
+/// Parse a `Retry-After` header value, which is either a number of seconds
+/// or an HTTP-date (RFC 7231 §7.1.3). Dates in the past yield `Duration::ZERO`
+/// rather than a negative duration.
+fn parse_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(reqwest::header::RETRY_AFTER)?.to_str().ok()?;
+
+    if let Ok(seconds) = value.trim().parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let target = httpdate::parse_http_date(value.trim()).ok()?;
+    Some(target.duration_since(std::time::SystemTime::now()).unwrap_or(Duration::ZERO))
+}
+
+fn jittered_backoff(base: Duration, attempt: u32) -> Duration {
+    let exponential = base.as_secs_f64() * 2f64.powi(attempt as i32);
+    let jitter = rand::thread_rng().gen_range(0.5..1.5);
+    Duration::from_secs_f64(exponential * jitter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::State;
+    use axum::routing::get;
+    use axum::Router;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+    use std::time::Instant;
+    use tokio::net::TcpListener;
+
+    async fn handler(State(calls): State<Arc<AtomicU32>>) -> axum::response::Response {
+        use axum::http::header;
+        use axum::response::IntoResponse;
+
+        if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+            (axum::http::StatusCode::TOO_MANY_REQUESTS, [(header::RETRY_AFTER, "1")], "").into_response()
+        } else {
+            (axum::http::StatusCode::OK, "ok").into_response()
+        }
+    }
+
+    #[tokio::test]
+    async fn waits_at_least_the_retry_after_hint_before_retrying() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let app = Router::new().route("/", get(handler)).with_state(calls);
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let client = ThrottledClient::new(reqwest::Client::new()).with_max_retries(3);
+        let url = format!("http://{addr}/");
+
+        let start = Instant::now();
+        let response = client.send_request(|http| http.get(&url)).await.unwrap();
+        let elapsed = start.elapsed();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(elapsed >= Duration::from_millis(950), "expected to wait ~1s, waited {elapsed:?}");
+    }
+
+    #[tokio::test]
+    async fn repeated_requests_through_a_pool_configured_client_reuse_the_same_connection() {
+        use std::net::SocketAddr;
+
+        async fn connecting_addr_handler(
+            axum::extract::ConnectInfo(addr): axum::extract::ConnectInfo<SocketAddr>,
+        ) -> String {
+            addr.to_string()
+        }
+
+        let app = Router::new().route("/", get(connecting_addr_handler));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await.unwrap();
+        });
+
+        let client = ThrottledClient::with_pool_config(rig_core::HttpPoolConfig::default());
+        let url = format!("http://{addr}/");
+
+        // Sequential (not concurrent) requests so a pooled connection is
+        // free to be reused rather than forcing a second one to open.
+        let first_conn = client.send_request(|http| http.get(&url)).await.unwrap().text().await.unwrap();
+        let second_conn = client.send_request(|http| http.get(&url)).await.unwrap().text().await.unwrap();
+
+        assert_eq!(first_conn, second_conn, "expected the second request to reuse the first's pooled connection");
+    }
+}
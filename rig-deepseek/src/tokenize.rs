@@ -0,0 +1,68 @@
+use crate::model::ModelMetadata;
+
+/// Characters per token under the heuristic below. No BPE tokenizer is
+/// bundled, so this trades precision for zero extra dependencies; it's
+/// deliberately conservative (undercounts rather than overcounts tokens).
+const CHARS_PER_TOKEN: usize = 4;
+
+const TRUNCATION_MARKER: &str = "\n...[truncated]...\n";
+
+/// Rough token count for `text`, used to decide whether a prompt needs
+/// truncation before it's sent to a model.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+/// Truncate `prompt` from the middle, keeping its head and tail intact, so
+/// that `estimate_tokens(result) + reserve_output` stays within
+/// `model.context_window`. Returns `prompt` unchanged if it already fits.
+pub fn fit_to_context(prompt: &str, model: &ModelMetadata, reserve_output: usize) -> String {
+    let available_tokens = model.context_window.saturating_sub(reserve_output);
+    if estimate_tokens(prompt) <= available_tokens {
+        return prompt.to_string();
+    }
+
+    let marker_tokens = estimate_tokens(TRUNCATION_MARKER);
+    let budget_chars = available_tokens.saturating_sub(marker_tokens) * CHARS_PER_TOKEN;
+
+    let chars: Vec<char> = prompt.chars().collect();
+    let head_len = budget_chars / 2;
+    let tail_len = budget_chars - head_len;
+
+    let head: String = chars.iter().take(head_len).collect();
+    let tail: String = chars.iter().rev().take(tail_len).rev().collect();
+
+    format!("{head}{TRUNCATION_MARKER}{tail}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn model(context_window: usize) -> ModelMetadata {
+        ModelMetadata {
+            name: "deepseek-chat".to_string(),
+            context_window,
+            capabilities: Vec::new(),
+            embedding_dimension: None,
+            available: true,
+        }
+    }
+
+    #[test]
+    fn short_prompt_is_returned_unchanged() {
+        let prompt = "hello world";
+        assert_eq!(fit_to_context(prompt, &model(1000), 0), prompt);
+    }
+
+    #[test]
+    fn over_long_prompt_is_truncated_but_preserves_both_ends() {
+        let prompt = format!("HEAD{}TAIL", "x".repeat(10_000));
+        let result = fit_to_context(&prompt, &model(100), 0);
+
+        assert!(estimate_tokens(&result) <= 100);
+        assert!(result.starts_with("HEAD"));
+        assert!(result.ends_with("TAIL"));
+        assert!(result.len() < prompt.len());
+    }
+}
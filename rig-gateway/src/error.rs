@@ -0,0 +1,38 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum GatewayError {
+    #[error("no route matches path '{0}'")]
+    NoRoute(String),
+
+    #[error("upstream request failed: {0}")]
+    Upstream(#[from] reqwest::Error),
+
+    #[error("upstream response body of {actual} bytes exceeds limit of {limit} bytes")]
+    BodyTooLarge { actual: usize, limit: usize },
+
+    #[error("upstream request to '{0}' timed out")]
+    UpstreamTimeout(String),
+
+    #[error("request body of {actual} bytes exceeds limit of {limit} bytes")]
+    RequestBodyTooLarge { actual: usize, limit: usize },
+
+    #[error("unsupported content type '{0}', this route only accepts application/json")]
+    UnsupportedContentType(String),
+}
+
+impl IntoResponse for GatewayError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            GatewayError::NoRoute(_) => StatusCode::NOT_FOUND,
+            GatewayError::Upstream(_) => StatusCode::BAD_GATEWAY,
+            GatewayError::BodyTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            GatewayError::UpstreamTimeout(_) => StatusCode::GATEWAY_TIMEOUT,
+            GatewayError::RequestBodyTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            GatewayError::UnsupportedContentType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        };
+        (status, self.to_string()).into_response()
+    }
+}
@@ -0,0 +1,433 @@
+use axum::body::Body;
+use axum::extract::{Request, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use futures::StreamExt;
+use serde::Serialize;
+use tracing::{warn, Instrument};
+use uuid::Uuid;
+
+/// Request headers that are specific to the client-gateway hop and must not
+/// be blindly forwarded to the upstream.
+const HOP_BY_HOP_HEADERS: &[&str] = &[
+    "host",
+    "connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+];
+
+fn forwarded_headers(headers: &HeaderMap) -> HeaderMap {
+    headers
+        .iter()
+        .filter(|(name, _)| !HOP_BY_HOP_HEADERS.contains(&name.as_str()))
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect()
+}
+
+/// axum (and its `Request`) is built on `http` 1.x; reqwest 0.11 is built
+/// on `http` 0.2. They're distinct types with no conversion between them,
+/// so a request's method and headers have to be rebuilt byte-for-byte
+/// rather than simply passed through.
+fn to_reqwest_method(method: &axum::http::Method) -> reqwest::Method {
+    reqwest::Method::from_bytes(method.as_str().as_bytes()).unwrap_or(reqwest::Method::GET)
+}
+
+fn to_reqwest_headers(headers: &HeaderMap) -> reqwest::header::HeaderMap {
+    headers
+        .iter()
+        .filter_map(|(name, value)| {
+            let name = reqwest::header::HeaderName::from_bytes(name.as_str().as_bytes()).ok()?;
+            let value = reqwest::header::HeaderValue::from_bytes(value.as_bytes()).ok()?;
+            Some((name, value))
+        })
+        .collect()
+}
+
+use crate::error::GatewayError;
+use crate::health::HealthRegistry;
+use crate::route::{Route, RouteMatch};
+
+/// Default ceiling on upstream response bodies: 16 MiB.
+pub const DEFAULT_MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+/// Header carrying the correlation id used to trace a request across the
+/// gateway, broker, and stores. Forwarded as-is when the inbound request
+/// already carries one, so a caller can thread its own id through;
+/// otherwise the gateway mints a fresh one.
+const TRACE_ID_HEADER: &str = "x-trace-id";
+
+/// Use the inbound request's `X-Trace-Id` if present, otherwise mint a
+/// fresh one.
+fn trace_id(headers: &HeaderMap) -> String {
+    headers
+        .get(TRACE_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string())
+}
+
+/// Header clients can set to pin which side of a canary split they land on
+/// across repeated requests (e.g. a session or device id). Falls back to a
+/// fresh random key per request when absent, which still respects the
+/// configured weight in aggregate but gives no per-client stickiness.
+const CANARY_KEY_HEADER: &str = "x-canary-key";
+
+/// Hash `key` onto `0.0..1.0`, for comparing against a [`crate::route::CanaryConfig`] weight.
+fn hashed_fraction(key: &str) -> f64 {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    (hasher.finish() as f64) / (u64::MAX as f64)
+}
+
+/// Pick which upstream a request to `route` should be sent to: the canary
+/// upstream for `canary.weight` of requests (keyed deterministically by
+/// [`CANARY_KEY_HEADER`] when present), the stable one otherwise.
+fn select_upstream<'a>(route: &'a Route, headers: &HeaderMap) -> &'a str {
+    let Some(canary) = &route.canary else {
+        return &route.upstream;
+    };
+    let key = headers
+        .get(CANARY_KEY_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    if hashed_fraction(&key) < canary.weight {
+        &canary.upstream
+    } else {
+        &route.upstream
+    }
+}
+
+#[derive(Clone)]
+pub struct Gateway {
+    routes: Vec<Route>,
+    client: reqwest::Client,
+    max_body_bytes: usize,
+    health: HealthRegistry,
+}
+
+impl Gateway {
+    pub fn new(routes: Vec<Route>) -> Self {
+        Self::with_pool_config(routes, rig_core::HttpPoolConfig::default())
+    }
+
+    /// Like [`Self::new`], but building the shared upstream client with
+    /// `pool_config` instead of [`rig_core::HttpPoolConfig::default`].
+    pub fn with_pool_config(routes: Vec<Route>, pool_config: rig_core::HttpPoolConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .pool_idle_timeout(pool_config.idle_timeout)
+            .pool_max_idle_per_host(pool_config.max_idle_per_host)
+            .build()
+            .expect("pool_config produces a valid reqwest::Client");
+        Self { routes, client, max_body_bytes: DEFAULT_MAX_BODY_BYTES, health: HealthRegistry::new() }
+    }
+
+    pub fn with_max_body_bytes(mut self, max_body_bytes: usize) -> Self {
+        self.max_body_bytes = max_body_bytes;
+        self
+    }
+
+    /// Start background health polling for every registered upstream and
+    /// keep its result on this gateway's [`HealthRegistry`].
+    pub fn start_health_polling(&self, interval: std::time::Duration) {
+        let upstreams: Vec<String> = self.routes.iter().map(|r| r.upstream.clone()).collect();
+        self.health.spawn_polling(upstreams, interval);
+    }
+
+    /// Find the first *healthy* route whose pattern matches `path`, in
+    /// declaration order — more specific routes should be registered
+    /// before broader wildcard ones.
+    async fn find_route(&self, path: &str) -> Option<RouteMatch<'_>> {
+        for route in &self.routes {
+            if let Some(m) = route.matches(path) {
+                if self.health.is_healthy(&route.upstream).await {
+                    return Some(m);
+                }
+            }
+        }
+        None
+    }
+
+    /// Summarize whether this gateway can actually serve traffic: at least
+    /// one registered upstream (deduped across routes that share one) must
+    /// currently be reporting healthy.
+    pub async fn readiness(&self) -> ReadinessSummary {
+        let mut seen = std::collections::HashSet::new();
+        let mut healthy_upstreams = 0;
+        for route in &self.routes {
+            if seen.insert(route.upstream.clone()) && self.health.is_healthy(&route.upstream).await {
+                healthy_upstreams += 1;
+            }
+        }
+        let total_upstreams = seen.len();
+        ReadinessSummary { ready: healthy_upstreams > 0, healthy_upstreams, total_upstreams }
+    }
+}
+
+/// Body returned by [`handle_readyz`], summarizing downstream health so an
+/// orchestrator probing readiness can see why, not just whether.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadinessSummary {
+    pub ready: bool,
+    pub healthy_upstreams: usize,
+    pub total_upstreams: usize,
+}
+
+/// Axum handler for `GET /healthz`: always `200` once the process is up
+/// and able to respond at all — no dependency checks, unlike
+/// [`handle_readyz`].
+pub async fn handle_healthz() -> StatusCode {
+    StatusCode::OK
+}
+
+/// Axum handler for `GET /readyz`: `200` with a [`ReadinessSummary`] body
+/// if at least one upstream is healthy, `503` with the same body
+/// otherwise — so an orchestrator can tell a cold-starting gateway apart
+/// from a genuinely unhealthy one.
+pub async fn handle_readyz(State(gateway): State<Gateway>) -> Response {
+    let summary = gateway.readiness().await;
+    let status = if summary.ready { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (status, Json(summary)).into_response()
+}
+
+/// Axum handler that proxies every request through to the matching route's
+/// upstream, streaming the response body instead of buffering it whole.
+/// Wrapped in a span (`service`, `route`, `upstream`, `latency_ms`,
+/// `status`) so it shows up in whatever `tracing` subscriber is
+/// installed — including an OTLP exporter, when the `otel` feature is on
+/// (see [`rig-gateway::otel`]).
+pub async fn handle_request(State(gateway): State<Gateway>, req: Request) -> Response {
+    let path = req.uri().path().to_string();
+    let span = tracing::info_span!(
+        "gateway.request",
+        service = "rig-gateway",
+        path = %path,
+        route = tracing::field::Empty,
+        upstream = tracing::field::Empty,
+        status = tracing::field::Empty,
+        latency_ms = tracing::field::Empty,
+    );
+    async move {
+        let start = std::time::Instant::now();
+        let response = match proxy(&gateway, req).await {
+            Ok(resp) => resp,
+            Err(err) => err.into_response(),
+        };
+        let span = tracing::Span::current();
+        span.record("status", response.status().as_u16());
+        span.record("latency_ms", start.elapsed().as_millis() as u64);
+        response
+    }
+    .instrument(span)
+    .await
+}
+
+/// Send the upstream request, retrying up to `policy.max_retries` times on
+/// connection errors or timeouts, with a fixed backoff between attempts.
+async fn send_with_retry(
+    url: &str,
+    build: impl Fn() -> reqwest::RequestBuilder,
+    policy: &crate::route::RetryPolicy,
+) -> Result<reqwest::Response, GatewayError> {
+    let mut attempt = 0;
+    loop {
+        let outcome = tokio::time::timeout(policy.timeout, build().send()).await;
+        match outcome {
+            Ok(Ok(response)) => return Ok(response),
+            Ok(Err(err)) if attempt < policy.max_retries => {
+                warn!(%err, attempt, "upstream request failed, retrying");
+            }
+            Ok(Err(err)) => return Err(GatewayError::Upstream(err)),
+            Err(_) if attempt < policy.max_retries => {
+                warn!(attempt, "upstream request timed out, retrying");
+            }
+            Err(_) => return Err(GatewayError::UpstreamTimeout(url.to_string())),
+        }
+        attempt += 1;
+        tokio::time::sleep(policy.backoff).await;
+    }
+}
+
+async fn proxy(gateway: &Gateway, req: Request) -> Result<Response, GatewayError> {
+    let path = req.uri().path().to_string();
+    let matched = gateway
+        .find_route(&path)
+        .await
+        .ok_or_else(|| GatewayError::NoRoute(path.clone()))?;
+
+    let upstream = select_upstream(matched.route, req.headers());
+    tracing::Span::current().record("route", matched.route.path.as_str());
+    tracing::Span::current().record("upstream", upstream);
+    let upstream_url = if matched.remainder.is_empty() {
+        format!("{upstream}{path}")
+    } else {
+        format!("{upstream}/{}", matched.remainder)
+    };
+    if matched.route.json_only {
+        let content_type = req
+            .headers()
+            .get(axum::http::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("");
+        if !content_type.starts_with("application/json") {
+            return Err(GatewayError::UnsupportedContentType(content_type.to_string()));
+        }
+    }
+
+    let request_body_limit = matched.route.max_request_body_bytes.unwrap_or(gateway.max_body_bytes);
+    if let Some(content_length) = req
+        .headers()
+        .get(axum::http::header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<usize>().ok())
+    {
+        if content_length > request_body_limit {
+            return Err(GatewayError::RequestBodyTooLarge {
+                actual: content_length,
+                limit: request_body_limit,
+            });
+        }
+    }
+
+    let policy = matched.route.retry_policy.clone();
+    let method = req.method().clone();
+    let original_host = req
+        .headers()
+        .get(axum::http::header::HOST)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+    let headers = forwarded_headers(req.headers());
+    let trace_id = trace_id(req.headers());
+    let body = axum::body::to_bytes(req.into_body(), request_body_limit)
+        .await
+        .unwrap_or_default();
+
+    let response = send_with_retry(
+        &upstream_url,
+        || {
+            gateway
+                .client
+                .request(to_reqwest_method(&method), &upstream_url)
+                .headers(to_reqwest_headers(&headers))
+                .header("x-forwarded-for", "gateway")
+                .header("x-forwarded-host", original_host.as_str())
+                .header(TRACE_ID_HEADER, trace_id.as_str())
+                .body(body.clone())
+        },
+        &policy,
+    )
+    .await?;
+    let status = response.status();
+
+    if let Some(len) = response.content_length() {
+        if len as usize > gateway.max_body_bytes {
+            return Err(GatewayError::BodyTooLarge {
+                actual: len as usize,
+                limit: gateway.max_body_bytes,
+            });
+        }
+    }
+
+    let limit = gateway.max_body_bytes;
+    let mut seen = 0usize;
+    let stream = response.bytes_stream().map(move |chunk| match chunk {
+        Ok(bytes) => {
+            seen += bytes.len();
+            if seen > limit {
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    format!("upstream body exceeded {limit} byte limit"),
+                ))
+            } else {
+                Ok(bytes)
+            }
+        }
+        Err(err) => {
+            warn!(%err, "failed reading upstream body chunk");
+            Err(std::io::Error::new(std::io::ErrorKind::Other, err.to_string()))
+        }
+    });
+
+    let body = Body::from_stream(stream);
+    let status = StatusCode::from_u16(status.as_u16()).unwrap_or(StatusCode::BAD_GATEWAY);
+    Ok(Response::builder().status(status).body(body).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trace_id_reuses_an_inbound_header_instead_of_minting_a_new_one() {
+        let mut headers = HeaderMap::new();
+        headers.insert(TRACE_ID_HEADER, "req-123".parse().unwrap());
+        assert_eq!(trace_id(&headers), "req-123");
+    }
+
+    #[test]
+    fn trace_id_mints_a_fresh_one_when_absent() {
+        assert!(!trace_id(&HeaderMap::new()).is_empty());
+    }
+
+    #[tokio::test]
+    async fn healthz_reports_ok_regardless_of_downstream_health() {
+        assert_eq!(handle_healthz().await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn readyz_is_ok_when_the_registry_has_a_healthy_upstream() {
+        let gateway = Gateway::new(vec![Route::new("/data", "http://upstream")]);
+        gateway.health.set("http://upstream", true).await;
+
+        let summary = gateway.readiness().await;
+        assert!(summary.ready);
+        assert_eq!(summary.healthy_upstreams, 1);
+        assert_eq!(summary.total_upstreams, 1);
+
+        let response = handle_readyz(State(gateway)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn select_upstream_without_a_canary_always_picks_stable() {
+        let route = Route::new("/data", "http://stable");
+        let mut headers = HeaderMap::new();
+        headers.insert(CANARY_KEY_HEADER, "client-1".parse().unwrap());
+        assert_eq!(select_upstream(&route, &headers), "http://stable");
+    }
+
+    #[test]
+    fn select_upstream_is_stable_for_a_repeated_canary_key() {
+        use crate::route::CanaryConfig;
+        let route = Route::new("/data", "http://stable").with_canary(CanaryConfig::new("http://canary", 0.5));
+        let mut headers = HeaderMap::new();
+        headers.insert(CANARY_KEY_HEADER, "client-1".parse().unwrap());
+
+        let first = select_upstream(&route, &headers);
+        for _ in 0..10 {
+            assert_eq!(select_upstream(&route, &headers), first);
+        }
+    }
+
+    #[tokio::test]
+    async fn readyz_is_unavailable_when_no_upstream_is_healthy() {
+        let gateway = Gateway::new(vec![Route::new("/data", "http://upstream")]);
+        gateway.health.set("http://upstream", false).await;
+
+        let summary = gateway.readiness().await;
+        assert!(!summary.ready);
+        assert_eq!(summary.healthy_upstreams, 0);
+
+        let response = handle_readyz(State(gateway)).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+
+/// Tracks the last-known liveness of each registered upstream, as observed
+/// by periodically polling `{upstream}/health`.
+#[derive(Clone, Default)]
+pub struct HealthRegistry {
+    healthy: Arc<RwLock<HashMap<String, bool>>>,
+}
+
+impl HealthRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn is_healthy(&self, upstream: &str) -> bool {
+        // Unknown upstreams are assumed healthy until the first poll completes.
+        self.healthy.read().await.get(upstream).copied().unwrap_or(true)
+    }
+
+    /// Record `upstream`'s health directly, bypassing the poll loop.
+    /// `pub(crate)` rather than fully public: callers outside this crate
+    /// should observe health via [`Self::is_healthy`], not set it, since
+    /// [`Self::spawn_polling`] is the single source of truth in production.
+    pub(crate) async fn set(&self, upstream: &str, healthy: bool) {
+        self.healthy.write().await.insert(upstream.to_string(), healthy);
+    }
+
+    /// Spawn a background task that polls every upstream in `upstreams`
+    /// every `interval`, updating this registry's view of their health.
+    pub fn spawn_polling(&self, upstreams: Vec<String>, interval: Duration) {
+        let registry = self.clone();
+        let client = reqwest::Client::new();
+        tokio::spawn(async move {
+            loop {
+                for upstream in &upstreams {
+                    let url = format!("{upstream}/health");
+                    let healthy = match client.get(&url).send().await {
+                        Ok(resp) => resp.status().is_success(),
+                        Err(err) => {
+                            warn!(%err, %upstream, "health check failed");
+                            false
+                        }
+                    };
+                    debug!(%upstream, healthy, "health check result");
+                    registry.set(upstream, healthy).await;
+                }
+                tokio::time::sleep(interval).await;
+            }
+        });
+    }
+}
@@ -0,0 +1,11 @@
+mod error;
+mod gateway;
+mod health;
+#[cfg(feature = "otel")]
+pub mod otel;
+mod route;
+
+pub use error::GatewayError;
+pub use gateway::{handle_healthz, handle_readyz, handle_request, Gateway, ReadinessSummary, DEFAULT_MAX_BODY_BYTES};
+pub use health::HealthRegistry;
+pub use route::{CanaryConfig, Route, RetryPolicy};
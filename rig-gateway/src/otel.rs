@@ -0,0 +1,97 @@
+//! Optional OpenTelemetry span export, enabled by the `otel` cargo feature.
+//! [`handle_request`](crate::handle_request) already emits a `tracing`
+//! span per request; this module only adds a layer that exports it (and
+//! everything else the process emits) over OTLP.
+
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::TracerProvider;
+use opentelemetry_sdk::{runtime, Resource};
+use tracing_subscriber::layer::SubscriberExt;
+
+/// Where to export spans, and what service name to tag them with.
+#[derive(Debug, Clone)]
+pub struct TracingConfig {
+    pub otlp_endpoint: String,
+    pub service_name: String,
+}
+
+impl TracingConfig {
+    pub fn new(otlp_endpoint: impl Into<String>, service_name: impl Into<String>) -> Self {
+        Self {
+            otlp_endpoint: otlp_endpoint.into(),
+            service_name: service_name.into(),
+        }
+    }
+}
+
+/// Build an OTLP trace pipeline for `config` and install it as a layer on
+/// the global `tracing` subscriber. Returns the [`TracerProvider`] so the
+/// caller can flush/shut it down on exit.
+pub fn init(config: &TracingConfig) -> Result<TracerProvider, opentelemetry::trace::TraceError> {
+    let provider = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(config.otlp_endpoint.clone()),
+        )
+        .with_trace_config(opentelemetry_sdk::trace::config().with_resource(Resource::new(vec![
+            KeyValue::new("service.name", config.service_name.clone()),
+        ])))
+        .install_batch(runtime::Tokio)?;
+
+    let tracer = provider.tracer(config.service_name.clone());
+    let subscriber = tracing_subscriber::Registry::default().with(tracing_opentelemetry::layer().with_tracer(tracer));
+    let _ = tracing::subscriber::set_global_default(subscriber);
+
+    Ok(provider)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::extract::State;
+    use axum::routing::get;
+    use axum::Router;
+    use opentelemetry::trace::{Tracer, TracerProvider as _};
+    use opentelemetry_sdk::testing::trace::InMemorySpanExporter;
+    use opentelemetry_sdk::trace::TracerProvider;
+    use tokio::net::TcpListener;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    use crate::{handle_request, Gateway, Route};
+
+    #[tokio::test]
+    async fn a_gateway_request_emits_a_span_per_request() {
+        let upstream = Router::new().route("/data", get(|| async { "ok" }));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let upstream_addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, upstream).await.unwrap();
+        });
+
+        let exporter = InMemorySpanExporter::default();
+        let provider = TracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let tracer = provider.tracer("test");
+        let subscriber = tracing_subscriber::Registry::default().with(tracing_opentelemetry::layer().with_tracer(tracer));
+
+        let gateway = Gateway::new(vec![Route::new("/data", format!("http://{upstream_addr}"))]);
+
+        let _guard = tracing::subscriber::set_default(subscriber);
+        handle_request(State(gateway.clone()), request_to("/data")).await;
+        handle_request(State(gateway), request_to("/data")).await;
+        drop(_guard);
+
+        provider.force_flush();
+        let spans = exporter.get_finished_spans().unwrap();
+        let gateway_spans: Vec<_> = spans.iter().filter(|span| span.name == "gateway.request").collect();
+        assert_eq!(gateway_spans.len(), 2, "expected one span per request, got {spans:?}");
+    }
+
+    fn request_to(path: &str) -> axum::extract::Request {
+        axum::http::Request::builder().uri(path).body(axum::body::Body::empty()).unwrap()
+    }
+}
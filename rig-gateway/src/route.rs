@@ -0,0 +1,209 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Timeout and retry behavior applied to requests forwarded through a
+/// [`Route`]. Retries apply only to idempotent failures: connection errors
+/// and timeouts, not upstream 4xx/5xx responses.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub timeout: Duration,
+    pub max_retries: u32,
+    pub backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+            max_retries: 0,
+            backoff: Duration::from_millis(100),
+        }
+    }
+}
+
+/// Sends a fraction of a [`Route`]'s traffic to a separate canary upstream
+/// instead of the stable one, for staged rollouts.
+#[derive(Debug, Clone)]
+pub struct CanaryConfig {
+    pub upstream: String,
+    /// Fraction of requests sent to [`Self::upstream`] instead of the
+    /// route's stable `upstream`, in `0.0..=1.0`.
+    pub weight: f64,
+}
+
+impl CanaryConfig {
+    pub fn new(upstream: impl Into<String>, weight: f64) -> Self {
+        Self {
+            upstream: upstream.into(),
+            weight: weight.clamp(0.0, 1.0),
+        }
+    }
+}
+
+/// A single forwarding rule. `path` may contain `:name` segments, which are
+/// captured as path parameters, and/or end in `*` to match any suffix as a
+/// prefix route (e.g. `/api/*` matches `/api/anything/below`).
+#[derive(Debug, Clone)]
+pub struct Route {
+    pub path: String,
+    pub upstream: String,
+    pub retry_policy: RetryPolicy,
+    /// Reject requests whose `Content-Type` isn't `application/json` with a
+    /// `415`, before forwarding to the upstream.
+    pub json_only: bool,
+    /// Reject requests whose body exceeds this many bytes with a `413`,
+    /// before forwarding to the upstream. `None` defers to the gateway's
+    /// overall body limit.
+    pub max_request_body_bytes: Option<usize>,
+    /// Optional canary split: when set, a fraction of requests to this
+    /// route are sent to [`CanaryConfig::upstream`] instead of
+    /// [`Self::upstream`].
+    pub canary: Option<CanaryConfig>,
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Param(String),
+    Wildcard,
+}
+
+/// The result of successfully matching a request path against a [`Route`].
+#[derive(Debug, Clone)]
+pub struct RouteMatch<'a> {
+    pub route: &'a Route,
+    pub params: HashMap<String, String>,
+    /// The portion of the path consumed by a trailing `*` wildcard, if any.
+    pub remainder: String,
+}
+
+impl Route {
+    pub fn new(path: impl Into<String>, upstream: impl Into<String>) -> Self {
+        let path = path.into();
+        let segments = Self::parse_segments(&path);
+        Self {
+            path,
+            upstream: upstream.into(),
+            retry_policy: RetryPolicy::default(),
+            json_only: false,
+            max_request_body_bytes: None,
+            canary: None,
+            segments,
+        }
+    }
+
+    pub fn with_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// Reject requests to this route whose `Content-Type` isn't JSON.
+    pub fn with_json_only(mut self) -> Self {
+        self.json_only = true;
+        self
+    }
+
+    /// Reject requests to this route whose body exceeds `max_bytes`.
+    pub fn with_max_request_body_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_request_body_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Send `canary.weight` of this route's traffic to `canary.upstream`
+    /// instead of [`Self::upstream`].
+    pub fn with_canary(mut self, canary: CanaryConfig) -> Self {
+        self.canary = Some(canary);
+        self
+    }
+
+    fn parse_segments(path: &str) -> Vec<Segment> {
+        path.trim_matches('/')
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                if s == "*" {
+                    Segment::Wildcard
+                } else if let Some(name) = s.strip_prefix(':') {
+                    Segment::Param(name.to_string())
+                } else {
+                    Segment::Literal(s.to_string())
+                }
+            })
+            .collect()
+    }
+
+    /// Attempt to match `request_path` against this route, extracting any
+    /// `:name` path parameters and the wildcard remainder.
+    pub fn matches<'a>(&'a self, request_path: &str) -> Option<RouteMatch<'a>> {
+        let request_segments: Vec<&str> = request_path
+            .trim_matches('/')
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let mut params = HashMap::new();
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                Segment::Wildcard => {
+                    let remainder = request_segments.get(i..).unwrap_or(&[]).join("/");
+                    return Some(RouteMatch {
+                        route: self,
+                        params,
+                        remainder,
+                    });
+                }
+                Segment::Literal(expected) => {
+                    if request_segments.get(i) != Some(&expected.as_str()) {
+                        return None;
+                    }
+                }
+                Segment::Param(name) => {
+                    let value = request_segments.get(i)?;
+                    params.insert(name.clone(), value.to_string());
+                }
+            }
+        }
+
+        if request_segments.len() != self.segments.len() {
+            return None;
+        }
+
+        Some(RouteMatch {
+            route: self,
+            params,
+            remainder: String::new(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_param_segments() {
+        let route = Route::new("/agents/:id/status", "http://upstream");
+        let m = route.matches("/agents/42/status").unwrap();
+        assert_eq!(m.params.get("id"), Some(&"42".to_string()));
+    }
+
+    #[test]
+    fn matches_wildcard_prefix() {
+        let route = Route::new("/api/*", "http://upstream");
+        let m = route.matches("/api/v1/agents/42").unwrap();
+        assert_eq!(m.remainder, "v1/agents/42");
+    }
+
+    #[test]
+    fn rejects_mismatched_literal() {
+        let route = Route::new("/agents/:id/status", "http://upstream");
+        assert!(route.matches("/users/42/status").is_none());
+    }
+
+    #[test]
+    fn rejects_wrong_segment_count() {
+        let route = Route::new("/agents/:id", "http://upstream");
+        assert!(route.matches("/agents/42/status").is_none());
+    }
+}
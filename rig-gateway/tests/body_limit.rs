@@ -0,0 +1,35 @@
+use axum::routing::get;
+use axum::Router;
+use rig_gateway::{handle_request, Gateway, Route};
+use tokio::net::TcpListener;
+
+async fn spawn_upstream(body_len: usize) -> String {
+    let app = Router::new().route(
+        "/data",
+        get(move || async move { "x".repeat(body_len) }),
+    );
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{addr}")
+}
+
+#[tokio::test]
+async fn oversized_upstream_body_is_rejected() {
+    let upstream = spawn_upstream(1024).await;
+    let gateway = Gateway::new(vec![Route::new("/data", upstream)]).with_max_body_bytes(128);
+    let app = Router::new()
+        .route("/data", axum::routing::any(handle_request))
+        .with_state(gateway);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let resp = reqwest::get(format!("http://{addr}/data")).await.unwrap();
+    assert_eq!(resp.status(), 413);
+}
@@ -0,0 +1,57 @@
+use axum::routing::get;
+use axum::Router;
+use rig_gateway::{handle_request, CanaryConfig, Gateway, Route};
+use tokio::net::TcpListener;
+
+async fn spawn_upstream(label: &'static str) -> String {
+    let app = Router::new().route("/data", get(move || async move { label }));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{addr}")
+}
+
+#[tokio::test]
+async fn canary_weight_is_roughly_honored_over_many_requests() {
+    let stable = spawn_upstream("stable").await;
+    let canary = spawn_upstream("canary").await;
+
+    let weight = 0.2;
+    let gateway = Gateway::new(vec![
+        Route::new("/data", stable).with_canary(CanaryConfig::new(canary, weight)),
+    ]);
+    let app = Router::new()
+        .route("/data", axum::routing::any(handle_request))
+        .with_state(gateway);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let client = reqwest::Client::new();
+    let total = 1000;
+    let mut canary_hits = 0;
+    for _ in 0..total {
+        let body = client
+            .get(format!("http://{addr}/data"))
+            .send()
+            .await
+            .unwrap()
+            .text()
+            .await
+            .unwrap();
+        if body == "canary" {
+            canary_hits += 1;
+        }
+    }
+
+    let observed = canary_hits as f64 / total as f64;
+    assert!(
+        (observed - weight).abs() < 0.05,
+        "expected roughly {weight} of requests to hit the canary, observed {observed}"
+    );
+}
@@ -0,0 +1,49 @@
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use axum::extract::ConnectInfo;
+use axum::routing::get;
+use axum::Router;
+use rig_gateway::{handle_request, Gateway, Route};
+use tokio::net::TcpListener;
+
+async fn spawn_upstream_reporting_its_peer_addr() -> String {
+    async fn connecting_addr_handler(ConnectInfo(addr): ConnectInfo<SocketAddr>) -> String {
+        addr.to_string()
+    }
+
+    let app = Router::new().route("/data", get(connecting_addr_handler));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await.unwrap();
+    });
+    format!("http://{addr}")
+}
+
+#[tokio::test]
+async fn repeated_requests_through_the_gateway_reuse_the_same_upstream_connection() {
+    let upstream = spawn_upstream_reporting_its_peer_addr().await;
+    let gateway = Gateway::with_pool_config(
+        vec![Route::new("/data", upstream)],
+        rig_core::HttpPoolConfig { idle_timeout: Duration::from_secs(30), max_idle_per_host: 4 },
+    );
+    let app = Router::new().route("/data", axum::routing::any(handle_request)).with_state(gateway);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let client = reqwest::Client::new();
+    let url = format!("http://{addr}/data");
+
+    // Sequential (not concurrent) requests so the gateway's pooled
+    // connection to the upstream is free to be reused rather than forcing
+    // a second one to open.
+    let first_peer = client.get(&url).send().await.unwrap().text().await.unwrap();
+    let second_peer = client.get(&url).send().await.unwrap().text().await.unwrap();
+
+    assert_eq!(first_peer, second_peer, "expected the gateway to reuse its pooled connection to the upstream");
+}
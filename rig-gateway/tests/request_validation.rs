@@ -0,0 +1,87 @@
+use axum::routing::post;
+use axum::Router;
+use rig_gateway::{handle_request, Gateway, Route};
+use tokio::net::TcpListener;
+
+async fn spawn_upstream() -> String {
+    let app = Router::new().route("/data", post(|| async { "ok" }));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    format!("http://{addr}")
+}
+
+#[tokio::test]
+async fn oversized_request_body_is_rejected_before_forwarding() {
+    let upstream = spawn_upstream().await;
+    let gateway = Gateway::new(vec![
+        Route::new("/data", upstream).with_max_request_body_bytes(8),
+    ]);
+    let app = Router::new()
+        .route("/data", axum::routing::any(handle_request))
+        .with_state(gateway);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let resp = reqwest::Client::new()
+        .post(format!("http://{addr}/data"))
+        .body("this body is way over the limit")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 413);
+}
+
+#[tokio::test]
+async fn wrong_content_type_is_rejected_on_a_json_only_route() {
+    let upstream = spawn_upstream().await;
+    let gateway = Gateway::new(vec![Route::new("/data", upstream).with_json_only()]);
+    let app = Router::new()
+        .route("/data", axum::routing::any(handle_request))
+        .with_state(gateway);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let resp = reqwest::Client::new()
+        .post(format!("http://{addr}/data"))
+        .header("content-type", "text/plain")
+        .body("not json")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 415);
+}
+
+#[tokio::test]
+async fn matching_content_type_is_forwarded_on_a_json_only_route() {
+    let upstream = spawn_upstream().await;
+    let gateway = Gateway::new(vec![Route::new("/data", upstream).with_json_only()]);
+    let app = Router::new()
+        .route("/data", axum::routing::any(handle_request))
+        .with_state(gateway);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let resp = reqwest::Client::new()
+        .post(format!("http://{addr}/data"))
+        .header("content-type", "application/json")
+        .body("{}")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+}
@@ -0,0 +1,166 @@
+use std::sync::Arc;
+
+use arrow_array::{Float32Array, RecordBatch, RecordBatchIterator, StringArray};
+use arrow_schema::{DataType, Field, Schema};
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use lancedb::query::{ExecutableQuery, QueryBase};
+use lancedb::Table;
+use rig_vectorstore::{SearchResult, VectorStore};
+
+#[derive(Debug, thiserror::Error)]
+pub enum LanceStoreError {
+    #[error("lancedb operation failed: {0}")]
+    Operation(#[from] lancedb::Error),
+    #[error("arrow error: {0}")]
+    Arrow(#[from] arrow_schema::ArrowError),
+}
+
+/// MEAP backend that stores agent-produced embeddings in a LanceDB table
+/// with an `id` (string), `vector` (fixed-size float32 list) and
+/// `payload` (JSON-encoded string) column. The table must already exist
+/// with that schema; this store only reads and writes rows.
+pub struct LanceStore {
+    table: Table,
+    dimensions: i32,
+}
+
+impl LanceStore {
+    pub async fn connect(uri: &str, table_name: &str, dimensions: i32) -> Result<Self, LanceStoreError> {
+        let connection = lancedb::connect(uri).execute().await?;
+        let table = connection.open_table(table_name).execute().await?;
+        Ok(Self { table, dimensions })
+    }
+
+    pub async fn upsert_row(&self, id: String, vector: Vec<f32>, payload: serde_json::Value) -> Result<(), LanceStoreError> {
+        // LanceDB has no native upsert: delete any existing row for this
+        // id first, then append the new one.
+        self.delete_row(id.clone()).await?;
+
+        let schema = row_schema(self.dimensions);
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(StringArray::from(vec![id])),
+                Arc::new(vector_column(&[vector], self.dimensions)),
+                Arc::new(StringArray::from(vec![payload.to_string()])),
+            ],
+        )?;
+        let reader = RecordBatchIterator::new(vec![Ok(batch)], schema);
+        self.table.add(Box::new(reader)).execute().await?;
+        Ok(())
+    }
+
+    pub async fn search_rows(
+        &self,
+        query: Vec<f32>,
+        limit: usize,
+        filter: Option<serde_json::Value>,
+    ) -> Result<Vec<SearchResult>, LanceStoreError> {
+        let mut search = self.table.query().nearest_to(query.as_slice())?.limit(limit);
+        if let Some(predicate) = exact_match_predicate(&filter) {
+            search = search.only_if(predicate);
+        }
+
+        let batches: Vec<RecordBatch> = search.execute().await?.try_collect().await?;
+        let mut results = Vec::new();
+        for batch in batches {
+            let ids = batch.column_by_name("id").and_then(|c| c.as_any().downcast_ref::<StringArray>());
+            let payloads = batch.column_by_name("payload").and_then(|c| c.as_any().downcast_ref::<StringArray>());
+            let distances = batch.column_by_name("_distance").and_then(|c| c.as_any().downcast_ref::<Float32Array>());
+            let (Some(ids), Some(payloads), Some(distances)) = (ids, payloads, distances) else { continue };
+            for row in 0..batch.num_rows() {
+                results.push(SearchResult {
+                    id: ids.value(row).to_string(),
+                    score: distances.value(row),
+                    payload: serde_json::from_str(payloads.value(row)).unwrap_or(serde_json::Value::Null),
+                });
+            }
+        }
+        Ok(results)
+    }
+
+    pub async fn delete_row(&self, id: String) -> Result<(), LanceStoreError> {
+        self.table.delete(&format!("id = '{id}'")).await?;
+        Ok(())
+    }
+}
+
+fn row_schema(dimensions: i32) -> Arc<Schema> {
+    Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new(
+            "vector",
+            DataType::FixedSizeList(Arc::new(Field::new("item", DataType::Float32, true)), dimensions),
+            false,
+        ),
+        Field::new("payload", DataType::Utf8, false),
+    ]))
+}
+
+fn vector_column(vectors: &[Vec<f32>], dimensions: i32) -> arrow_array::FixedSizeListArray {
+    arrow_array::FixedSizeListArray::from_iter_primitive::<arrow_array::types::Float32Type, _, _>(
+        vectors.iter().map(|vector| Some(vector.iter().copied().map(Some).collect::<Vec<_>>())),
+        dimensions,
+    )
+}
+
+/// Build a LanceDB SQL predicate ANDing together exact matches for each
+/// field in a flat JSON object. Anything that isn't a flat object
+/// (including `None`) is treated as "no filter".
+fn exact_match_predicate(filter: &Option<serde_json::Value>) -> Option<String> {
+    let object = filter.as_ref()?.as_object()?;
+    let clauses: Vec<String> = object
+        .iter()
+        .map(|(key, value)| format!("{key} = '{}'", value.as_str().unwrap_or_default()))
+        .collect();
+    if clauses.is_empty() {
+        None
+    } else {
+        Some(clauses.join(" AND "))
+    }
+}
+
+#[async_trait]
+impl VectorStore for LanceStore {
+    async fn upsert(
+        &self,
+        id: String,
+        vector: Vec<f32>,
+        payload: serde_json::Value,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.upsert_row(id, vector, payload).await.map_err(Into::into)
+    }
+
+    async fn search(
+        &self,
+        query: Vec<f32>,
+        limit: usize,
+        filter: Option<serde_json::Value>,
+    ) -> Result<Vec<SearchResult>, Box<dyn std::error::Error + Send + Sync>> {
+        self.search_rows(query, limit, filter).await.map_err(Into::into)
+    }
+
+    async fn delete(&self, id: String) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.delete_row(id).await.map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Requires a LanceDB table named `rig-lancedb-tests` with the
+    /// `id`/`vector`/`payload` schema this store expects; run with
+    /// `cargo test -- --ignored`.
+    #[tokio::test]
+    #[ignore]
+    async fn upserts_and_finds_a_row_through_the_trait_object() {
+        let store: Box<dyn VectorStore> =
+            Box::new(LanceStore::connect("data/rig-lancedb-tests.lance", "rig-lancedb-tests", 3).await.unwrap());
+        store.upsert("1".to_string(), vec![0.1, 0.2, 0.3], serde_json::json!({"kind": "test"})).await.unwrap();
+        let results = store.search(vec![0.1, 0.2, 0.3], 1, None).await.unwrap();
+        assert_eq!(results[0].id, "1");
+        store.delete("1".to_string()).await.unwrap();
+    }
+}
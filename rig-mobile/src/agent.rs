@@ -0,0 +1,412 @@
+use std::collections::{HashMap, VecDeque};
+use std::io::Read;
+use std::sync::{Arc, Mutex};
+
+use rig_core::{Connection, DeadLetterStore, SecurityManager};
+use uuid::Uuid;
+
+use crate::error::Error;
+use crate::message::{AgentMessage, MessageType};
+use crate::network::{NetworkManager, NetworkStatus, QueuePolicy};
+
+/// Default cap on how many times [`MobileAgent::retransmit_pending`]
+/// retries a single unacked message before giving up on it.
+const DEFAULT_MAX_RECONNECT_ATTEMPTS: u32 = 5;
+
+struct PendingMessage {
+    message: AgentMessage,
+    attempts: u32,
+}
+
+/// A mobile client's side of a MEAP connection: wraps the same
+/// [`Connection`] the server pools, and additionally tracks outgoing
+/// messages that asked for an ack so they can be retransmitted across
+/// reconnects.
+#[derive(Clone)]
+pub struct MobileAgent {
+    connection: Connection,
+    security: Option<SecurityManager>,
+    pending: Arc<Mutex<HashMap<Uuid, PendingMessage>>>,
+    max_reconnect_attempts: u32,
+    network: Option<NetworkManager>,
+    queue_policy: QueuePolicy,
+    message_queue: Arc<Mutex<VecDeque<AgentMessage>>>,
+    dead_letters: Option<Arc<dyn DeadLetterStore>>,
+}
+
+impl MobileAgent {
+    pub fn new(connection: Connection) -> Self {
+        Self {
+            connection,
+            security: None,
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            max_reconnect_attempts: DEFAULT_MAX_RECONNECT_ATTEMPTS,
+            network: None,
+            queue_policy: QueuePolicy::default(),
+            message_queue: Arc::new(Mutex::new(VecDeque::new())),
+            dead_letters: None,
+        }
+    }
+
+    /// Decrypt `encrypted` inbound messages with `security` in
+    /// [`Self::process_messages`].
+    pub fn with_security(mut self, security: SecurityManager) -> Self {
+        self.security = Some(security);
+        self
+    }
+
+    pub fn with_max_reconnect_attempts(mut self, max_reconnect_attempts: u32) -> Self {
+        self.max_reconnect_attempts = max_reconnect_attempts;
+        self
+    }
+
+    /// Defer non-urgent [`Self::send_message`] calls per `network`'s
+    /// conditions, instead of always sending immediately.
+    pub fn with_network(mut self, network: NetworkManager) -> Self {
+        self.network = Some(network);
+        self
+    }
+
+    pub fn with_queue_policy(mut self, queue_policy: QueuePolicy) -> Self {
+        self.queue_policy = queue_policy;
+        self
+    }
+
+    /// Route messages that fail to send or fail to decode into `store`
+    /// instead of dropping them, so they can be inspected or replayed
+    /// later via [`rig_core::retry_dead_letters`].
+    pub fn with_dead_letter_store(mut self, store: Arc<dyn DeadLetterStore>) -> Self {
+        self.dead_letters = Some(store);
+        self
+    }
+
+    /// Send `message`, tracking it for retransmission if it asked for an
+    /// ack. If a [`NetworkManager`] is configured and current conditions
+    /// favor deferral, the message is held in the outgoing queue instead
+    /// of being sent (unless its priority is urgent per
+    /// [`QueuePolicy`]), and flushed later by [`Self::update_network`].
+    pub async fn send_message(&self, message: AgentMessage) -> Result<(), Error> {
+        if let Some(network) = &self.network {
+            if self.queue_policy.should_defer(&message, network).await {
+                self.message_queue.lock().unwrap().push_back(message);
+                return Ok(());
+            }
+        }
+
+        if message.requires_ack {
+            self.pending
+                .lock()
+                .unwrap()
+                .insert(message.id, PendingMessage { message: message.clone(), attempts: 0 });
+        }
+
+        let result = self.transmit(&message).await;
+        if let Err(err) = &result {
+            self.dead_letter(&message, err.to_string()).await;
+        }
+        result
+    }
+
+    /// Capture `message` in the configured [`DeadLetterStore`], if any, so
+    /// a send or decode failure isn't just dropped on the floor.
+    async fn dead_letter(&self, message: &AgentMessage, error: String) {
+        if let Some(dead_letters) = &self.dead_letters {
+            let payload = serde_json::to_value(message).unwrap_or(serde_json::Value::Null);
+            dead_letters.capture(payload, error).await;
+        }
+    }
+
+    /// Update the device's current network/battery status, and flush any
+    /// messages held in the outgoing queue that no longer need to be
+    /// deferred under the new conditions.
+    pub async fn update_network(&self, status: NetworkStatus) {
+        let Some(network) = &self.network else { return };
+        network.update(status).await;
+        self.flush_queue().await;
+    }
+
+    /// Re-submit every currently-queued message through
+    /// [`Self::send_message`], which puts still-deferred messages (if
+    /// conditions only partially improved) straight back on the queue.
+    async fn flush_queue(&self) {
+        let queued: VecDeque<AgentMessage> = std::mem::take(&mut *self.message_queue.lock().unwrap());
+        for message in queued {
+            let _ = self.send_message(message).await;
+        }
+    }
+
+    async fn transmit(&self, message: &AgentMessage) -> Result<(), Error> {
+        let payload = serde_json::to_string(message)?;
+        self.connection.send(payload).await.map_err(Error::from)
+    }
+
+    /// Clear a pending outgoing message once its ack has arrived.
+    pub fn acknowledge(&self, message_id: Uuid) {
+        self.pending.lock().unwrap().remove(&message_id);
+    }
+
+    /// Drop pending messages past their `expires_at`, and retransmit the
+    /// rest, giving up on (and dropping) any that have already hit
+    /// `max_reconnect_attempts`. Intended to be called after a reconnect.
+    pub async fn retransmit_pending(&self) {
+        let to_send = {
+            let mut pending = self.pending.lock().unwrap();
+            pending.retain(|_, entry| !entry.message.is_expired());
+
+            let mut to_send = Vec::new();
+            pending.retain(|_, entry| {
+                if entry.attempts >= self.max_reconnect_attempts {
+                    return false;
+                }
+                entry.attempts += 1;
+                to_send.push(entry.message.clone());
+                true
+            });
+            to_send
+        };
+
+        for message in to_send {
+            let _ = self.transmit(&message).await;
+        }
+    }
+
+    /// Decode and dispatch a batch of raw inbound frames. A
+    /// `MessageType::Command` carrying an `action: "ack"` payload clears
+    /// the corresponding entry from the pending-ack tracker instead of
+    /// being surfaced to the caller. Each frame is handled independently:
+    /// a malformed frame is logged and skipped rather than aborting the
+    /// rest of the batch.
+    pub async fn process_messages(&self, raw_messages: &[Vec<u8>]) -> Result<(), Box<dyn std::error::Error>> {
+        for raw in raw_messages {
+            if let Err(err) = self.process_one_message(raw).await {
+                if let Some(dead_letters) = &self.dead_letters {
+                    let payload = serde_json::json!({ "raw_base64": base64::encode(raw) });
+                    dead_letters.capture(payload, err.to_string()).await;
+                }
+                tracing::warn!(%err, "failed to process inbound message, routed to dead-letter store");
+            }
+        }
+        Ok(())
+    }
+
+    async fn process_one_message(&self, raw: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+        let decoded = base64::decode(raw)?;
+        let plaintext = match &self.security {
+            Some(security) => security.decrypt(&decoded)?,
+            None => decoded,
+        };
+        let message: AgentMessage = serde_json::from_slice(&plaintext)?;
+        let content = resolve_content(&message)?;
+
+        if message.message_type == MessageType::Command
+            && content.get("action").and_then(|v| v.as_str()) == Some("ack")
+        {
+            if let Some(target) = content
+                .get("message_id")
+                .and_then(|v| v.as_str())
+                .and_then(|s| Uuid::parse_str(s).ok())
+            {
+                self.acknowledge(target);
+            }
+        }
+
+        // Non-ack messages are decoded but otherwise unhandled here;
+        // dispatching them to an application-supplied handler is out of
+        // scope for this type.
+        Ok(())
+    }
+}
+
+/// Resolve a decoded [`AgentMessage`]'s actual content, decompressing it
+/// first if `message.compressed` says it needs it. Driven by the explicit
+/// flag rather than sniffing `content` for a compression magic prefix,
+/// which misfires on ordinary text that happens to start the same way.
+fn resolve_content(message: &AgentMessage) -> Result<serde_json::Value, Error> {
+    if message.compressed {
+        decompress_content(&message.content)
+    } else {
+        Ok(message.content.clone())
+    }
+}
+
+/// Decode a base64-encoded, zlib-compressed JSON payload back into a
+/// [`serde_json::Value`].
+fn decompress_content(content: &serde_json::Value) -> Result<serde_json::Value, Error> {
+    let encoded = content
+        .as_str()
+        .ok_or_else(|| Error::Decode("compressed content must be a string".to_string()))?;
+    let compressed = base64::decode(encoded).map_err(|err| Error::Decode(err.to_string()))?;
+
+    let mut decoder = flate2::read::ZlibDecoder::new(&compressed[..]);
+    let mut decompressed = String::new();
+    decoder
+        .read_to_string(&mut decompressed)
+        .map_err(|err| Error::Decode(err.to_string()))?;
+
+    serde_json::from_str(&decompressed).map_err(Error::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::sync::mpsc as test_mpsc;
+    use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+    /// A sink that forwards every frame it's given into an unbounded
+    /// channel, so tests can assert on what a `Connection` actually sent.
+    fn capturing_sink() -> (
+        impl futures::Sink<WsMessage, Error = std::convert::Infallible> + Send + Unpin + 'static,
+        test_mpsc::UnboundedReceiver<WsMessage>,
+    ) {
+        let (tx, rx) = test_mpsc::unbounded_channel::<WsMessage>();
+        let sink = futures::sink::unfold(tx, |tx, item: WsMessage| async move {
+            let _ = tx.send(item);
+            Ok::<_, std::convert::Infallible>(tx)
+        });
+        (sink, rx)
+    }
+
+    #[tokio::test]
+    async fn a_message_past_its_expiry_is_dropped_instead_of_retransmitted() {
+        let (sink, mut rx) = capturing_sink();
+        let agent = MobileAgent::new(Connection::spawn(sink));
+
+        let message = AgentMessage::new(MessageType::Data, serde_json::json!({}))
+            .requiring_ack()
+            .with_ttl(Duration::from_secs(0));
+        agent.send_message(message).await.unwrap();
+        rx.recv().await.unwrap(); // the initial send
+
+        agent.retransmit_pending().await;
+        assert!(rx.try_recv().is_err(), "an expired message should not be retransmitted");
+    }
+
+    #[tokio::test]
+    async fn acknowledging_a_message_clears_it_from_the_pending_tracker() {
+        let (sink, mut rx) = capturing_sink();
+        let agent = MobileAgent::new(Connection::spawn(sink));
+
+        let message = AgentMessage::new(MessageType::Data, serde_json::json!({})).requiring_ack();
+        let message_id = message.id;
+        agent.send_message(message).await.unwrap();
+        rx.recv().await.unwrap(); // the initial send
+
+        agent.acknowledge(message_id);
+
+        agent.retransmit_pending().await;
+        assert!(rx.try_recv().is_err(), "an acknowledged message should not be retransmitted");
+    }
+
+    #[tokio::test]
+    async fn an_unacked_message_is_retransmitted_up_to_the_reconnect_attempt_limit() {
+        let (sink, mut rx) = capturing_sink();
+        let agent = MobileAgent::new(Connection::spawn(sink)).with_max_reconnect_attempts(2);
+
+        let message = AgentMessage::new(MessageType::Data, serde_json::json!({})).requiring_ack();
+        agent.send_message(message).await.unwrap();
+        rx.recv().await.unwrap(); // the initial send
+
+        agent.retransmit_pending().await;
+        rx.recv().await.expect("first retry should be sent");
+        agent.retransmit_pending().await;
+        rx.recv().await.expect("second retry should be sent");
+        agent.retransmit_pending().await;
+        assert!(rx.try_recv().is_err(), "retries should stop once the attempt limit is hit");
+    }
+
+    #[tokio::test]
+    async fn a_send_failure_lands_the_message_in_the_dead_letter_store_with_the_error_text() {
+        use rig_core::{CircuitBreaker, InMemoryDeadLetterStore};
+
+        let (sink, _rx) = capturing_sink();
+        let cb = CircuitBreaker::new(1, Duration::from_secs(60));
+        let dead_letters = Arc::new(InMemoryDeadLetterStore::new());
+        let agent = MobileAgent::new(Connection::spawn_with_circuit_breaker(sink, cb))
+            .with_dead_letter_store(dead_letters.clone());
+
+        // Trip the circuit breaker so the next send fails immediately.
+        agent.connection.circuit_breaker.record_failure().await;
+
+        let message = AgentMessage::new(MessageType::Data, serde_json::json!({"n": 1}));
+        let err = agent.send_message(message).await.unwrap_err();
+
+        let entries = dead_letters.entries().await;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].error, err.to_string());
+    }
+
+    #[test]
+    fn uncompressed_content_that_happens_to_start_with_the_old_sniff_prefix_is_left_untouched() {
+        let message = AgentMessage::new(MessageType::Data, serde_json::json!("eJust a normal string"));
+        let content = resolve_content(&message).unwrap();
+        assert_eq!(content, serde_json::json!("eJust a normal string"));
+    }
+
+    #[tokio::test]
+    async fn a_malformed_message_is_skipped_without_blocking_a_valid_one_in_the_same_batch() {
+        let (sink, _rx) = capturing_sink();
+        let agent = MobileAgent::new(Connection::spawn(sink));
+
+        let pending_id = Uuid::new_v4();
+        agent.pending.lock().unwrap().insert(
+            pending_id,
+            PendingMessage {
+                message: AgentMessage::new(MessageType::Data, serde_json::json!({})).requiring_ack(),
+                attempts: 0,
+            },
+        );
+
+        let ack = AgentMessage::new(
+            MessageType::Command,
+            serde_json::json!({ "action": "ack", "message_id": pending_id }),
+        );
+        let valid_raw = base64::encode(serde_json::to_vec(&ack).unwrap());
+        let malformed_raw = "not valid base64!!".to_string();
+
+        agent
+            .process_messages(&[malformed_raw.into_bytes(), valid_raw.into_bytes()])
+            .await
+            .unwrap();
+
+        assert!(
+            !agent.pending.lock().unwrap().contains_key(&pending_id),
+            "the valid ack should still clear its pending entry despite the malformed message before it"
+        );
+    }
+
+    fn cellular() -> NetworkStatus {
+        NetworkStatus { connection_type: crate::network::ConnectionType::Cellular, is_charging: false, battery_percent: 80 }
+    }
+
+    fn wifi() -> NetworkStatus {
+        NetworkStatus { connection_type: crate::network::ConnectionType::Wifi, is_charging: true, battery_percent: 80 }
+    }
+
+    #[tokio::test]
+    async fn a_low_priority_message_is_held_while_on_cellular_and_flushed_once_on_wifi() {
+        let (sink, mut rx) = capturing_sink();
+        let agent = MobileAgent::new(Connection::spawn(sink)).with_network(NetworkManager::new(cellular()));
+
+        agent
+            .send_message(AgentMessage::new(MessageType::Data, serde_json::json!({})))
+            .await
+            .unwrap();
+        assert!(rx.try_recv().is_err(), "a low-priority message should be held on a metered connection");
+
+        agent.update_network(wifi()).await;
+        let delivered = rx.recv().await.expect("the held message should flush once on wifi");
+        assert!(matches!(delivered, WsMessage::Text(_)));
+    }
+
+    #[tokio::test]
+    async fn a_high_priority_message_sends_immediately_even_on_cellular() {
+        let (sink, mut rx) = capturing_sink();
+        let agent = MobileAgent::new(Connection::spawn(sink)).with_network(NetworkManager::new(cellular()));
+
+        let message = AgentMessage::new(MessageType::Data, serde_json::json!({})).with_priority(5);
+        agent.send_message(message).await.unwrap();
+
+        assert!(rx.try_recv().is_ok(), "a high-priority message should bypass deferral");
+    }
+}
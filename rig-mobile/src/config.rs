@@ -0,0 +1,164 @@
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::device::{DeviceFeatureDetector, DeviceInfo};
+
+/// Why a feature ended up enabled or disabled for a particular device, for
+/// UIs that want to show a specific explanation rather than a bare on/off
+/// flag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeatureStatus {
+    Enabled,
+    /// The flag is off (or not configured at all), regardless of device.
+    DisabledByFlag,
+    /// The flag is on, but the device lacks a capability it requires, per
+    /// [`DeviceFeatureDetector`].
+    UnsupportedByDevice,
+    /// The flag is on and the device is otherwise capable, but its OS
+    /// version is below the feature's configured minimum.
+    DeviceTooOld,
+}
+
+impl FeatureStatus {
+    pub fn is_enabled(self) -> bool {
+        matches!(self, FeatureStatus::Enabled)
+    }
+}
+
+/// A single feature flag: whether it's on at all, plus the device
+/// constraints it additionally requires.
+#[derive(Debug, Clone, Default)]
+pub struct FeatureFlag {
+    pub enabled: bool,
+    /// A [`DeviceFeatureDetector`] capability the device must support,
+    /// e.g. `"high_end_device"`.
+    pub requires_device_feature: Option<&'static str>,
+    /// Minimum OS version (compared component-wise as dot-separated
+    /// numbers) the device must report.
+    pub min_os_version: Option<String>,
+}
+
+/// Server-configurable feature flags, additionally gated per-device on
+/// top of the simple on/off flag.
+#[derive(Debug, Clone, Default)]
+pub struct AppConfig {
+    flags: HashMap<String, FeatureFlag>,
+}
+
+impl AppConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_flag(&mut self, feature: impl Into<String>, flag: FeatureFlag) {
+        self.flags.insert(feature.into(), flag);
+    }
+
+    /// Whether `feature` is enabled for `info`. Delegates to
+    /// [`Self::feature_status`]; callers that need to know *why* a
+    /// feature is disabled should use that instead.
+    pub fn is_feature_enabled_for_device(&self, feature: &str, info: &DeviceInfo, detector: &DeviceFeatureDetector) -> bool {
+        self.feature_status(feature, info, detector).is_enabled()
+    }
+
+    /// Evaluate `feature` for `info`, distinguishing *why* it's disabled
+    /// rather than collapsing everything to a bool.
+    pub fn feature_status(&self, feature: &str, info: &DeviceInfo, detector: &DeviceFeatureDetector) -> FeatureStatus {
+        let Some(flag) = self.flags.get(feature) else {
+            return FeatureStatus::DisabledByFlag;
+        };
+        if !flag.enabled {
+            return FeatureStatus::DisabledByFlag;
+        }
+        if let Some(required) = flag.requires_device_feature {
+            if !detector.supports_feature(info, required) {
+                return FeatureStatus::UnsupportedByDevice;
+            }
+        }
+        if let Some(min_version) = &flag.min_os_version {
+            if compare_versions(&info.os_version, min_version) == Ordering::Less {
+                return FeatureStatus::DeviceTooOld;
+            }
+        }
+        FeatureStatus::Enabled
+    }
+}
+
+/// Compare two dot-separated numeric version strings component-wise,
+/// treating a missing or unparsable component as `0`.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parse = |v: &str| -> Vec<u32> { v.split('.').map(|part| part.parse().unwrap_or(0)).collect() };
+    let (a, b) = (parse(a), parse(b));
+    for i in 0..a.len().max(b.len()) {
+        match a.get(i).copied().unwrap_or(0).cmp(&b.get(i).copied().unwrap_or(0)) {
+            Ordering::Equal => continue,
+            ordering => return ordering,
+        }
+    }
+    Ordering::Equal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::Platform;
+
+    fn device(os_version: &str) -> DeviceInfo {
+        DeviceInfo {
+            platform: Platform::Android,
+            os_version: os_version.to_string(),
+            model: "Pixel 7".to_string(),
+            model_generation: None,
+        }
+    }
+
+    #[test]
+    fn an_unconfigured_feature_is_disabled_by_flag() {
+        let config = AppConfig::new();
+        let status = config.feature_status("new_thing", &device("13"), &DeviceFeatureDetector::new());
+        assert_eq!(status, FeatureStatus::DisabledByFlag);
+    }
+
+    #[test]
+    fn a_flag_turned_off_is_disabled_by_flag_even_with_a_capable_device() {
+        let mut config = AppConfig::new();
+        config.set_flag("new_thing", FeatureFlag { enabled: false, ..Default::default() });
+        let status = config.feature_status("new_thing", &device("13"), &DeviceFeatureDetector::new());
+        assert_eq!(status, FeatureStatus::DisabledByFlag);
+    }
+
+    #[test]
+    fn a_device_missing_a_required_capability_is_unsupported() {
+        let mut config = AppConfig::new();
+        config.set_flag(
+            "new_thing",
+            FeatureFlag { enabled: true, requires_device_feature: Some("high_end_device"), ..Default::default() },
+        );
+        let status = config.feature_status("new_thing", &device("8"), &DeviceFeatureDetector::new());
+        assert_eq!(status, FeatureStatus::UnsupportedByDevice);
+    }
+
+    #[test]
+    fn a_device_below_the_minimum_os_version_is_too_old() {
+        let mut config = AppConfig::new();
+        config.set_flag("new_thing", FeatureFlag { enabled: true, min_os_version: Some("12".to_string()), ..Default::default() });
+        let status = config.feature_status("new_thing", &device("10"), &DeviceFeatureDetector::new());
+        assert_eq!(status, FeatureStatus::DeviceTooOld);
+    }
+
+    #[test]
+    fn a_capable_up_to_date_device_has_the_feature_enabled() {
+        let mut config = AppConfig::new();
+        config.set_flag(
+            "new_thing",
+            FeatureFlag {
+                enabled: true,
+                requires_device_feature: Some("high_end_device"),
+                min_os_version: Some("12".to_string()),
+            },
+        );
+        let status = config.feature_status("new_thing", &device("13"), &DeviceFeatureDetector::new());
+        assert_eq!(status, FeatureStatus::Enabled);
+        assert!(config.is_feature_enabled_for_device("new_thing", &device("13"), &DeviceFeatureDetector::new()));
+    }
+}
@@ -0,0 +1,238 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Common Android vendor UA model formats, tried in order. Compiled once
+/// via [`ANDROID_MODEL_PATTERNS`] rather than per call.
+const ANDROID_MODEL_PATTERN_SOURCES: [&str; 5] = [
+    r"Android [\d.]+; ([^;)]+) Build",
+    r"Android [\d.]+; ([^;)]+)\)",
+    r"; ([A-Za-z0-9\-_]+) Build/",
+    r"\(Linux; Android [\d.]+; ([^;)]+)\)",
+    r"Mobile; ([^;)]+)\)",
+];
+
+static ANDROID_OS_VERSION_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"Android (\d+(?:\.\d+)?)").expect("valid regex"));
+
+static ANDROID_MODEL_PATTERNS: Lazy<Vec<Regex>> = Lazy::new(|| {
+    ANDROID_MODEL_PATTERN_SOURCES
+        .iter()
+        .map(|pattern| Regex::new(pattern).expect("valid regex"))
+        .collect()
+});
+
+static IOS_VERSION_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"OS (\d+_\d+)").expect("valid regex"));
+
+/// Matches an iPhone hardware identifier, e.g. `iPhone14,5`, as sent by
+/// native app UAs that include `CFNetwork`/device-model info. Safari's own
+/// UA never includes this, so it's only ever present for first-party apps
+/// that set their own `User-Agent`.
+static IOS_HARDWARE_ID_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"iPhone(\d+),\d+").expect("valid regex"));
+
+/// Coarse platform family a [`DeviceInfo`] was detected for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Platform {
+    Ios,
+    Android,
+    Other,
+}
+
+/// What's known about the device an agent is running on, typically
+/// populated by parsing a `User-Agent` header via [`UserAgentParser`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceInfo {
+    pub platform: Platform,
+    pub os_version: String,
+    pub model: String,
+    /// Marketing generation of an iPhone (e.g. `13` for the iPhone 13
+    /// line), when it could be determined from the UA's hardware
+    /// identifier. `None` for Android/other platforms, and for iOS UAs
+    /// (like Safari's) that don't expose a hardware identifier at all —
+    /// left unset rather than guessed.
+    pub model_generation: Option<u32>,
+}
+
+/// Parses a raw HTTP `User-Agent` header into a [`DeviceInfo`].
+pub struct UserAgentParser;
+
+impl UserAgentParser {
+    pub fn parse(user_agent: &str) -> DeviceInfo {
+        if user_agent.contains("Android") {
+            DeviceInfo {
+                platform: Platform::Android,
+                os_version: extract_android_os_version(user_agent).unwrap_or_default(),
+                model: extract_android_model(user_agent).unwrap_or_else(|| "unknown".to_string()),
+                model_generation: None,
+            }
+        } else if user_agent.contains("iPhone") || user_agent.contains("iPad") {
+            DeviceInfo {
+                platform: Platform::Ios,
+                os_version: extract_ios_version(user_agent).unwrap_or_default(),
+                model: "iPhone".to_string(),
+                model_generation: extract_iphone_generation(user_agent),
+            }
+        } else {
+            DeviceInfo {
+                platform: Platform::Other,
+                os_version: String::new(),
+                model: "unknown".to_string(),
+                model_generation: None,
+            }
+        }
+    }
+}
+
+/// Pull the Android OS version out of a UA string like
+/// `Mozilla/5.0 (Linux; Android 13; Pixel 7)`.
+fn extract_android_os_version(user_agent: &str) -> Option<String> {
+    ANDROID_OS_VERSION_RE.captures(user_agent).map(|captures| captures[1].to_string())
+}
+
+/// Pull the device model out of a UA string, trying a handful of common
+/// Android vendor formats in order and returning the first match.
+fn extract_android_model(user_agent: &str) -> Option<String> {
+    ANDROID_MODEL_PATTERNS
+        .iter()
+        .find_map(|re| re.captures(user_agent))
+        .map(|captures| captures[1].trim().to_string())
+}
+
+/// Pull the iOS version out of a UA string like
+/// `Mozilla/5.0 (iPhone; CPU iPhone OS 17_1 like Mac OS X)`.
+fn extract_ios_version(user_agent: &str) -> Option<String> {
+    IOS_VERSION_RE.captures(user_agent).map(|captures| captures[1].replace('_', "."))
+}
+
+/// Map an iPhone hardware identifier's major number (e.g. `14` from
+/// `iPhone14,5`) to its marketing generation (e.g. `13` for the iPhone 13
+/// line) — hardware majors run one ahead of the generation they shipped
+/// with — or `None` if the UA has no hardware identifier at all.
+fn extract_iphone_generation(user_agent: &str) -> Option<u32> {
+    let captures = IOS_HARDWARE_ID_RE.captures(user_agent)?;
+    let major: u32 = captures[1].parse().ok()?;
+    major.checked_sub(1)
+}
+
+/// Evaluates named feature-support checks against a [`DeviceInfo`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DeviceFeatureDetector;
+
+impl DeviceFeatureDetector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn supports_feature(&self, info: &DeviceInfo, feature: &str) -> bool {
+        match feature {
+            "high_end_device" => self.is_high_end(info),
+            _ => false,
+        }
+    }
+
+    fn is_high_end(&self, info: &DeviceInfo) -> bool {
+        match info.platform {
+            Platform::Ios => info.model_generation.is_some_and(|generation| generation >= 13),
+            Platform::Android => info
+                .os_version
+                .split('.')
+                .next()
+                .and_then(|major| major.parse::<u32>().ok())
+                .is_some_and(|major| major >= 12),
+            Platform::Other => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_an_android_user_agent_into_platform_and_os_version() {
+        let info = UserAgentParser::parse("Mozilla/5.0 (Linux; Android 13; Pixel 7 Build/TQ3A.230901.001)");
+        assert_eq!(info.platform, Platform::Android);
+        assert_eq!(info.os_version, "13");
+    }
+
+    #[test]
+    fn parses_an_iphone_user_agent_into_the_ios_platform() {
+        let info = UserAgentParser::parse("Mozilla/5.0 (iPhone; CPU iPhone OS 17_1 like Mac OS X)");
+        assert_eq!(info.platform, Platform::Ios);
+        assert_eq!(info.os_version, "17.1");
+    }
+
+    /// Guards the move to cached, `Lazy`-compiled regexes: parsing a large
+    /// batch should still produce the exact same results as compiling the
+    /// patterns fresh would, just without paying for it on every call.
+    #[test]
+    fn parsing_ten_thousand_user_agents_yields_unchanged_results() {
+        let user_agents = [
+            "Mozilla/5.0 (Linux; Android 13; Pixel 7 Build/TQ3A.230901.001)",
+            "Mozilla/5.0 (iPhone; CPU iPhone OS 17_1 like Mac OS X)",
+            "Mozilla/5.0 (Linux; Android 8.1.0; SM-G950F Build/M1AJQ)",
+            "Mozilla/5.0 (compatible; some-bot/1.0)",
+        ];
+        let expected: Vec<DeviceInfo> = user_agents.iter().map(|ua| UserAgentParser::parse(ua)).collect();
+
+        for _ in 0..10_000 {
+            for (ua, expected) in user_agents.iter().zip(&expected) {
+                assert_eq!(&UserAgentParser::parse(ua), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn a_recent_android_major_version_is_treated_as_high_end() {
+        let info = DeviceInfo {
+            platform: Platform::Android,
+            os_version: "13".to_string(),
+            model: "Pixel 7".to_string(),
+            model_generation: None,
+        };
+        assert!(DeviceFeatureDetector::new().supports_feature(&info, "high_end_device"));
+    }
+
+    #[test]
+    fn an_old_android_major_version_is_not_high_end() {
+        let info = DeviceInfo {
+            platform: Platform::Android,
+            os_version: "8".to_string(),
+            model: "Pixel".to_string(),
+            model_generation: None,
+        };
+        assert!(!DeviceFeatureDetector::new().supports_feature(&info, "high_end_device"));
+    }
+
+    #[test]
+    fn an_iphone_ua_with_a_hardware_identifier_resolves_its_generation() {
+        let info = UserAgentParser::parse("MyApp/2.0 (iPhone14,5; iOS 17.1) CFNetwork/1406.0.4 Darwin/22.4.0");
+        assert_eq!(info.model_generation, Some(13));
+    }
+
+    #[test]
+    fn a_plain_safari_iphone_ua_has_no_resolvable_generation() {
+        let info = UserAgentParser::parse("Mozilla/5.0 (iPhone; CPU iPhone OS 17_1 like Mac OS X)");
+        assert_eq!(info.model_generation, None);
+    }
+
+    #[test]
+    fn an_iphone_with_a_recent_generation_is_high_end() {
+        let info = DeviceInfo {
+            platform: Platform::Ios,
+            os_version: "17.1".to_string(),
+            model: "iPhone".to_string(),
+            model_generation: Some(14),
+        };
+        assert!(DeviceFeatureDetector::new().supports_feature(&info, "high_end_device"));
+    }
+
+    #[test]
+    fn an_iphone_with_no_known_generation_is_not_high_end() {
+        let info = DeviceInfo {
+            platform: Platform::Ios,
+            os_version: "17.1".to_string(),
+            model: "iPhone".to_string(),
+            model_generation: None,
+        };
+        assert!(!DeviceFeatureDetector::new().supports_feature(&info, "high_end_device"));
+    }
+}
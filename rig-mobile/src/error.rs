@@ -0,0 +1,11 @@
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("mobile transport error: {0}")]
+    Transport(#[from] rig_core::Error),
+
+    #[error("failed to decode message: {0}")]
+    Decode(String),
+
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
@@ -0,0 +1,20 @@
+//! Mobile SDK support for MEAP agents: a lightweight message envelope and
+//! client-side agent built on top of [`rig_core::Connection`], plus (in
+//! later modules) device-capability detection and feature gating for
+//! constrained mobile environments.
+
+mod agent;
+mod config;
+mod device;
+mod error;
+mod message;
+mod model_bridge;
+mod network;
+
+pub use agent::MobileAgent;
+pub use config::{AppConfig, FeatureFlag, FeatureStatus};
+pub use device::{DeviceFeatureDetector, DeviceInfo, Platform, UserAgentParser};
+pub use error::Error;
+pub use message::{AgentMessage, MessageType};
+pub use model_bridge::{capability_for_device, select_model_for_device};
+pub use network::{ConnectionType, NetworkManager, NetworkStatus, QueuePolicy};
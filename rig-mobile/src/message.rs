@@ -0,0 +1,91 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Kind of a mobile [`AgentMessage`]. Distinct from
+/// `rig_core::protocol::MessageType`: this models the simplified
+/// data/command/ack shapes a mobile SDK needs over its own wire format,
+/// not the full agent protocol envelope.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageType {
+    Data,
+    Command,
+    Ack,
+}
+
+/// A single message exchanged with a mobile agent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentMessage {
+    pub id: Uuid,
+    pub message_type: MessageType,
+    pub content: serde_json::Value,
+    pub priority: u8,
+    pub encrypted: bool,
+    /// Whether `content` is zlib-compressed (and base64-encoded, since
+    /// JSON has no binary type) rather than plain JSON. Checked
+    /// explicitly instead of sniffing `content` for a compression magic
+    /// prefix, which misfires on ordinary text.
+    pub compressed: bool,
+    pub requires_ack: bool,
+    pub expires_at: Option<u64>,
+}
+
+impl AgentMessage {
+    pub fn new(message_type: MessageType, content: serde_json::Value) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            message_type,
+            content,
+            priority: 3,
+            encrypted: false,
+            compressed: false,
+            requires_ack: false,
+            expires_at: None,
+        }
+    }
+
+    pub fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Mark this message as expiring `ttl` from now.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.expires_at = Some(now_unix() + ttl.as_secs());
+        self
+    }
+
+    pub fn requiring_ack(mut self) -> Self {
+        self.requires_ack = true;
+        self
+    }
+
+    /// Whether `expires_at` has passed. A message with no expiry never
+    /// expires.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at.is_some_and(|expires_at| now_unix() >= expires_at)
+    }
+}
+
+fn now_unix() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_message_with_a_zero_ttl_is_immediately_expired() {
+        let message = AgentMessage::new(MessageType::Data, serde_json::json!({})).with_ttl(Duration::from_secs(0));
+        assert!(message.is_expired());
+    }
+
+    #[test]
+    fn a_message_with_no_ttl_never_expires() {
+        let message = AgentMessage::new(MessageType::Data, serde_json::json!({}));
+        assert!(!message.is_expired());
+    }
+}
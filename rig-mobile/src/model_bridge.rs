@@ -0,0 +1,100 @@
+use rig_deepseek::{Capability, DeepseekError, ModelSelector};
+
+use crate::device::{DeviceFeatureDetector, DeviceInfo};
+use crate::network::NetworkStatus;
+
+/// Pick the Deepseek capability tier appropriate for `device` and
+/// `network`: a metered connection always biases toward the cheapest
+/// tier regardless of hardware, and otherwise only a device
+/// `detector` considers high-end is allowed the most capable tier.
+pub fn capability_for_device(device: &DeviceInfo, network: &NetworkStatus, detector: &DeviceFeatureDetector) -> Capability {
+    if network.metered() {
+        return Capability::Basic;
+    }
+
+    if detector.supports_feature(device, "high_end_device") {
+        Capability::Advanced
+    } else {
+        Capability::Standard
+    }
+}
+
+/// Select a model from `selector` fitting `device`'s and `network`'s
+/// constraints, within `remaining_budget` for `context_size` tokens.
+pub fn select_model_for_device(
+    selector: &ModelSelector,
+    device: &DeviceInfo,
+    network: &NetworkStatus,
+    detector: &DeviceFeatureDetector,
+    context_size: usize,
+    remaining_budget: f64,
+) -> Result<String, DeepseekError> {
+    let capability = capability_for_device(device, network, detector);
+    selector.select_within_budget(capability, context_size, remaining_budget)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::Platform;
+    use crate::network::ConnectionType;
+    use rig_deepseek::{ModelCapability, ModelSpec};
+
+    fn catalog() -> ModelSelector {
+        ModelSelector::new(vec![
+            ModelSpec {
+                name: "deepseek-lite".to_string(),
+                capability: Capability::Basic,
+                cost_per_1k_tokens: 0.001,
+                supported_capabilities: vec![ModelCapability::Chat],
+                context_window: 4096,
+            },
+            ModelSpec {
+                name: "deepseek-chat".to_string(),
+                capability: Capability::Standard,
+                cost_per_1k_tokens: 0.01,
+                supported_capabilities: vec![ModelCapability::Chat, ModelCapability::FunctionCalling],
+                context_window: 32768,
+            },
+            ModelSpec {
+                name: "deepseek-reasoner".to_string(),
+                capability: Capability::Advanced,
+                cost_per_1k_tokens: 0.5,
+                supported_capabilities: vec![ModelCapability::Chat, ModelCapability::FunctionCalling],
+                context_window: 65536,
+            },
+        ])
+    }
+
+    fn wifi() -> NetworkStatus {
+        NetworkStatus { connection_type: ConnectionType::Wifi, is_charging: true, battery_percent: 90 }
+    }
+
+    fn cellular() -> NetworkStatus {
+        NetworkStatus { connection_type: ConnectionType::Cellular, is_charging: false, battery_percent: 90 }
+    }
+
+    #[test]
+    fn a_low_end_android_device_yields_the_smaller_model_id() {
+        let device = DeviceInfo { platform: Platform::Android, os_version: "8".to_string(), model: "Budget Phone".to_string(), model_generation: None };
+        let model =
+            select_model_for_device(&catalog(), &device, &wifi(), &DeviceFeatureDetector::new(), 10_000, 1.0).unwrap();
+        assert_eq!(model, "deepseek-chat");
+    }
+
+    #[test]
+    fn a_metered_connection_forces_the_cheapest_model_even_on_high_end_hardware() {
+        let device = DeviceInfo { platform: Platform::Android, os_version: "14".to_string(), model: "Pixel 8".to_string(), model_generation: None };
+        let model =
+            select_model_for_device(&catalog(), &device, &cellular(), &DeviceFeatureDetector::new(), 10_000, 1.0).unwrap();
+        assert_eq!(model, "deepseek-lite");
+    }
+
+    #[test]
+    fn a_high_end_device_on_wifi_can_use_the_most_capable_model() {
+        let device = DeviceInfo { platform: Platform::Android, os_version: "14".to_string(), model: "Pixel 8".to_string(), model_generation: None };
+        let model =
+            select_model_for_device(&catalog(), &device, &wifi(), &DeviceFeatureDetector::new(), 10_000, 1.0).unwrap();
+        assert_eq!(model, "deepseek-reasoner");
+    }
+}
@@ -0,0 +1,141 @@
+use std::sync::Arc;
+
+use tokio::sync::RwLock;
+
+use crate::message::AgentMessage;
+
+/// Battery percentage below which [`NetworkStatus::low_battery`] considers
+/// the device low, unless it's charging.
+const LOW_BATTERY_THRESHOLD: u8 = 20;
+
+/// Priority at/above which [`QueuePolicy::should_defer`] always sends
+/// immediately, bypassing deferral regardless of network/battery state.
+const DEFAULT_URGENT_PRIORITY_THRESHOLD: u8 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionType {
+    Wifi,
+    Cellular,
+}
+
+/// Snapshot of on-device conditions relevant to deciding whether it's a
+/// good time to send non-urgent traffic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetworkStatus {
+    pub connection_type: ConnectionType,
+    pub is_charging: bool,
+    pub battery_percent: u8,
+}
+
+impl NetworkStatus {
+    pub fn metered(&self) -> bool {
+        self.connection_type == ConnectionType::Cellular
+    }
+
+    pub fn low_battery(&self) -> bool {
+        !self.is_charging && self.battery_percent < LOW_BATTERY_THRESHOLD
+    }
+}
+
+/// Tracks the device's current network/battery status. Updated from
+/// outside (typically a platform-specific listener bridged in by the
+/// application) via [`Self::update`].
+#[derive(Clone)]
+pub struct NetworkManager {
+    status: Arc<RwLock<NetworkStatus>>,
+}
+
+impl NetworkManager {
+    pub fn new(status: NetworkStatus) -> Self {
+        Self { status: Arc::new(RwLock::new(status)) }
+    }
+
+    pub async fn update(&self, status: NetworkStatus) {
+        *self.status.write().await = status;
+    }
+
+    pub async fn status(&self) -> NetworkStatus {
+        *self.status.read().await
+    }
+
+    /// Whether conditions currently favor deferring non-urgent traffic: a
+    /// metered connection, or a low, non-charging battery.
+    pub async fn should_defer_non_urgent(&self) -> bool {
+        let status = self.status().await;
+        status.metered() || status.low_battery()
+    }
+}
+
+/// Decides whether an [`AgentMessage`] should be held rather than sent
+/// immediately, based on its priority and current [`NetworkManager`]
+/// conditions.
+#[derive(Debug, Clone, Copy)]
+pub struct QueuePolicy {
+    pub urgent_priority_threshold: u8,
+}
+
+impl Default for QueuePolicy {
+    fn default() -> Self {
+        Self { urgent_priority_threshold: DEFAULT_URGENT_PRIORITY_THRESHOLD }
+    }
+}
+
+impl QueuePolicy {
+    pub fn new(urgent_priority_threshold: u8) -> Self {
+        Self { urgent_priority_threshold }
+    }
+
+    /// Whether `message` should be deferred given `network`'s current
+    /// conditions. Messages at or above [`Self::urgent_priority_threshold`]
+    /// are never deferred.
+    pub async fn should_defer(&self, message: &AgentMessage, network: &NetworkManager) -> bool {
+        message.priority < self.urgent_priority_threshold && network.should_defer_non_urgent().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cellular() -> NetworkStatus {
+        NetworkStatus { connection_type: ConnectionType::Cellular, is_charging: false, battery_percent: 80 }
+    }
+
+    fn wifi() -> NetworkStatus {
+        NetworkStatus { connection_type: ConnectionType::Wifi, is_charging: true, battery_percent: 80 }
+    }
+
+    #[tokio::test]
+    async fn a_low_priority_message_is_deferred_on_a_metered_connection() {
+        let network = NetworkManager::new(cellular());
+        let policy = QueuePolicy::default();
+        let message = crate::message::AgentMessage::new(crate::message::MessageType::Data, serde_json::json!({}));
+        assert!(policy.should_defer(&message, &network).await);
+    }
+
+    #[tokio::test]
+    async fn a_high_priority_message_is_never_deferred() {
+        let network = NetworkManager::new(cellular());
+        let policy = QueuePolicy::default();
+        let message = crate::message::AgentMessage::new(crate::message::MessageType::Data, serde_json::json!({}))
+            .with_priority(5);
+        assert!(!policy.should_defer(&message, &network).await);
+    }
+
+    #[tokio::test]
+    async fn a_low_priority_message_is_not_deferred_on_wifi_while_charging() {
+        let network = NetworkManager::new(wifi());
+        let policy = QueuePolicy::default();
+        let message = crate::message::AgentMessage::new(crate::message::MessageType::Data, serde_json::json!({}));
+        assert!(!policy.should_defer(&message, &network).await);
+    }
+
+    #[tokio::test]
+    async fn a_low_battery_defers_even_on_wifi() {
+        let network =
+            NetworkManager::new(NetworkStatus { connection_type: ConnectionType::Wifi, is_charging: false, battery_percent: 5 });
+        let policy = QueuePolicy::default();
+        let message = crate::message::AgentMessage::new(crate::message::MessageType::Data, serde_json::json!({}));
+        assert!(policy.should_defer(&message, &network).await);
+    }
+}
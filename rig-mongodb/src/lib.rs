@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use mongodb::bson::doc;
+use mongodb::options::IndexOptions;
+use mongodb::{Client, IndexModel};
+use rig_core::protocol::stream::StreamReceiver;
+use rig_core::Protocol;
+
+/// How long a seen idempotency key is remembered before MongoDB's TTL
+/// index expires it, after which the key is eligible to be reprocessed if
+/// redelivered again.
+const IDEMPOTENCY_KEY_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, thiserror::Error)]
+pub enum MongoStoreError {
+    #[error("failed to connect to MongoDB: {0}")]
+    Connect(#[from] mongodb::error::Error),
+}
+
+impl rig_core::BackendError for MongoStoreError {
+    fn code(&self) -> rig_core::ErrorCode {
+        rig_core::ErrorCode::BackendUnavailable
+    }
+
+    fn is_retryable(&self) -> bool {
+        true
+    }
+}
+
+/// MEAP backend that persists agent messages into MongoDB.
+pub struct MongoStore {
+    client: Client,
+    database: String,
+}
+
+impl MongoStore {
+    pub async fn connect(uri: &str, database: impl Into<String>) -> Result<Self, MongoStoreError> {
+        let client = Client::with_uri_str(uri).await?;
+        let store = Self { client, database: database.into() };
+        store.ensure_idempotency_index().await?;
+        Ok(store)
+    }
+
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    pub fn database(&self) -> &str {
+        &self.database
+    }
+
+    /// A TTL index on `seen_at` lets MongoDB expire old idempotency keys
+    /// for us, rather than us running a periodic purge.
+    async fn ensure_idempotency_index(&self) -> Result<(), MongoStoreError> {
+        let collection = self.client.database(&self.database).collection::<mongodb::bson::Document>("idempotency_keys");
+        let index = IndexModel::builder()
+            .keys(doc! { "seen_at": 1 })
+            .options(IndexOptions::builder().expire_after(Some(IDEMPOTENCY_KEY_TTL)).build())
+            .build();
+        collection.create_index(index, None).await?;
+        Ok(())
+    }
+
+    /// Record `key` as seen and return whether it had already been seen
+    /// (within the TTL window) — the caller should skip processing if so.
+    /// Relies on `_id` uniqueness rather than a read-then-write, so two
+    /// concurrent deliveries of the same key can't both win.
+    async fn record_idempotency_key(&self, key: &str) -> Result<bool, mongodb::error::Error> {
+        let collection = self.client.database(&self.database).collection::<mongodb::bson::Document>("idempotency_keys");
+        let result = collection.insert_one(doc! { "_id": key, "seen_at": mongodb::bson::DateTime::now() }, None).await;
+        match result {
+            Ok(_) => Ok(false),
+            Err(err) if is_duplicate_key_error(&err) => Ok(true),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Whether `err` is MongoDB's duplicate-key error (code 11000), i.e. the
+/// `_id` we tried to insert — here, an idempotency key — already exists.
+fn is_duplicate_key_error(err: &mongodb::error::Error) -> bool {
+    matches!(
+        err.kind.as_ref(),
+        mongodb::error::ErrorKind::Write(mongodb::error::WriteFailure::WriteError(write_error))
+            if write_error.code == 11000
+    )
+}
+
+#[async_trait]
+impl Protocol for MongoStore {
+    async fn handle_stream(&self, mut receiver: StreamReceiver) {
+        while let Some(message) = receiver.recv().await {
+            if let Some(key) = &message.idempotency_key {
+                match self.record_idempotency_key(key).await {
+                    Ok(true) => {
+                        tracing::debug!(id = %message.id, %key, "skipping duplicate message (idempotency key already seen)");
+                        continue;
+                    }
+                    Ok(false) => {}
+                    Err(err) => {
+                        tracing::warn!(id = %message.id, %err, "failed to check idempotency key");
+                        continue;
+                    }
+                }
+            }
+
+            let collection = self.client.database(&self.database).collection::<mongodb::bson::Document>("messages");
+            let document = doc! { "_id": message.id.to_string(), "payload": message.payload.to_string() };
+            if let Err(err) = collection.insert_one(document, None).await {
+                tracing::warn!(id = %message.id, %err, "failed to persist message to mongodb");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rig_core::protocol::{self, MessageType, ProtocolMessage};
+
+    use super::*;
+
+    /// Requires a local MongoDB instance; run with `cargo test -- --ignored`.
+    #[tokio::test]
+    #[ignore]
+    async fn a_message_redelivered_with_the_same_idempotency_key_is_only_saved_once() {
+        let store = Arc::new(MongoStore::connect("mongodb://localhost:27017", "rig-mongodb-tests").await.unwrap());
+        let (tx, rx) = protocol::stream::channel(4);
+
+        let handle = {
+            let store = store.clone();
+            tokio::spawn(async move { store.handle_stream(rx).await })
+        };
+
+        let first = ProtocolMessage::new(MessageType::Data, serde_json::json!({"n": 1})).with_idempotency_key("retry-1");
+        let second = ProtocolMessage::new(MessageType::Data, serde_json::json!({"n": 1})).with_idempotency_key("retry-1");
+        tx.send(first).await.unwrap();
+        tx.send(second).await.unwrap();
+        drop(tx);
+        handle.await.unwrap();
+
+        let collection = store.client().database(store.database()).collection::<mongodb::bson::Document>("messages");
+        let count = collection.count_documents(doc! {}, None).await.unwrap();
+        assert_eq!(count, 1);
+    }
+}
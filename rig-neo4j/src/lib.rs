@@ -0,0 +1,35 @@
+use async_trait::async_trait;
+use neo4rs::Graph;
+use rig_core::protocol::stream::StreamReceiver;
+use rig_core::Protocol;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Neo4jStoreError {
+    #[error("failed to connect to Neo4j: {0}")]
+    Connect(#[from] neo4rs::Error),
+}
+
+/// MEAP backend that persists agent messages as a graph in Neo4j.
+pub struct Neo4jStore {
+    graph: Graph,
+}
+
+impl Neo4jStore {
+    pub async fn connect(uri: &str, user: &str, password: &str) -> Result<Self, Neo4jStoreError> {
+        let graph = Graph::new(uri, user, password).await?;
+        Ok(Self { graph })
+    }
+
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+}
+
+#[async_trait]
+impl Protocol for Neo4jStore {
+    async fn handle_stream(&self, mut receiver: StreamReceiver) {
+        while let Some(message) = receiver.recv().await {
+            tracing::debug!(id = %message.id, "received message for neo4j store");
+        }
+    }
+}
@@ -0,0 +1,303 @@
+use async_trait::async_trait;
+use qdrant_client::qdrant::{
+    Condition, DeletePointsBuilder, Filter, PointId, PointStruct, PointsIdsList, SearchPointsBuilder,
+    UpsertPointsBuilder,
+};
+use qdrant_client::{Payload, Qdrant};
+use rig_core::protocol::stream::StreamReceiver;
+use rig_core::Protocol;
+use rig_vectorstore::{SearchResult, VectorStore};
+
+#[derive(Debug, thiserror::Error)]
+pub enum QdrantStoreError {
+    #[error("failed to connect to Qdrant: {0}")]
+    Connect(qdrant_client::QdrantError),
+    #[error("qdrant operation failed: {0}")]
+    Operation(#[from] qdrant_client::QdrantError),
+    #[error("invalid payload: {0}")]
+    Payload(String),
+    #[error("unknown protocol action: {0}")]
+    UnknownAction(String),
+    #[error("failed to parse request payload: {0}")]
+    Request(#[from] serde_json::Error),
+}
+
+impl rig_core::BackendError for QdrantStoreError {
+    /// `Connect`/`Operation` mean Qdrant itself is unreachable or
+    /// rejected the call; the rest are caller mistakes retrying won't fix.
+    fn code(&self) -> rig_core::ErrorCode {
+        match self {
+            QdrantStoreError::Connect(_) | QdrantStoreError::Operation(_) => rig_core::ErrorCode::BackendUnavailable,
+            QdrantStoreError::Payload(_) | QdrantStoreError::UnknownAction(_) | QdrantStoreError::Request(_) => {
+                rig_core::ErrorCode::Internal
+            }
+        }
+    }
+
+    fn is_retryable(&self) -> bool {
+        matches!(self, QdrantStoreError::Connect(_) | QdrantStoreError::Operation(_))
+    }
+}
+
+/// MEAP backend that stores agent-produced embeddings in a single Qdrant
+/// collection.
+pub struct QdrantStore {
+    client: Qdrant,
+    collection: String,
+}
+
+impl QdrantStore {
+    pub fn connect(url: &str, collection: impl Into<String>) -> Result<Self, QdrantStoreError> {
+        let client = Qdrant::from_url(url).build().map_err(QdrantStoreError::Connect)?;
+        Ok(Self { client, collection: collection.into() })
+    }
+
+    pub fn client(&self) -> &Qdrant {
+        &self.client
+    }
+
+    pub async fn upsert_point(&self, id: String, vector: Vec<f32>, payload: serde_json::Value) -> Result<(), QdrantStoreError> {
+        let payload: Payload = payload.try_into().map_err(|err| QdrantStoreError::Payload(format!("{err:?}")))?;
+        let point = PointStruct::new(id, vector, payload);
+        self.client.upsert_points(UpsertPointsBuilder::new(&self.collection, vec![point])).await?;
+        Ok(())
+    }
+
+    pub async fn search_points(
+        &self,
+        query: Vec<f32>,
+        limit: usize,
+        filter: Option<serde_json::Value>,
+    ) -> Result<Vec<SearchResult>, QdrantStoreError> {
+        let mut builder = SearchPointsBuilder::new(&self.collection, query, limit as u64).with_payload(true);
+        if let Some(filter) = exact_match_filter(&filter) {
+            builder = builder.filter(filter);
+        }
+        let response = self.client.search_points(builder).await?;
+        Ok(response
+            .result
+            .into_iter()
+            .map(|scored| SearchResult {
+                id: point_id_to_string(scored.id),
+                score: scored.score,
+                payload: payload_to_json(scored.payload),
+            })
+            .collect())
+    }
+
+    pub async fn delete_point(&self, id: String) -> Result<(), QdrantStoreError> {
+        let selector = PointsIdsList { ids: vec![PointId::from(id)] };
+        self.client.delete_points(DeletePointsBuilder::new(&self.collection).points(selector)).await?;
+        Ok(())
+    }
+
+    /// Combine vector similarity with keyword matching so exact keyword
+    /// hits aren't lost to a purely geometric ranking.
+    ///
+    /// Oversamples `limit * 4` nearest neighbours by vector score alone,
+    /// then re-ranks them by `blended = alpha * vector_score + (1 -
+    /// alpha) * keyword_score`, where `vector_score` is Qdrant's cosine
+    /// score and `keyword_score` is the fraction of `keywords` that
+    /// appear (case-insensitively) anywhere in the point's payload. The
+    /// oversample keeps a close-but-keyword-missing point from crowding
+    /// out a farther keyword match before re-ranking gets a chance to
+    /// promote it. `alpha` of `1.0` is pure vector search, `0.0` is pure
+    /// keyword search.
+    pub async fn hybrid_search(
+        &self,
+        query_vector: Vec<f32>,
+        keywords: &[String],
+        limit: usize,
+        alpha: f32,
+    ) -> Result<Vec<SearchResult>, QdrantStoreError> {
+        let candidates = self.search_points(query_vector, (limit * 4).max(limit), None).await?;
+        Ok(rerank_hybrid(candidates, keywords, alpha, limit))
+    }
+
+    /// Dispatch a protocol action by name, matching the string-keyed
+    /// actions MEAP agents use for Qdrant-backed tools.
+    pub async fn handle_action(&self, action: &str, payload: serde_json::Value) -> Result<serde_json::Value, QdrantStoreError> {
+        match action {
+            "hybrid_search" => {
+                let query_vector: Vec<f32> = serde_json::from_value(payload["query_vector"].clone())?;
+                let keywords: Vec<String> = serde_json::from_value(payload["keywords"].clone())?;
+                let limit = payload["limit"].as_u64().unwrap_or(10) as usize;
+                let alpha = payload["alpha"].as_f64().map(|a| a as f32).unwrap_or(DEFAULT_HYBRID_ALPHA);
+                let results = self.hybrid_search(query_vector, &keywords, limit, alpha).await?;
+                Ok(serde_json::json!({ "results": results }))
+            }
+            other => Err(QdrantStoreError::UnknownAction(other.to_string())),
+        }
+    }
+}
+
+/// Default blend weight for [`QdrantStore::hybrid_search`] when the
+/// `"hybrid_search"` action omits `alpha`: an even split between vector
+/// and keyword scoring.
+const DEFAULT_HYBRID_ALPHA: f32 = 0.5;
+
+/// Blend each candidate's vector score with its keyword match score and
+/// return the top `limit`, highest-blended-score first. Split out from
+/// [`QdrantStore::hybrid_search`] so the ranking math can be tested
+/// without a live Qdrant instance.
+fn rerank_hybrid(candidates: Vec<SearchResult>, keywords: &[String], alpha: f32, limit: usize) -> Vec<SearchResult> {
+    let mut scored: Vec<(f32, SearchResult)> = candidates
+        .into_iter()
+        .map(|result| {
+            let keyword_score = keyword_match_score(&result.payload, keywords);
+            let blended = alpha * result.score + (1.0 - alpha) * keyword_score;
+            (blended, result)
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+    scored.truncate(limit);
+    scored.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Score in `[0, 1]`: the fraction of `keywords` that appear
+/// (case-insensitively) as a substring of any string value in `payload`.
+fn keyword_match_score(payload: &serde_json::Value, keywords: &[String]) -> f32 {
+    if keywords.is_empty() {
+        return 0.0;
+    }
+    let haystack = payload_text(payload).to_lowercase();
+    let matched = keywords.iter().filter(|keyword| haystack.contains(&keyword.to_lowercase())).count();
+    matched as f32 / keywords.len() as f32
+}
+
+fn payload_text(payload: &serde_json::Value) -> String {
+    match payload {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Object(map) => map.values().map(payload_text).collect::<Vec<_>>().join(" "),
+        serde_json::Value::Array(items) => items.iter().map(payload_text).collect::<Vec<_>>().join(" "),
+        _ => String::new(),
+    }
+}
+
+/// Build a `must`-all-match [`Filter`] from a flat JSON object of exact
+/// payload field values. Anything that isn't a flat object (including
+/// `None`) is treated as "no filter".
+fn exact_match_filter(filter: &Option<serde_json::Value>) -> Option<Filter> {
+    let object = filter.as_ref()?.as_object()?;
+    let conditions: Vec<Condition> = object
+        .iter()
+        .map(|(key, value)| Condition::matches(key, value.as_str().unwrap_or_default().to_string()))
+        .collect();
+    if conditions.is_empty() {
+        None
+    } else {
+        Some(Filter::must(conditions))
+    }
+}
+
+fn point_id_to_string(id: Option<PointId>) -> String {
+    use qdrant_client::qdrant::point_id::PointIdOptions;
+    match id.and_then(|id| id.point_id_options) {
+        Some(PointIdOptions::Num(num)) => num.to_string(),
+        Some(PointIdOptions::Uuid(uuid)) => uuid,
+        None => String::new(),
+    }
+}
+
+fn payload_to_json(payload: std::collections::HashMap<String, qdrant_client::qdrant::Value>) -> serde_json::Value {
+    serde_json::Value::Object(payload.into_iter().map(|(key, value)| (key, qdrant_value_to_json(value))).collect())
+}
+
+fn qdrant_value_to_json(value: qdrant_client::qdrant::Value) -> serde_json::Value {
+    use qdrant_client::qdrant::value::Kind;
+    match value.kind {
+        Some(Kind::StringValue(s)) => serde_json::Value::String(s),
+        Some(Kind::IntegerValue(i)) => serde_json::Value::from(i),
+        Some(Kind::DoubleValue(d)) => serde_json::Value::from(d),
+        Some(Kind::BoolValue(b)) => serde_json::Value::Bool(b),
+        Some(Kind::ListValue(list)) => {
+            serde_json::Value::Array(list.values.into_iter().map(qdrant_value_to_json).collect())
+        }
+        Some(Kind::StructValue(s)) => {
+            serde_json::Value::Object(s.fields.into_iter().map(|(k, v)| (k, qdrant_value_to_json(v))).collect())
+        }
+        Some(Kind::NullValue(_)) | None => serde_json::Value::Null,
+    }
+}
+
+#[async_trait]
+impl VectorStore for QdrantStore {
+    async fn upsert(
+        &self,
+        id: String,
+        vector: Vec<f32>,
+        payload: serde_json::Value,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.upsert_point(id, vector, payload).await.map_err(Into::into)
+    }
+
+    async fn search(
+        &self,
+        query: Vec<f32>,
+        limit: usize,
+        filter: Option<serde_json::Value>,
+    ) -> Result<Vec<SearchResult>, Box<dyn std::error::Error + Send + Sync>> {
+        self.search_points(query, limit, filter).await.map_err(Into::into)
+    }
+
+    async fn delete(&self, id: String) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.delete_point(id).await.map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl Protocol for QdrantStore {
+    async fn handle_stream(&self, mut receiver: StreamReceiver) {
+        while let Some(message) = receiver.recv().await {
+            tracing::debug!(id = %message.id, "received message for qdrant store");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(id: &str, score: f32, payload: serde_json::Value) -> SearchResult {
+        SearchResult { id: id.to_string(), score, payload }
+    }
+
+    #[test]
+    fn a_keyword_only_match_is_surfaced_above_a_closer_but_keyword_missing_vector() {
+        let candidates = vec![
+            result("closer-but-no-keyword", 0.95, serde_json::json!({"text": "unrelated content"})),
+            result("farther-with-keyword", 0.4, serde_json::json!({"text": "mentions qdrant explicitly"})),
+        ];
+        let ranked = rerank_hybrid(candidates, &["qdrant".to_string()], 0.5, 2);
+        assert_eq!(ranked[0].id, "farther-with-keyword");
+    }
+
+    #[test]
+    fn pure_vector_alpha_ignores_keyword_score() {
+        let candidates = vec![
+            result("closer", 0.95, serde_json::json!({"text": "unrelated"})),
+            result("farther", 0.4, serde_json::json!({"text": "mentions qdrant"})),
+        ];
+        let ranked = rerank_hybrid(candidates, &["qdrant".to_string()], 1.0, 2);
+        assert_eq!(ranked[0].id, "closer");
+    }
+
+    #[test]
+    fn keyword_match_score_is_the_fraction_of_keywords_present() {
+        let payload = serde_json::json!({"text": "the quick brown fox"});
+        let keywords = vec!["quick".to_string(), "slow".to_string()];
+        assert_eq!(keyword_match_score(&payload, &keywords), 0.5);
+    }
+
+    /// Requires a local Qdrant instance; run with `cargo test -- --ignored`.
+    #[tokio::test]
+    #[ignore]
+    async fn upserts_and_finds_a_point_through_the_trait_object() {
+        let store: Box<dyn VectorStore> =
+            Box::new(QdrantStore::connect("http://localhost:6334", "rig-qdrant-tests").unwrap());
+        store.upsert("1".to_string(), vec![0.1, 0.2, 0.3], serde_json::json!({"kind": "test"})).await.unwrap();
+        let results = store.search(vec![0.1, 0.2, 0.3], 1, None).await.unwrap();
+        assert_eq!(results[0].id, "1");
+        store.delete("1".to_string()).await.unwrap();
+    }
+}
@@ -0,0 +1,253 @@
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use rig_core::protocol::stream::StreamReceiver;
+use rig_core::Protocol;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::Mutex;
+
+/// How long a seen idempotency key is remembered before it's eligible to
+/// be forgotten (and thus reprocessed if redelivered again after this
+/// long). Keeps the dedup table from growing without bound.
+const IDEMPOTENCY_KEY_TTL_SECS: i64 = 24 * 60 * 60;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SqliteStoreError {
+    #[error("failed to open SQLite database: {0}")]
+    Connect(#[from] rusqlite::Error),
+    #[error("sqlite operation failed: {0}")]
+    Operation(rusqlite::Error),
+    #[error("i/o error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("invalid message JSON: {0}")]
+    Serialization(#[from] serde_json::Error),
+}
+
+/// One row of `messages`, as exchanged by [`SqliteStore::export_messages`]
+/// / [`SqliteStore::import_messages`] — newline-delimited JSON, one
+/// message per line.
+#[derive(Serialize, Deserialize)]
+struct ExportedMessage {
+    id: String,
+    payload: serde_json::Value,
+}
+
+/// MEAP backend that persists agent messages into a local SQLite file.
+/// `rusqlite::Connection` isn't `Sync`, so it's wrapped in a [`Mutex`] to
+/// satisfy [`Protocol`]'s `Send + Sync` bound.
+pub struct SqliteStore {
+    conn: Mutex<rusqlite::Connection>,
+}
+
+impl SqliteStore {
+    pub fn connect(path: impl AsRef<Path>) -> Result<Self, SqliteStoreError> {
+        let conn = rusqlite::Connection::open(path)?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS messages (id TEXT PRIMARY KEY, payload TEXT NOT NULL)",
+            (),
+        )?;
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS idempotency_keys (key TEXT PRIMARY KEY, seen_at_unix INTEGER NOT NULL)",
+            (),
+        )?;
+        Ok(Self { conn: Mutex::new(conn) })
+    }
+
+    /// Record `key` as seen and return whether it had already been seen
+    /// (within the TTL window) — the caller should skip processing if so.
+    /// Expired keys are purged opportunistically on every call rather than
+    /// via a background task.
+    fn record_idempotency_key(conn: &rusqlite::Connection, key: &str) -> Result<bool, rusqlite::Error> {
+        let now = now_unix();
+        conn.execute("DELETE FROM idempotency_keys WHERE seen_at_unix < ?1", [now - IDEMPOTENCY_KEY_TTL_SECS])?;
+
+        let already_seen: bool =
+            conn.query_row("SELECT EXISTS(SELECT 1 FROM idempotency_keys WHERE key = ?1)", [key], |row| row.get(0))?;
+        if !already_seen {
+            conn.execute("INSERT INTO idempotency_keys (key, seen_at_unix) VALUES (?1, ?2)", (key, now))?;
+        }
+        Ok(already_seen)
+    }
+
+    /// Stream every row in `messages` to `writer` as newline-delimited
+    /// JSON, for moving history into another store via
+    /// [`Self::import_messages`]. Returns the number of messages written.
+    pub async fn export_messages(&self, mut writer: impl AsyncWrite + Unpin) -> Result<usize, SqliteStoreError> {
+        let conn = self.conn.lock().await;
+        let mut statement = conn.prepare("SELECT id, payload FROM messages").map_err(SqliteStoreError::Operation)?;
+        let mut rows = statement.query(()).map_err(SqliteStoreError::Operation)?;
+
+        let mut count = 0;
+        while let Some(row) = rows.next().map_err(SqliteStoreError::Operation)? {
+            let id: String = row.get(0).map_err(SqliteStoreError::Operation)?;
+            let payload_text: String = row.get(1).map_err(SqliteStoreError::Operation)?;
+            let payload: serde_json::Value = serde_json::from_str(&payload_text)?;
+            let line = serde_json::to_string(&ExportedMessage { id, payload })?;
+            writer.write_all(line.as_bytes()).await?;
+            writer.write_all(b"\n").await?;
+            count += 1;
+        }
+        writer.flush().await?;
+        Ok(count)
+    }
+
+    /// Ingest newline-delimited JSON produced by [`Self::export_messages`],
+    /// skipping any message whose `id` already exists so re-running an
+    /// import (or importing overlapping exports) doesn't duplicate rows.
+    /// Returns the number of messages actually inserted.
+    pub async fn import_messages(&self, reader: impl AsyncRead + Unpin) -> Result<usize, SqliteStoreError> {
+        let mut lines = BufReader::new(reader).lines();
+        let conn = self.conn.lock().await;
+
+        let mut count = 0;
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let message: ExportedMessage = serde_json::from_str(&line)?;
+            let inserted = conn
+                .execute("INSERT OR IGNORE INTO messages (id, payload) VALUES (?1, ?2)", (message.id, message.payload.to_string()))
+                .map_err(SqliteStoreError::Operation)?;
+            count += inserted;
+        }
+        Ok(count)
+    }
+}
+
+fn now_unix() -> i64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs() as i64
+}
+
+#[async_trait]
+impl Protocol for SqliteStore {
+    async fn handle_stream(&self, mut receiver: StreamReceiver) {
+        while let Some(message) = receiver.recv().await {
+            let conn = self.conn.lock().await;
+
+            if let Some(key) = &message.idempotency_key {
+                match Self::record_idempotency_key(&conn, key) {
+                    Ok(true) => {
+                        tracing::debug!(id = %message.id, %key, "skipping duplicate message (idempotency key already seen)");
+                        continue;
+                    }
+                    Ok(false) => {}
+                    Err(err) => {
+                        tracing::warn!(id = %message.id, %err, "failed to check idempotency key");
+                        continue;
+                    }
+                }
+            }
+
+            if let Err(err) = conn.execute(
+                "INSERT OR REPLACE INTO messages (id, payload) VALUES (?1, ?2)",
+                (message.id.to_string(), message.payload.to_string()),
+            ) {
+                tracing::warn!(id = %message.id, %err, "failed to persist message to sqlite");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rig_core::protocol::{self, MessageType, ProtocolMessage};
+
+    use super::*;
+
+    #[test]
+    fn connect_creates_the_messages_table() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let store = SqliteStore::connect(file.path()).unwrap();
+
+        let conn = store.conn.blocking_lock();
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name='messages'", (), |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[tokio::test]
+    async fn a_message_redelivered_with_the_same_idempotency_key_is_only_saved_once() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let store = Arc::new(SqliteStore::connect(file.path()).unwrap());
+        let (tx, rx) = protocol::stream::channel(4);
+
+        let handle = {
+            let store = store.clone();
+            tokio::spawn(async move { store.handle_stream(rx).await })
+        };
+
+        let first = ProtocolMessage::new(MessageType::Data, serde_json::json!({"n": 1})).with_idempotency_key("retry-1");
+        let second = ProtocolMessage::new(MessageType::Data, serde_json::json!({"n": 1})).with_idempotency_key("retry-1");
+        assert_ne!(first.id, second.id);
+        tx.send(first).await.unwrap();
+        tx.send(second).await.unwrap();
+        drop(tx);
+        handle.await.unwrap();
+
+        let conn = store.conn.lock().await;
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM messages", (), |row| row.get(0)).unwrap();
+        assert_eq!(count, 1);
+    }
+
+    async fn insert_message(store: &SqliteStore, id: &str, payload: serde_json::Value) {
+        let conn = store.conn.lock().await;
+        conn.execute("INSERT INTO messages (id, payload) VALUES (?1, ?2)", (id, payload.to_string())).unwrap();
+    }
+
+    async fn all_messages(store: &SqliteStore) -> Vec<(String, serde_json::Value)> {
+        let conn = store.conn.lock().await;
+        let mut statement = conn.prepare("SELECT id, payload FROM messages ORDER BY id").unwrap();
+        let mut rows = statement.query(()).unwrap();
+        let mut messages = Vec::new();
+        while let Some(row) = rows.next().unwrap() {
+            let id: String = row.get(0).unwrap();
+            let payload: String = row.get(1).unwrap();
+            messages.push((id, serde_json::from_str(&payload).unwrap()));
+        }
+        messages
+    }
+
+    #[tokio::test]
+    async fn exported_messages_round_trip_into_a_fresh_store() {
+        let source_file = tempfile::NamedTempFile::new().unwrap();
+        let source = SqliteStore::connect(source_file.path()).unwrap();
+        insert_message(&source, "a", serde_json::json!({"n": 1})).await;
+        insert_message(&source, "b", serde_json::json!({"n": 2})).await;
+
+        let mut buffer = Vec::new();
+        let exported = source.export_messages(&mut buffer).await.unwrap();
+        assert_eq!(exported, 2);
+
+        let dest_file = tempfile::NamedTempFile::new().unwrap();
+        let dest = SqliteStore::connect(dest_file.path()).unwrap();
+        let imported = dest.import_messages(buffer.as_slice()).await.unwrap();
+        assert_eq!(imported, 2);
+
+        assert_eq!(all_messages(&source).await, all_messages(&dest).await);
+    }
+
+    #[tokio::test]
+    async fn importing_an_already_present_id_is_ignored_rather_than_duplicated() {
+        let source_file = tempfile::NamedTempFile::new().unwrap();
+        let source = SqliteStore::connect(source_file.path()).unwrap();
+        insert_message(&source, "a", serde_json::json!({"n": 1})).await;
+
+        let mut buffer = Vec::new();
+        source.export_messages(&mut buffer).await.unwrap();
+
+        let dest_file = tempfile::NamedTempFile::new().unwrap();
+        let dest = SqliteStore::connect(dest_file.path()).unwrap();
+        insert_message(&dest, "a", serde_json::json!({"n": 1})).await;
+
+        let imported = dest.import_messages(buffer.as_slice()).await.unwrap();
+        assert_eq!(imported, 0);
+        assert_eq!(all_messages(&dest).await.len(), 1);
+    }
+}
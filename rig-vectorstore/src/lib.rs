@@ -0,0 +1,39 @@
+//! A backend-agnostic vector store trait, so application code can swap
+//! between [`rig-qdrant`](https://docs.rs/rig-qdrant) and
+//! [`rig-lancedb`](https://docs.rs/rig-lancedb) (or future backends)
+//! without changing call sites.
+
+use async_trait::async_trait;
+
+/// A single match returned from [`VectorStore::search`].
+#[derive(Debug, Clone, PartialEq, serde::Serialize)]
+pub struct SearchResult {
+    pub id: String,
+    pub score: f32,
+    pub payload: serde_json::Value,
+}
+
+/// Common vector-store operations. Backends keep their own error types
+/// internally but report failures here as a boxed `std::error::Error` so
+/// the trait stays object-safe and callers can hold a
+/// `Box<dyn VectorStore>` without committing to one backend.
+#[async_trait]
+pub trait VectorStore: Send + Sync {
+    async fn upsert(
+        &self,
+        id: String,
+        vector: Vec<f32>,
+        payload: serde_json::Value,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+    /// Find the `limit` nearest matches to `query`. `filter`, when set, is
+    /// a flat JSON object of payload fields that must match exactly.
+    async fn search(
+        &self,
+        query: Vec<f32>,
+        limit: usize,
+        filter: Option<serde_json::Value>,
+    ) -> Result<Vec<SearchResult>, Box<dyn std::error::Error + Send + Sync>>;
+
+    async fn delete(&self, id: String) -> Result<(), Box<dyn std::error::Error + Send + Sync>>;
+}
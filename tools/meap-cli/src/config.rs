@@ -0,0 +1,129 @@
+use std::path::Path;
+
+use serde::Deserialize;
+
+/// Connection defaults loaded from `meap.toml`, overridable by environment
+/// variables, which are in turn overridable by explicit CLI flags. See
+/// [`Self::resolve_server_url`]/[`Self::resolve_security_key`] for the
+/// precedence chain each field goes through.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ConnectionConfig {
+    pub buffer_size: usize,
+    pub reconnect_attempts: u32,
+    pub reconnect_delay_ms: u64,
+    pub server_url: Option<String>,
+    pub security_key: Option<String>,
+}
+
+impl Default for ConnectionConfig {
+    fn default() -> Self {
+        Self {
+            buffer_size: 1024,
+            reconnect_attempts: 3,
+            reconnect_delay_ms: 500,
+            server_url: None,
+            security_key: None,
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("failed to read config file: {0}")]
+    Read(#[from] std::io::Error),
+    #[error("failed to parse config file: {0}")]
+    Parse(#[from] toml::de::Error),
+}
+
+impl ConnectionConfig {
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(toml::from_str(&contents)?)
+    }
+
+    /// Load `path` if given, else fall back to built-in defaults, then
+    /// layer `MEAP_SERVER_URL`/`MEAP_SECURITY_KEY` env vars on top.
+    pub fn load_or_default(path: Option<&Path>) -> Result<Self, ConfigError> {
+        let config = match path {
+            Some(path) => Self::load(path)?,
+            None => Self::default(),
+        };
+        Ok(config.apply_env())
+    }
+
+    fn apply_env(mut self) -> Self {
+        if let Ok(url) = std::env::var("MEAP_SERVER_URL") {
+            self.server_url = Some(url);
+        }
+        if let Ok(key) = std::env::var("MEAP_SECURITY_KEY") {
+            self.security_key = Some(key);
+        }
+        self
+    }
+
+    /// CLI flag > env var > config file > built-in default.
+    pub fn resolve_server_url(&self, cli_flag: Option<String>) -> Option<String> {
+        cli_flag.or_else(|| self.server_url.clone())
+    }
+
+    /// CLI flag > env var > config file > built-in default.
+    pub fn resolve_security_key(&self, cli_flag: Option<String>) -> Option<String> {
+        cli_flag.or_else(|| self.security_key.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_sample_toml_file_parses_into_the_expected_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("meap.toml");
+        std::fs::write(
+            &path,
+            r#"
+            buffer_size = 4096
+            reconnect_attempts = 10
+            reconnect_delay_ms = 250
+            server_url = "127.0.0.1:9000"
+            security_key = "abc123"
+            "#,
+        )
+        .unwrap();
+
+        let config = ConnectionConfig::load(&path).unwrap();
+        assert_eq!(
+            config,
+            ConnectionConfig {
+                buffer_size: 4096,
+                reconnect_attempts: 10,
+                reconnect_delay_ms: 250,
+                server_url: Some("127.0.0.1:9000".to_string()),
+                security_key: Some("abc123".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn missing_fields_fall_back_to_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("meap.toml");
+        std::fs::write(&path, "buffer_size = 2048").unwrap();
+
+        let config = ConnectionConfig::load(&path).unwrap();
+        assert_eq!(config.buffer_size, 2048);
+        assert_eq!(config.reconnect_attempts, ConnectionConfig::default().reconnect_attempts);
+    }
+
+    #[test]
+    fn cli_flag_wins_over_a_configured_server_url() {
+        let config = ConnectionConfig {
+            server_url: Some("configured:9000".to_string()),
+            ..ConnectionConfig::default()
+        };
+        assert_eq!(config.resolve_server_url(Some("cli:9000".to_string())), Some("cli:9000".to_string()));
+        assert_eq!(config.resolve_server_url(None), Some("configured:9000".to_string()));
+    }
+}
@@ -0,0 +1,61 @@
+use dialoguer::{Input, Select};
+use rig_core::Protocol;
+use rig_mongodb::MongoStore;
+use rig_neo4j::Neo4jStore;
+use rig_qdrant::QdrantStore;
+use rig_sqlite::SqliteStore;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SelectionError {
+    #[error("failed reading input: {0}")]
+    Prompt(#[from] std::io::Error),
+    #[error("failed to connect to MongoDB: {0}")]
+    Mongo(#[from] rig_mongodb::MongoStoreError),
+    #[error("failed to connect to Neo4j: {0}")]
+    Neo4j(#[from] rig_neo4j::Neo4jStoreError),
+    #[error("failed to connect to Qdrant: {0}")]
+    Qdrant(#[from] rig_qdrant::QdrantStoreError),
+    #[error("failed to open SQLite database: {0}")]
+    Sqlite(#[from] rig_sqlite::SqliteStoreError),
+}
+
+const BACKENDS: [&str; 4] = ["MongoDB", "Neo4j", "Qdrant", "SQLite"];
+
+/// Prompt the user for a storage backend and the connection details it
+/// needs, then construct and return the matching store as a boxed
+/// [`Protocol`]. Every branch handles a failed prompt or failed connection
+/// as a recoverable [`SelectionError`] rather than panicking.
+pub async fn interactive_protocol_selection() -> Result<Box<dyn Protocol>, SelectionError> {
+    let choice = Select::new()
+        .with_prompt("Select a storage backend")
+        .items(&BACKENDS)
+        .default(0)
+        .interact()?;
+
+    match BACKENDS[choice] {
+        "MongoDB" => {
+            let uri: String = Input::new().with_prompt("MongoDB URI").default("mongodb://localhost:27017".into()).interact_text()?;
+            let database: String = Input::new().with_prompt("Database name").default("meap".into()).interact_text()?;
+            let store = MongoStore::connect(&uri, database).await?;
+            Ok(Box::new(store))
+        }
+        "Neo4j" => {
+            let uri: String = Input::new().with_prompt("Neo4j URI").default("bolt://localhost:7687".into()).interact_text()?;
+            let user: String = Input::new().with_prompt("Username").default("neo4j".into()).interact_text()?;
+            let password: String = Input::new().with_prompt("Password").interact_text()?;
+            let store = Neo4jStore::connect(&uri, &user, &password).await?;
+            Ok(Box::new(store))
+        }
+        "Qdrant" => {
+            let url: String = Input::new().with_prompt("Qdrant URL").default("http://localhost:6334".into()).interact_text()?;
+            let store = QdrantStore::connect(&url)?;
+            Ok(Box::new(store))
+        }
+        "SQLite" => {
+            let path: String = Input::new().with_prompt("Database file path").default("meap.sqlite".into()).interact_text()?;
+            let store = SqliteStore::connect(&path)?;
+            Ok(Box::new(store))
+        }
+        other => unreachable!("unhandled backend in BACKENDS list: {other}"),
+    }
+}
@@ -0,0 +1,214 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::{Parser, Subcommand};
+use futures::{SinkExt, StreamExt};
+use rig_core::protocol::{MessageType, ProtocolMessage};
+use rig_core::{server_acceptor, Connection, MeapProtocol, MeapServer, SecurityManager};
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+mod config;
+mod interactive;
+mod watch;
+
+use config::ConnectionConfig;
+
+#[derive(Parser)]
+#[command(name = "meap", about = "MEAP agent protocol CLI")]
+struct Cli {
+    /// Path to a `meap.toml` providing connection defaults. Overridden by
+    /// the `MEAP_SERVER_URL`/`MEAP_SECURITY_KEY` env vars, which are in
+    /// turn overridden by the matching CLI flags.
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate a fresh agent identity (Ed25519 keypair). With --tls, also
+    /// generate a symmetric security key for encrypting the agent's
+    /// traffic; pass it to `connect`/`send` via --security-key.
+    Create {
+        #[arg(long)]
+        tls: bool,
+    },
+    /// Connect to a MEAP server and hold the connection open. `addr` may be
+    /// omitted if a `server_url` is available from `--config`/env.
+    Connect {
+        addr: Option<String>,
+        /// Hex-encoded security key from `create --tls`, to connect via
+        /// the encrypted path instead of sending plaintext frames.
+        #[arg(long)]
+        security_key: Option<String>,
+    },
+    /// Connect to a MEAP server and send one message. `addr` may be
+    /// omitted if a `server_url` is available from `--config`/env.
+    Send {
+        addr: Option<String>,
+        message: String,
+        #[arg(long)]
+        security_key: Option<String>,
+    },
+    /// Ask a MEAP server to list its connected agents.
+    List { addr: String },
+    /// Ask a MEAP server for its status.
+    Status { addr: String },
+    /// Interactively choose a storage backend and connect to it.
+    SelectProtocol,
+    /// Tail an agent's live message feed without the TUI.
+    Watch {
+        agent_id: String,
+        /// Only print messages whose payload has this `message_type`.
+        #[arg(long)]
+        message_type: Option<String>,
+        /// NATS URL to subscribe through.
+        #[arg(long, default_value = "nats://localhost:4222")]
+        broker_url: String,
+    },
+    /// Start a standalone MEAP WebSocket server.
+    Serve {
+        /// Address to listen on, e.g. 127.0.0.1:9000.
+        addr: SocketAddr,
+        /// TLS certificate (PEM). Requires --key.
+        #[arg(long, requires = "key")]
+        cert: Option<PathBuf>,
+        /// TLS private key (PEM). Requires --cert.
+        #[arg(long, requires = "cert")]
+        key: Option<PathBuf>,
+    },
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    tracing_subscriber::fmt::init();
+
+    let cli = Cli::parse();
+    let config = ConnectionConfig::load_or_default(cli.config.as_deref())?;
+
+    match cli.command {
+        Command::Create { tls } => create(tls),
+        Command::Connect { addr, security_key } => {
+            let addr = require_addr(config.resolve_server_url(addr))?;
+            connect(&addr, config.resolve_security_key(security_key)).await
+        }
+        Command::Send { addr, message, security_key } => {
+            let addr = require_addr(config.resolve_server_url(addr))?;
+            send(&addr, &message, config.resolve_security_key(security_key)).await
+        }
+        Command::List { addr } => query(&addr, "list").await,
+        Command::Status { addr } => query(&addr, "status").await,
+        Command::SelectProtocol => select_protocol().await,
+        Command::Watch { agent_id, message_type, broker_url } => watch::watch(&broker_url, &agent_id, message_type).await,
+        Command::Serve { addr, cert, key } => serve(addr, cert, key).await,
+    }
+}
+
+/// `Connect`/`Send`'s `addr` is optional on the CLI because it can come
+/// from `--config`/env instead; this turns "nothing provided it" into the
+/// same kind of error clap would raise for a missing required argument.
+fn require_addr(addr: Option<String>) -> Result<String, Box<dyn std::error::Error>> {
+    addr.ok_or_else(|| "no address given: pass it directly, or set server_url in --config/MEAP_SERVER_URL".into())
+}
+
+fn create(tls: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let protocol = MeapProtocol::generate()?;
+    println!("public key: {}", hex::encode(&protocol.public_key()));
+    if tls {
+        let key = SecurityManager::generate_key();
+        println!("security key: {}", hex::encode(&key));
+    }
+    Ok(())
+}
+
+/// Build the `rig_core::Connection` handle for `addr`'s WebSocket sink,
+/// applying `security_key` (if given) via [`rig_core::ConnectionPool::add_secure_connection`]'s
+/// same `with_security` path so CLI traffic and server traffic take the
+/// identical encrypted route.
+async fn dial(addr: &str, security_key: Option<String>) -> Result<Connection, Box<dyn std::error::Error>> {
+    let (ws, _response) = tokio_tungstenite::connect_async(format!("ws://{addr}")).await?;
+    let (sink, _stream) = ws.split();
+    let connection = Connection::spawn(sink);
+
+    Ok(match security_key {
+        Some(hex_key) => connection.with_security(SecurityManager::new(hex::decode(&hex_key)?)),
+        None => connection,
+    })
+}
+
+async fn connect(addr: &str, security_key: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+    let _connection = dial(addr, security_key).await?;
+    println!("connected to {addr}, press Ctrl-C to disconnect");
+    tokio::signal::ctrl_c().await?;
+    Ok(())
+}
+
+async fn send(addr: &str, message: &str, security_key: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+    let connection = dial(addr, security_key).await?;
+    let envelope = ProtocolMessage::new(MessageType::Data, serde_json::json!({ "message": message }));
+    connection.send(serde_json::to_string(&envelope)?).await?;
+    // Give the connection's background write task a moment to flush
+    // before the process exits out from under it.
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    println!("sent: {message}");
+    Ok(())
+}
+
+/// Best-effort request/reply used by `List`/`Status`: send a `Data`
+/// envelope carrying the action name, then wait briefly for any reply
+/// frame. The server doesn't guarantee a response to unrecognized
+/// actions, so a timeout is reported rather than treated as an error.
+async fn query(addr: &str, action: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let (mut ws, _response) = tokio_tungstenite::connect_async(format!("ws://{addr}")).await?;
+    let envelope = ProtocolMessage::new(MessageType::Data, serde_json::json!({ "action": action }));
+    ws.send(WsMessage::Text(serde_json::to_string(&envelope)?)).await?;
+
+    match tokio::time::timeout(Duration::from_secs(3), ws.next()).await {
+        Ok(Some(Ok(WsMessage::Text(reply)))) => println!("{reply}"),
+        Ok(Some(Ok(_))) | Ok(None) => println!("no response from server"),
+        Ok(Some(Err(err))) => println!("connection error: {err}"),
+        Err(_) => println!("timed out waiting for a response"),
+    }
+    Ok(())
+}
+
+async fn select_protocol() -> Result<(), Box<dyn std::error::Error>> {
+    match interactive::interactive_protocol_selection().await {
+        Ok(_protocol) => println!("connected"),
+        Err(err) => println!("failed to connect: {err}"),
+    }
+    Ok(())
+}
+
+async fn serve(addr: SocketAddr, cert: Option<PathBuf>, key: Option<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    let tls = match (cert, key) {
+        (Some(cert), Some(key)) => Some(server_acceptor(cert, key)?),
+        _ => None,
+    };
+
+    let server = MeapServer::new();
+    let bound = server.start(addr, tls).await?;
+    println!("listening on {bound}");
+    tokio::signal::ctrl_c().await?;
+    Ok(())
+}
+
+mod hex {
+    pub fn encode(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{b:02x}")).collect()
+    }
+
+    pub fn decode(hex: &str) -> Result<[u8; 32], String> {
+        if hex.len() != 64 {
+            return Err(format!("expected a 64-character hex key, got {} characters", hex.len()));
+        }
+        let mut key = [0u8; 32];
+        for (i, byte) in key.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).map_err(|e| e.to_string())?;
+        }
+        Ok(key)
+    }
+}
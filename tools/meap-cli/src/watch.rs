@@ -0,0 +1,111 @@
+use rig_broker::{Broker, Message};
+use tokio::sync::mpsc;
+
+/// Queue group used for watch subscriptions. Every `watch` invocation gets
+/// its own group (rather than sharing one) so that multiple people can tail
+/// the same agent at once without NATS load-balancing the messages across
+/// them.
+fn watch_queue(agent_id: &str) -> String {
+    format!("watch.{agent_id}")
+}
+
+fn topic_for(agent_id: &str) -> String {
+    format!("agent.{agent_id}")
+}
+
+/// `true` if `message` should be printed given the optional `--message-type`
+/// filter. A message with no `message_type` field in its payload never
+/// matches a filter, but always matches when no filter was given.
+fn matches_filter(message: &Message, message_type: Option<&str>) -> bool {
+    match message_type {
+        None => true,
+        Some(wanted) => message.payload.get("message_type").and_then(|v| v.as_str()) == Some(wanted),
+    }
+}
+
+/// Render `message` as pretty-printed JSON with a colorized from→to header.
+/// Missing `from`/`to` fields render as `?` rather than failing, since the
+/// payload shape isn't enforced by the broker.
+fn render(message: &Message) -> String {
+    let from = message.payload.get("from").and_then(|v| v.as_str()).unwrap_or("?");
+    let to = message.payload.get("to").and_then(|v| v.as_str()).unwrap_or("?");
+    let body = serde_json::to_string_pretty(&message.payload).unwrap_or_else(|_| message.payload.to_string());
+    format!("\x1b[36m{from}\x1b[0m -> \x1b[35m{to}\x1b[0m\n{body}")
+}
+
+/// Subscribe to `agent_id`'s message feed and print each message that
+/// passes `message_type` as colorized JSON, until cancelled on Ctrl-C.
+///
+/// `Broker::subscribe`'s handler is a plain synchronous `Fn`, so it's used
+/// only to forward decoded messages across an unbounded channel into this
+/// async loop, which does the filtering, rendering, and Ctrl-C handling.
+pub async fn watch(broker_url: &str, agent_id: &str, message_type: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+    let broker = Broker::connect(broker_url).await?;
+    let (tx, mut rx) = mpsc::unbounded_channel::<Message>();
+
+    broker
+        .subscribe(&topic_for(agent_id), &watch_queue(agent_id), move |message| {
+            let _ = tx.send(message);
+        })
+        .await?;
+
+    println!("watching {agent_id}, press Ctrl-C to stop");
+    loop {
+        tokio::select! {
+            message = rx.recv() => match message {
+                Some(message) => {
+                    if matches_filter(&message, message_type.as_deref()) {
+                        println!("{}", render(&message));
+                    }
+                }
+                None => break,
+            },
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+
+    broker.unsubscribe(&topic_for(agent_id), &watch_queue(agent_id)).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn render_includes_from_to_and_full_payload() {
+        let message = Message::new("agent.a1", json!({"from": "a1", "to": "a2", "message_type": "ping"}));
+        let rendered = render(&message);
+        assert!(rendered.contains("a1"));
+        assert!(rendered.contains("a2"));
+        assert!(rendered.contains("\"message_type\": \"ping\""));
+    }
+
+    #[test]
+    fn matches_filter_rejects_a_different_message_type() {
+        let message = Message::new("agent.a1", json!({"message_type": "ping"}));
+        assert!(matches_filter(&message, Some("ping")));
+        assert!(!matches_filter(&message, Some("pong")));
+        assert!(matches_filter(&message, None));
+    }
+
+    #[tokio::test]
+    async fn a_couple_of_messages_piped_through_the_channel_render_in_order() {
+        let (tx, mut rx) = mpsc::unbounded_channel::<Message>();
+        tx.send(Message::new("agent.a1", json!({"from": "a1", "to": "a2", "message_type": "ping"}))).unwrap();
+        tx.send(Message::new("agent.a1", json!({"from": "a2", "to": "a1", "message_type": "pong"}))).unwrap();
+
+        let mut rendered = Vec::new();
+        for _ in 0..2 {
+            let message = rx.recv().await.unwrap();
+            if matches_filter(&message, Some("ping")) {
+                rendered.push(render(&message));
+            }
+        }
+
+        assert_eq!(rendered.len(), 1);
+        assert!(rendered[0].contains("a1"));
+        assert!(rendered[0].contains("a2"));
+    }
+}
@@ -0,0 +1,195 @@
+use futures::{SinkExt, StreamExt};
+use rig_core::MeapServer;
+use tokio_tungstenite::tungstenite::Message as WsMessage;
+
+/// Exercises the same `MeapServer::start` path the `Serve` subcommand
+/// drives, rather than spawning the `meap` binary as a subprocess.
+#[tokio::test]
+async fn a_client_can_connect_to_a_server_started_on_an_ephemeral_port() {
+    let server = MeapServer::new();
+    let addr = server.start("127.0.0.1:0".parse().unwrap(), None).await.unwrap();
+
+    let (_ws, response) = tokio_tungstenite::connect_async(format!("ws://{addr}")).await.unwrap();
+    assert_eq!(response.status(), 101);
+
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert_eq!(server.pool().len().await, 1);
+}
+
+#[tokio::test]
+async fn a_connected_client_receives_a_close_frame_on_shutdown() {
+    let server = MeapServer::new();
+    let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
+    let addr = server
+        .start_with_shutdown("127.0.0.1:0".parse().unwrap(), None, async {
+            let _ = shutdown_rx.await;
+        })
+        .await
+        .unwrap();
+
+    let (mut ws, _response) = tokio_tungstenite::connect_async(format!("ws://{addr}")).await.unwrap();
+    ws.next().await.unwrap().unwrap(); // drain the connect handshake
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert_eq!(server.pool().len().await, 1);
+
+    shutdown_tx.send(()).unwrap();
+
+    let frame = tokio::time::timeout(std::time::Duration::from_secs(1), ws.next())
+        .await
+        .expect("should receive a frame before timing out")
+        .expect("stream should not end without a frame")
+        .unwrap();
+    assert!(matches!(frame, WsMessage::Close(_)));
+}
+
+#[tokio::test]
+async fn an_agent_that_registers_with_capabilities_is_recorded_in_list_agents() {
+    use rig_core::{MessageType, ProtocolMessage};
+
+    let server = MeapServer::new();
+    let addr = server.start("127.0.0.1:0".parse().unwrap(), None).await.unwrap();
+
+    let (mut ws, _response) = tokio_tungstenite::connect_async(format!("ws://{addr}")).await.unwrap();
+    let registration = ProtocolMessage::new(
+        MessageType::Connect,
+        serde_json::json!({ "capabilities": ["chat", "search"] }),
+    );
+    ws.send(WsMessage::Text(serde_json::to_string(&registration).unwrap()))
+        .await
+        .unwrap();
+
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    let agents = server.pool().list_agents().await;
+    assert_eq!(agents.len(), 1);
+    assert_eq!(agents[0].capabilities, vec!["chat", "search"]);
+    assert_eq!(agents[0].status, "connected");
+}
+
+#[tokio::test]
+async fn sending_to_an_unconnected_target_gets_the_sender_an_error_reply() {
+    use rig_core::{MessageType, ProtocolMessage};
+
+    let server = MeapServer::new();
+    let addr = server.start("127.0.0.1:0".parse().unwrap(), None).await.unwrap();
+
+    let (mut ws, _response) = tokio_tungstenite::connect_async(format!("ws://{addr}")).await.unwrap();
+    ws.next().await.unwrap().unwrap(); // drain the connect handshake
+    // Registration frame, with no capabilities, so the router has a
+    // sender connection to reply to.
+    let registration = ProtocolMessage::new(MessageType::Connect, serde_json::json!({}));
+    ws.send(WsMessage::Text(serde_json::to_string(&registration).unwrap()))
+        .await
+        .unwrap();
+
+    let target_id = uuid::Uuid::new_v4();
+    let data = ProtocolMessage::new(MessageType::Data, serde_json::json!({ "target": target_id }));
+    ws.send(WsMessage::Text(serde_json::to_string(&data).unwrap()))
+        .await
+        .unwrap();
+
+    let frame = tokio::time::timeout(std::time::Duration::from_secs(1), ws.next())
+        .await
+        .expect("should receive a reply before timing out")
+        .expect("stream should not end without a frame")
+        .unwrap();
+    let WsMessage::Text(text) = frame else {
+        panic!("expected a text frame");
+    };
+    let reply: ProtocolMessage = serde_json::from_str(&text).unwrap();
+    assert_eq!(reply.message_type, MessageType::Error);
+
+    // The queued-then-delivered-on-reconnect path is covered at the
+    // router unit-test level (`rig_core::router`), since this transport
+    // mints a fresh connection id on every reconnect rather than letting
+    // an agent keep a stable identity across connections.
+}
+
+#[tokio::test]
+async fn a_second_client_is_notified_when_the_first_disconnects() {
+    use rig_core::{MessageType, ProtocolMessage};
+
+    let server = MeapServer::new();
+    let addr = server.start("127.0.0.1:0".parse().unwrap(), None).await.unwrap();
+
+    let (first, _response) = tokio_tungstenite::connect_async(format!("ws://{addr}")).await.unwrap();
+    let (mut second, _response) = tokio_tungstenite::connect_async(format!("ws://{addr}")).await.unwrap();
+    second.next().await.unwrap().unwrap(); // drain the connect handshake
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+    // Presence updates exclude the subject agent itself, so `second`
+    // shouldn't have received anything yet from either its own join or
+    // `first`'s (which happened before `second` was in the pool).
+    drop(first);
+
+    let frame = tokio::time::timeout(std::time::Duration::from_secs(1), second.next())
+        .await
+        .expect("should receive a presence update before timing out")
+        .expect("stream should not end without a frame")
+        .unwrap();
+    let WsMessage::Text(text) = frame else {
+        panic!("expected a text frame");
+    };
+    let presence: ProtocolMessage = serde_json::from_str(&text).unwrap();
+    assert_eq!(presence.message_type, MessageType::Status);
+    assert_eq!(presence.payload["status"], "disconnected");
+}
+
+#[tokio::test]
+async fn a_client_with_a_compatible_protocol_version_receives_the_handshake_and_stays_connected() {
+    use rig_core::{MessageType, ProtocolMessage, PROTOCOL_VERSION};
+
+    let server = MeapServer::new();
+    let addr = server.start("127.0.0.1:0".parse().unwrap(), None).await.unwrap();
+
+    let (mut ws, _response) = tokio_tungstenite::connect_async(format!("ws://{addr}")).await.unwrap();
+
+    let frame = tokio::time::timeout(std::time::Duration::from_secs(1), ws.next())
+        .await
+        .expect("should receive the handshake before timing out")
+        .expect("stream should not end without a frame")
+        .unwrap();
+    let WsMessage::Text(text) = frame else {
+        panic!("expected a text frame");
+    };
+    let handshake: ProtocolMessage = serde_json::from_str(&text).unwrap();
+    assert_eq!(handshake.message_type, MessageType::Connect);
+    assert_eq!(handshake.protocol_version, PROTOCOL_VERSION);
+
+    let registration = ProtocolMessage::new(MessageType::Connect, serde_json::json!({ "capabilities": [] }));
+    ws.send(WsMessage::Text(serde_json::to_string(&registration).unwrap())).await.unwrap();
+
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert_eq!(server.pool().len().await, 1);
+}
+
+#[tokio::test]
+async fn a_client_with_an_incompatible_protocol_version_is_closed() {
+    use rig_core::{MessageType, ProtocolMessage, PROTOCOL_VERSION};
+
+    let server = MeapServer::new();
+    let addr = server.start("127.0.0.1:0".parse().unwrap(), None).await.unwrap();
+
+    let (mut ws, _response) = tokio_tungstenite::connect_async(format!("ws://{addr}")).await.unwrap();
+
+    // Drain the server's own handshake before sending ours, so the next
+    // frame we read is unambiguously its reaction to our version.
+    ws.next().await.unwrap().unwrap();
+
+    let mut registration = ProtocolMessage::new(MessageType::Connect, serde_json::json!({ "capabilities": [] }));
+    registration.protocol_version = PROTOCOL_VERSION + 1;
+    ws.send(WsMessage::Text(serde_json::to_string(&registration).unwrap())).await.unwrap();
+
+    let frame = tokio::time::timeout(std::time::Duration::from_secs(1), ws.next())
+        .await
+        .expect("should receive a close frame before timing out")
+        .expect("stream should not end without a frame")
+        .unwrap();
+    assert!(matches!(frame, WsMessage::Close(_)));
+}
+
+#[tokio::test]
+async fn sqlite_backend_construction_succeeds_against_a_temp_file() {
+    let file = tempfile::NamedTempFile::new().unwrap();
+    let store = rig_sqlite::SqliteStore::connect(file.path()).unwrap();
+    let _protocol: Box<dyn rig_core::Protocol> = Box::new(store);
+}
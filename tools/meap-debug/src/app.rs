@@ -0,0 +1,54 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize};
+use std::sync::Arc;
+use std::time::Instant;
+
+use rig_core::ConnectionPool;
+use tokio::sync::RwLock;
+
+/// One entry in the connected-agent list, as rendered by the TUI.
+#[derive(Debug, Clone)]
+pub struct AgentInfo {
+    pub id: String,
+    pub status: String,
+    pub capabilities: Vec<String>,
+    pub last_heartbeat: Instant,
+}
+
+/// One entry in the scrolling message log.
+#[derive(Debug, Clone)]
+pub struct LogMessage {
+    pub agent_id: String,
+    pub body: String,
+}
+
+/// Shared state for the debug TUI, held behind an `Arc` so the render loop
+/// and the key-handling loop both see the same state through `&App` rather
+/// than `&mut App`. `messages`/`agents` are snapshotted with `.read().await`
+/// before each draw, never touched from inside the synchronous draw
+/// closure. Fields the key handlers toggle (`show_help`, `show_filter_menu`,
+/// `selected_index`) need interior mutability for the same reason — they're
+/// reached through `&App`, so a plain `bool`/`usize` field would never
+/// compile against the key-handling loop's shared reference.
+pub struct App {
+    pub connection_pool: ConnectionPool,
+    pub messages: Arc<RwLock<Vec<LogMessage>>>,
+    pub agents: Arc<RwLock<Vec<AgentInfo>>>,
+    pub show_help: AtomicBool,
+    pub show_filter_menu: AtomicBool,
+    pub show_detail: AtomicBool,
+    pub selected_index: AtomicUsize,
+}
+
+impl App {
+    pub fn new(connection_pool: ConnectionPool) -> Self {
+        Self {
+            connection_pool,
+            messages: Arc::new(RwLock::new(Vec::new())),
+            agents: Arc::new(RwLock::new(Vec::new())),
+            show_help: AtomicBool::new(false),
+            show_filter_menu: AtomicBool::new(false),
+            show_detail: AtomicBool::new(false),
+            selected_index: AtomicUsize::new(0),
+        }
+    }
+}
@@ -0,0 +1,248 @@
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crossterm::event::{self, Event, KeyCode};
+use ratatui::backend::Backend;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, Paragraph};
+use ratatui::{Frame, Terminal};
+
+use crate::app::{AgentInfo, App, LogMessage};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Drive the TUI: on every tick, snapshot the shared state with
+/// `.read().await` and hand owned vectors into the synchronous `draw`
+/// closure, then poll for a key event. Taking the locks before
+/// `terminal.draw` — rather than calling `blocking_read` from inside it —
+/// is what keeps this loop off the runtime's worker threads while a draw
+/// is in flight; `blocking_read` inside an async task can deadlock the
+/// runtime if no other thread is free to make progress on the lock.
+pub async fn ui_loop<B: Backend>(app: Arc<App>, terminal: &mut Terminal<B>) -> Result<(), Box<dyn std::error::Error>> {
+    loop {
+        let messages = app.messages.read().await.clone();
+        let agents = app.agents.read().await.clone();
+        let selected_index = app.selected_index.load(Ordering::SeqCst);
+        let show_detail = app.show_detail.load(Ordering::SeqCst);
+
+        terminal.draw(|frame| draw(frame, &agents, &messages, selected_index, show_detail))?;
+
+        if event::poll(POLL_INTERVAL)? {
+            if let Event::Key(key) = event::read()? {
+                if key.code == KeyCode::Char('q') {
+                    return Ok(());
+                }
+                let agent_count = app.agents.read().await.len();
+                handle_key(&app, key.code, agent_count);
+            }
+        }
+    }
+}
+
+/// Toggle/move `app`'s UI state for a key event. Takes `&App` (not
+/// `&mut App`) since the render loop holds the same `Arc<App>` concurrently
+/// — every field this touches is an atomic for exactly that reason.
+/// `agent_count` clamps Up/Down to the current agent list so selection
+/// can't wander past its bounds.
+fn handle_key(app: &App, code: KeyCode, agent_count: usize) {
+    match code {
+        KeyCode::Char('h') => {
+            app.show_help.fetch_xor(true, Ordering::SeqCst);
+        }
+        KeyCode::Char('f') => {
+            app.show_filter_menu.fetch_xor(true, Ordering::SeqCst);
+        }
+        KeyCode::Enter => {
+            if agent_count > 0 {
+                app.show_detail.fetch_xor(true, Ordering::SeqCst);
+            }
+        }
+        KeyCode::Up => {
+            let current = app.selected_index.load(Ordering::SeqCst);
+            app.selected_index.store(current.saturating_sub(1), Ordering::SeqCst);
+        }
+        KeyCode::Down => {
+            if agent_count > 0 {
+                let current = app.selected_index.load(Ordering::SeqCst);
+                app.selected_index.store((current + 1).min(agent_count - 1), Ordering::SeqCst);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Messages belonging to `agent_id`, in original order, for the detail
+/// popup's history pane.
+fn messages_for_agent<'a>(messages: &'a [LogMessage], agent_id: &str) -> Vec<&'a LogMessage> {
+    messages.iter().filter(|message| message.agent_id == agent_id).collect()
+}
+
+fn draw(frame: &mut Frame<'_>, agents: &[AgentInfo], messages: &[LogMessage], selected_index: usize, show_detail: bool) {
+    let chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
+        .split(frame.size());
+
+    let agent_items: Vec<ListItem> = agents
+        .iter()
+        .enumerate()
+        .map(|(index, agent)| {
+            let label = if index == selected_index { format!("> {}", agent.id) } else { agent.id.clone() };
+            ListItem::new(label)
+        })
+        .collect();
+    frame.render_widget(
+        List::new(agent_items).block(Block::default().title("Agents").borders(Borders::ALL)),
+        chunks[0],
+    );
+
+    let message_items: Vec<ListItem> = messages
+        .iter()
+        .map(|message| ListItem::new(format!("{}: {}", message.agent_id, message.body)))
+        .collect();
+    frame.render_widget(
+        List::new(message_items).block(Block::default().title("Messages").borders(Borders::ALL)),
+        chunks[1],
+    );
+
+    if show_detail {
+        if let Some(agent) = agents.get(selected_index) {
+            draw_detail_popup(frame, agent, messages);
+        }
+    }
+}
+
+/// Render a centered overlay showing `agent`'s status, capabilities, last
+/// heartbeat age, and message history filtered from `messages`.
+fn draw_detail_popup(frame: &mut Frame<'_>, agent: &AgentInfo, messages: &[LogMessage]) {
+    let area = centered_rect(60, 60, frame.size());
+    let history = messages_for_agent(messages, &agent.id);
+
+    let mut lines = vec![
+        format!("status: {}", agent.status),
+        format!("capabilities: {}", agent.capabilities.join(", ")),
+        format!("last heartbeat: {:.1}s ago", agent.last_heartbeat.elapsed().as_secs_f64()),
+        String::new(),
+        "recent messages:".to_string(),
+    ];
+    lines.extend(history.iter().map(|message| format!("  {}", message.body)));
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(lines.join("\n")).block(Block::default().title(agent.id.as_str()).borders(Borders::ALL)),
+        area,
+    );
+}
+
+/// A `percent_x` by `percent_y` rectangle centered within `area`.
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let vertical = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Percentage((100 - percent_y) / 2),
+            Constraint::Percentage(percent_y),
+            Constraint::Percentage((100 - percent_y) / 2),
+        ])
+        .split(area);
+
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage((100 - percent_x) / 2),
+            Constraint::Percentage(percent_x),
+            Constraint::Percentage((100 - percent_x) / 2),
+        ])
+        .split(vertical[1])[1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+
+    #[tokio::test]
+    async fn one_render_tick_against_a_populated_app_does_not_block() {
+        let app = Arc::new(App::new(rig_core::ConnectionPool::new()));
+        app.agents.write().await.push(AgentInfo {
+            id: "agent-1".into(),
+            status: "connected".into(),
+            capabilities: vec!["chat".into()],
+            last_heartbeat: std::time::Instant::now(),
+        });
+        app.messages.write().await.push(LogMessage {
+            agent_id: "agent-1".into(),
+            body: "hello".into(),
+        });
+
+        let backend = TestBackend::new(80, 24);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        let messages = app.messages.read().await.clone();
+        let agents = app.agents.read().await.clone();
+
+        tokio::time::timeout(Duration::from_secs(1), async {
+            terminal.draw(|frame| draw(frame, &agents, &messages, 0, false)).unwrap();
+        })
+        .await
+        .expect("render tick should not block");
+    }
+
+    #[tokio::test]
+    async fn an_h_keypress_toggles_show_help() {
+        let app = App::new(rig_core::ConnectionPool::new());
+        assert!(!app.show_help.load(Ordering::SeqCst));
+
+        handle_key(&app, KeyCode::Char('h'), 0);
+        assert!(app.show_help.load(Ordering::SeqCst));
+
+        handle_key(&app, KeyCode::Char('h'), 0);
+        assert!(!app.show_help.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn selection_index_is_clamped_to_the_agent_list_bounds() {
+        let app = App::new(rig_core::ConnectionPool::new());
+
+        // Up never goes below zero, even with no agents.
+        handle_key(&app, KeyCode::Up, 0);
+        assert_eq!(app.selected_index.load(Ordering::SeqCst), 0);
+
+        // Down stops at the last valid index instead of running past it.
+        handle_key(&app, KeyCode::Down, 2);
+        assert_eq!(app.selected_index.load(Ordering::SeqCst), 1);
+        handle_key(&app, KeyCode::Down, 2);
+        assert_eq!(app.selected_index.load(Ordering::SeqCst), 1);
+
+        handle_key(&app, KeyCode::Up, 2);
+        assert_eq!(app.selected_index.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn messages_for_agent_filters_out_other_agents() {
+        let messages = vec![
+            LogMessage { agent_id: "agent-1".into(), body: "hello".into() },
+            LogMessage { agent_id: "agent-2".into(), body: "ignored".into() },
+            LogMessage { agent_id: "agent-1".into(), body: "world".into() },
+        ];
+
+        let filtered = messages_for_agent(&messages, "agent-1");
+        assert_eq!(filtered.len(), 2);
+        assert_eq!(filtered[0].body, "hello");
+        assert_eq!(filtered[1].body, "world");
+    }
+
+    #[tokio::test]
+    async fn an_enter_keypress_with_agents_present_shows_the_detail_popup() {
+        let app = App::new(rig_core::ConnectionPool::new());
+        app.agents.write().await.push(AgentInfo {
+            id: "agent-1".into(),
+            status: "connected".into(),
+            capabilities: vec!["chat".into()],
+            last_heartbeat: std::time::Instant::now(),
+        });
+
+        handle_key(&app, KeyCode::Enter, 1);
+        assert!(app.show_detail.load(Ordering::SeqCst));
+    }
+}